@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS checklist_items;
+DROP TABLE IF EXISTS travaux;
+DROP TABLE IF EXISTS utilisateurs;
+DROP TABLE IF EXISTS projets;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS modeles_checklist;
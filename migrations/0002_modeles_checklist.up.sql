@@ -0,0 +1,12 @@
+CREATE TABLE modeles_checklist (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    nom VARCHAR(255) NOT NULL,
+    type_travail VARCHAR(20) NOT NULL,
+    application VARCHAR(20),
+    environnement VARCHAR(20),
+    etapes JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX modeles_checklist_type_travail_idx ON modeles_checklist (type_travail);
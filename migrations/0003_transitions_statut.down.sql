@@ -0,0 +1 @@
+DROP TABLE IF EXISTS transitions_statut;
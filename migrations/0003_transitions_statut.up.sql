@@ -0,0 +1,11 @@
+CREATE TABLE transitions_statut (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    entite VARCHAR(20) NOT NULL,
+    entite_id UUID NOT NULL,
+    de VARCHAR(20) NOT NULL,
+    vers VARCHAR(20) NOT NULL,
+    raison TEXT,
+    date TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX transitions_statut_entite_idx ON transitions_statut (entite, entite_id, date);
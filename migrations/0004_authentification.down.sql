@@ -0,0 +1 @@
+ALTER TABLE utilisateurs DROP COLUMN mot_de_passe_hash;
@@ -0,0 +1 @@
+ALTER TABLE utilisateurs ADD COLUMN mot_de_passe_hash VARCHAR(255);
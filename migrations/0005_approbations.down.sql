@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS approbations;
+DROP TABLE IF EXISTS demandes_approbation;
@@ -0,0 +1,19 @@
+CREATE TABLE demandes_approbation (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    travail_id UUID NOT NULL REFERENCES travaux(id),
+    demandeur UUID NOT NULL,
+    date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    invalidee_le TIMESTAMPTZ
+);
+
+CREATE INDEX demandes_approbation_travail_idx ON demandes_approbation (travail_id, date);
+
+CREATE TABLE approbations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    demande_id UUID NOT NULL REFERENCES demandes_approbation(id),
+    approbateur UUID NOT NULL,
+    decision VARCHAR(20) NOT NULL,
+    commentaire TEXT,
+    date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (demande_id, approbateur)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS journal_audit;
+DROP FUNCTION IF EXISTS journal_audit_ajout_seul();
@@ -0,0 +1,23 @@
+CREATE TABLE journal_audit (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    acteur UUID,
+    date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    entite VARCHAR(20) NOT NULL,
+    entite_id UUID NOT NULL,
+    action VARCHAR(20) NOT NULL,
+    changements JSONB NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX journal_audit_entite_idx ON journal_audit (entite, entite_id, date);
+CREATE INDEX journal_audit_date_idx ON journal_audit (date);
+
+-- Journal en ajout seul : toute modification ou suppression est refusée
+CREATE FUNCTION journal_audit_ajout_seul() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'journal_audit est en ajout seul';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER journal_audit_ajout_seul
+    BEFORE UPDATE OR DELETE ON journal_audit
+    FOR EACH ROW EXECUTE FUNCTION journal_audit_ajout_seul();
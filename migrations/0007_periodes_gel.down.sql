@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS derogations_gel;
+DROP TABLE IF EXISTS periodes_gel;
@@ -0,0 +1,27 @@
+CREATE TABLE periodes_gel (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    date_debut TIMESTAMPTZ NOT NULL,
+    date_fin TIMESTAMPTZ NOT NULL,
+    -- Tableaux vides : toutes les applications / tous les environnements
+    applications VARCHAR(20)[] NOT NULL DEFAULT '{}',
+    environnements VARCHAR(20)[] NOT NULL DEFAULT '{}',
+    raison TEXT NOT NULL,
+    actif BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK (date_debut < date_fin)
+);
+
+CREATE INDEX periodes_gel_dates_idx ON periodes_gel (date_debut, date_fin);
+
+CREATE TABLE derogations_gel (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    periode_gel_id UUID NOT NULL REFERENCES periodes_gel(id),
+    travail_id UUID NOT NULL REFERENCES travaux(id),
+    auteur UUID NOT NULL,
+    justification TEXT NOT NULL,
+    date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (periode_gel_id, travail_id)
+);
+
+CREATE INDEX derogations_gel_travail_idx ON derogations_gel (travail_id);
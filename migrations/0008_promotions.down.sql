@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS travaux_promotion_de_idx;
+ALTER TABLE travaux DROP COLUMN IF EXISTS promotion_de;
@@ -0,0 +1,5 @@
+-- Chaîne de promotion Test → Formation → Production : un travail référence celui qu'il promeut,
+-- et un travail n'est promu qu'une fois
+ALTER TABLE travaux ADD COLUMN promotion_de UUID REFERENCES travaux(id);
+
+CREATE UNIQUE INDEX travaux_promotion_de_idx ON travaux (promotion_de);
@@ -0,0 +1,10 @@
+-- Dépendances fin → début : travail_id ne peut commencer qu'après la fin de depend_de
+CREATE TABLE dependances_travaux (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    travail_id UUID NOT NULL REFERENCES travaux(id),
+    depend_de UUID NOT NULL REFERENCES travaux(id),
+    UNIQUE (travail_id, depend_de),
+    CHECK (travail_id <> depend_de)
+);
+
+CREATE INDEX dependances_travaux_depend_de_idx ON dependances_travaux (depend_de);
@@ -0,0 +1,51 @@
+ALTER TABLE periodes_gel
+    ALTER COLUMN applications DROP DEFAULT,
+    ALTER COLUMN environnements DROP DEFAULT;
+ALTER TABLE periodes_gel
+    ALTER COLUMN applications TYPE VARCHAR(20)[] USING applications::text[],
+    ALTER COLUMN environnements TYPE VARCHAR(20)[] USING environnements::text[],
+    ALTER COLUMN applications SET DEFAULT '{}',
+    ALTER COLUMN environnements SET DEFAULT '{}';
+
+ALTER TABLE journal_audit
+    ALTER COLUMN entite TYPE VARCHAR(20) USING entite::text,
+    ALTER COLUMN action TYPE VARCHAR(20) USING action::text;
+
+ALTER TABLE approbations
+    ALTER COLUMN decision TYPE VARCHAR(20) USING decision::text;
+
+ALTER TABLE transitions_statut
+    ALTER COLUMN entite TYPE VARCHAR(20) USING entite::text;
+
+ALTER TABLE modeles_checklist
+    ALTER COLUMN type_travail TYPE VARCHAR(20) USING type_travail::text,
+    ALTER COLUMN application TYPE VARCHAR(20) USING application::text,
+    ALTER COLUMN environnement TYPE VARCHAR(20) USING environnement::text;
+
+ALTER TABLE checklist_items
+    ALTER COLUMN statut TYPE VARCHAR(20) USING statut::text;
+
+ALTER TABLE travaux
+    ALTER COLUMN type_travail TYPE VARCHAR(20) USING type_travail::text,
+    ALTER COLUMN application TYPE VARCHAR(20) USING application::text,
+    ALTER COLUMN environnement TYPE VARCHAR(20) USING environnement::text,
+    ALTER COLUMN statut TYPE VARCHAR(20) USING statut::text;
+
+ALTER TABLE utilisateurs
+    ALTER COLUMN role TYPE VARCHAR(20) USING role::text;
+
+ALTER TABLE projets
+    ALTER COLUMN statut TYPE VARCHAR(20) USING statut::text,
+    ALTER COLUMN priorite TYPE VARCHAR(20) USING priorite::text;
+
+DROP TYPE IF EXISTS decision_approbation;
+DROP TYPE IF EXISTS action_audit;
+DROP TYPE IF EXISTS type_entite;
+DROP TYPE IF EXISTS role_utilisateur;
+DROP TYPE IF EXISTS priorite;
+DROP TYPE IF EXISTS environnement;
+DROP TYPE IF EXISTS application;
+DROP TYPE IF EXISTS type_travail;
+DROP TYPE IF EXISTS statut_checklist;
+DROP TYPE IF EXISTS statut_travail;
+DROP TYPE IF EXISTS statut_projet;
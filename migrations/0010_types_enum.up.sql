@@ -0,0 +1,55 @@
+-- Énumérations stockées en types ENUM natifs plutôt qu'en VARCHAR libres. Les valeurs reprennent
+-- `as_str()` des énumérations de models.rs, dans l'ordre de déclaration (un test le vérifie) :
+-- toute nouvelle variante demande un `ALTER TYPE ... ADD VALUE` dans une migration.
+
+CREATE TYPE statut_projet AS ENUM ('Planifie', 'EnCours', 'Termine', 'Suspendu');
+CREATE TYPE statut_travail AS ENUM ('Planifie', 'EnCours', 'Termine', 'Suspendu', 'Annule');
+CREATE TYPE statut_checklist AS ENUM ('NonDemarre', 'EnCours', 'Termine', 'Bloque');
+CREATE TYPE type_travail AS ENUM ('CloneBd', 'Migration', 'Rehausement', 'MajApplication', 'Autre');
+CREATE TYPE application AS ENUM ('EspressoGfr', 'EspressoGrm', 'EspressoGrh', 'EspressoGpa', 'Autre');
+CREATE TYPE environnement AS ENUM ('Test', 'Formation', 'Production');
+CREATE TYPE priorite AS ENUM ('Faible', 'Moyenne', 'Haute', 'Critique');
+CREATE TYPE role_utilisateur AS ENUM ('Conseiller', 'Manager', 'Specialiste', 'Admin');
+CREATE TYPE type_entite AS ENUM ('Projet', 'Travail', 'Utilisateur', 'ChecklistItem', 'PeriodeGel', 'Dependance');
+CREATE TYPE action_audit AS ENUM ('Creation', 'Modification', 'Suppression');
+CREATE TYPE decision_approbation AS ENUM ('Approuve', 'Rejete');
+
+ALTER TABLE projets
+    ALTER COLUMN statut TYPE statut_projet USING statut::statut_projet,
+    ALTER COLUMN priorite TYPE priorite USING priorite::priorite;
+
+ALTER TABLE utilisateurs
+    ALTER COLUMN role TYPE role_utilisateur USING role::role_utilisateur;
+
+ALTER TABLE travaux
+    ALTER COLUMN type_travail TYPE type_travail USING type_travail::type_travail,
+    ALTER COLUMN application TYPE application USING application::application,
+    ALTER COLUMN environnement TYPE environnement USING environnement::environnement,
+    ALTER COLUMN statut TYPE statut_travail USING statut::statut_travail;
+
+ALTER TABLE checklist_items
+    ALTER COLUMN statut TYPE statut_checklist USING statut::statut_checklist;
+
+ALTER TABLE modeles_checklist
+    ALTER COLUMN type_travail TYPE type_travail USING type_travail::type_travail,
+    ALTER COLUMN application TYPE application USING application::application,
+    ALTER COLUMN environnement TYPE environnement USING environnement::environnement;
+
+ALTER TABLE transitions_statut
+    ALTER COLUMN entite TYPE type_entite USING entite::type_entite;
+
+ALTER TABLE approbations
+    ALTER COLUMN decision TYPE decision_approbation USING decision::decision_approbation;
+
+ALTER TABLE journal_audit
+    ALTER COLUMN entite TYPE type_entite USING entite::type_entite,
+    ALTER COLUMN action TYPE action_audit USING action::action_audit;
+
+ALTER TABLE periodes_gel
+    ALTER COLUMN applications DROP DEFAULT,
+    ALTER COLUMN environnements DROP DEFAULT;
+ALTER TABLE periodes_gel
+    ALTER COLUMN applications TYPE application[] USING applications::text[]::application[],
+    ALTER COLUMN environnements TYPE environnement[] USING environnements::text[]::environnement[],
+    ALTER COLUMN applications SET DEFAULT '{}',
+    ALTER COLUMN environnements SET DEFAULT '{}';
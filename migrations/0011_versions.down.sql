@@ -0,0 +1,3 @@
+ALTER TABLE utilisateurs DROP COLUMN IF EXISTS version;
+ALTER TABLE travaux DROP COLUMN IF EXISTS version;
+ALTER TABLE projets DROP COLUMN IF EXISTS version;
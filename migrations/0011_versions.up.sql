@@ -0,0 +1,5 @@
+-- Numéro de version des entités modifiables : incrémenté à chaque mise à jour, exposé en ETag et
+-- comparé à If-Match (verrouillage optimiste)
+ALTER TABLE projets ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE travaux ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE utilisateurs ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
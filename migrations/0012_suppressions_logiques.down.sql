@@ -0,0 +1,32 @@
+-- Les éléments encore supprimés logiquement sont purgés avec leurs dépendances ; les valeurs
+-- Restauration et Purge restent dans le type action_audit (PostgreSQL ne retire pas de valeur d'ENUM)
+CREATE TEMPORARY TABLE travaux_purges AS
+    SELECT id FROM travaux
+    WHERE deleted_at IS NOT NULL
+       OR projet_id IN (SELECT id FROM projets WHERE deleted_at IS NOT NULL)
+       OR responsable IN (SELECT id FROM utilisateurs WHERE deleted_at IS NOT NULL);
+UPDATE travaux SET promotion_de = NULL WHERE promotion_de IN (SELECT id FROM travaux_purges);
+DELETE FROM dependances_travaux
+    WHERE travail_id IN (SELECT id FROM travaux_purges) OR depend_de IN (SELECT id FROM travaux_purges);
+DELETE FROM derogations_gel WHERE travail_id IN (SELECT id FROM travaux_purges);
+DELETE FROM approbations
+    WHERE demande_id IN (SELECT id FROM demandes_approbation WHERE travail_id IN (SELECT id FROM travaux_purges));
+DELETE FROM demandes_approbation WHERE travail_id IN (SELECT id FROM travaux_purges);
+DELETE FROM checklist_items
+    WHERE travail_id IN (SELECT id FROM travaux_purges)
+       OR responsable IN (SELECT id FROM utilisateurs WHERE deleted_at IS NOT NULL);
+DELETE FROM travaux WHERE id IN (SELECT id FROM travaux_purges);
+DELETE FROM projets WHERE deleted_at IS NOT NULL;
+DELETE FROM utilisateurs WHERE deleted_at IS NOT NULL;
+DROP TABLE travaux_purges;
+
+DROP INDEX IF EXISTS travaux_promotion_de_idx;
+CREATE UNIQUE INDEX travaux_promotion_de_idx ON travaux (promotion_de);
+DROP INDEX IF EXISTS utilisateurs_email_key;
+ALTER TABLE utilisateurs ADD CONSTRAINT utilisateurs_email_key UNIQUE (email);
+DROP INDEX IF EXISTS projets_code_key;
+ALTER TABLE projets ADD CONSTRAINT projets_code_key UNIQUE (code);
+
+ALTER TABLE utilisateurs DROP COLUMN IF EXISTS deleted_at, DROP COLUMN IF EXISTS deleted_by;
+ALTER TABLE travaux DROP COLUMN IF EXISTS deleted_at, DROP COLUMN IF EXISTS deleted_by;
+ALTER TABLE projets DROP COLUMN IF EXISTS deleted_at, DROP COLUMN IF EXISTS deleted_by;
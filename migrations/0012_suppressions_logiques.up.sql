@@ -0,0 +1,17 @@
+-- Suppression logique : les projets, travaux et utilisateurs supprimés restent en base (date et
+-- auteur de la suppression) jusqu'à leur purge, une fois la période de rétention écoulée
+ALTER TABLE projets ADD COLUMN deleted_at TIMESTAMPTZ, ADD COLUMN deleted_by UUID;
+ALTER TABLE travaux ADD COLUMN deleted_at TIMESTAMPTZ, ADD COLUMN deleted_by UUID;
+ALTER TABLE utilisateurs ADD COLUMN deleted_at TIMESTAMPTZ, ADD COLUMN deleted_by UUID;
+
+-- L'unicité ne porte que sur les lignes non supprimées : un code, un email ou un travail promu
+-- libéré par une suppression peut être réutilisé (la restauration est alors refusée)
+ALTER TABLE projets DROP CONSTRAINT projets_code_key;
+CREATE UNIQUE INDEX projets_code_key ON projets (code) WHERE deleted_at IS NULL;
+ALTER TABLE utilisateurs DROP CONSTRAINT utilisateurs_email_key;
+CREATE UNIQUE INDEX utilisateurs_email_key ON utilisateurs (email) WHERE deleted_at IS NULL;
+DROP INDEX travaux_promotion_de_idx;
+CREATE UNIQUE INDEX travaux_promotion_de_idx ON travaux (promotion_de) WHERE deleted_at IS NULL;
+
+ALTER TYPE action_audit ADD VALUE 'Restauration';
+ALTER TYPE action_audit ADD VALUE 'Purge';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS utilisateurs_jeton_calendrier_idx;
+ALTER TABLE utilisateurs DROP COLUMN jeton_calendrier_empreinte;
@@ -0,0 +1,4 @@
+-- Empreinte SHA-256 du jeton d'abonnement au calendrier (le jeton lui-même n'est jamais stocké)
+ALTER TABLE utilisateurs ADD COLUMN jeton_calendrier_empreinte VARCHAR(64);
+CREATE UNIQUE INDEX utilisateurs_jeton_calendrier_idx ON utilisateurs (jeton_calendrier_empreinte)
+    WHERE jeton_calendrier_empreinte IS NOT NULL;
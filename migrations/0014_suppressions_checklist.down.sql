@@ -0,0 +1,2 @@
+DELETE FROM checklist_items WHERE deleted_at IS NOT NULL;
+ALTER TABLE checklist_items DROP COLUMN IF EXISTS deleted_at, DROP COLUMN IF EXISTS deleted_by;
@@ -0,0 +1,3 @@
+-- Les items de checklist supprimés en cascade avec leur responsable restent restaurables
+-- jusqu'à leur purge, comme l'utilisateur lui-même
+ALTER TABLE checklist_items ADD COLUMN deleted_at TIMESTAMPTZ, ADD COLUMN deleted_by UUID;
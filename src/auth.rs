@@ -0,0 +1,201 @@
+// Authentification : mots de passe hachés (PBKDF2-HMAC-SHA256) et jetons signés (HMAC-SHA256)
+// Jeton : base64url("<utilisateur_id>.<expiration unix>") + "." + base64url(signature)
+// Abonnement au calendrier : jeton aléatoire révocable, dont seule l'empreinte SHA-256 est stockée
+
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::pin::Pin;
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use anyhow::anyhow;
+use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{digest, hmac, pbkdf2};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::models::Utilisateur;
+use crate::repositories::UtilisateurRepository;
+
+const ALGORITHME: pbkdf2::Algorithm = pbkdf2::PBKDF2_HMAC_SHA256;
+const ITERATIONS: u32 = 100_000;
+const PREFIXE: &str = "pbkdf2-sha256";
+
+// Format stocké : pbkdf2-sha256$<itérations>$<sel>$<hash> ; les itérations sont conservées
+// pour pouvoir être augmentées sans invalider les mots de passe existants
+pub fn hacher_mot_de_passe(mot_de_passe: &str) -> anyhow::Result<String> {
+    let mut sel = [0u8; 16];
+    SystemRandom::new().fill(&mut sel).map_err(|_| anyhow!("Générateur aléatoire indisponible"))?;
+    let mut hash = [0u8; 32];
+    let iterations = NonZeroU32::new(ITERATIONS).ok_or_else(|| anyhow!("Nombre d'itérations nul"))?;
+    pbkdf2::derive(ALGORITHME, iterations, &sel, mot_de_passe.as_bytes(), &mut hash);
+    Ok(format!("{}${}${}${}", PREFIXE, ITERATIONS, STANDARD_NO_PAD.encode(sel), STANDARD_NO_PAD.encode(hash)))
+}
+
+pub fn verifier_mot_de_passe(mot_de_passe: &str, stocke: &str) -> bool {
+    let mut parties = stocke.split('$');
+    let (Some(PREFIXE), Some(iterations), Some(sel), Some(hash), None) =
+        (parties.next(), parties.next(), parties.next(), parties.next(), parties.next())
+    else {
+        return false;
+    };
+    let (Some(iterations), Ok(sel), Ok(hash)) = (
+        iterations.parse().ok().and_then(NonZeroU32::new),
+        STANDARD_NO_PAD.decode(sel),
+        STANDARD_NO_PAD.decode(hash),
+    ) else {
+        return false;
+    };
+    pbkdf2::verify(ALGORITHME, iterations, &sel, mot_de_passe.as_bytes(), &hash).is_ok()
+}
+
+// Le hachage est volontairement coûteux : exécuté hors des workers HTTP
+pub async fn hacher(mot_de_passe: String) -> Result<String, ApiError> {
+    web::block(move || hacher_mot_de_passe(&mot_de_passe))
+        .await
+        .map_err(|e| ApiError::Storage(anyhow!("{}", e)))?
+        .map_err(ApiError::Storage)
+}
+
+pub async fn verifier(mot_de_passe: String, stocke: String) -> Result<bool, ApiError> {
+    web::block(move || verifier_mot_de_passe(&mot_de_passe, &stocke))
+        .await
+        .map_err(|e| ApiError::Storage(anyhow!("{}", e)))
+}
+
+// Mot de passe des comptes de démo quand DEMO_MOT_DE_PASSE n'est pas défini
+pub fn mot_de_passe_aleatoire() -> anyhow::Result<String> {
+    let mut octets = [0u8; 18];
+    SystemRandom::new().fill(&mut octets).map_err(|_| anyhow!("Générateur aléatoire indisponible"))?;
+    Ok(URL_SAFE_NO_PAD.encode(octets))
+}
+
+// Clé de signature aléatoire : les jetons émis ne survivent pas à un redémarrage
+pub fn cle_aleatoire() -> anyhow::Result<hmac::Key> {
+    hmac::Key::generate(hmac::HMAC_SHA256, &SystemRandom::new()).map_err(|_| anyhow!("Générateur aléatoire indisponible"))
+}
+
+pub fn emettre_jeton(cle: &hmac::Key, utilisateur_id: Uuid, expiration: DateTime<Utc>) -> String {
+    let contenu = URL_SAFE_NO_PAD.encode(format!("{}.{}", utilisateur_id, expiration.timestamp()));
+    let signature = URL_SAFE_NO_PAD.encode(hmac::sign(cle, contenu.as_bytes()));
+    format!("{}.{}", contenu, signature)
+}
+
+// Id de l'utilisateur si la signature est valide et le jeton non expiré
+pub fn verifier_jeton(cle: &hmac::Key, jeton: &str) -> Option<Uuid> {
+    let (contenu, signature) = jeton.split_once('.')?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+    hmac::verify(cle, contenu.as_bytes(), &signature).ok()?;
+
+    let contenu = String::from_utf8(URL_SAFE_NO_PAD.decode(contenu).ok()?).ok()?;
+    let (utilisateur_id, expiration) = contenu.split_once('.')?;
+    let expiration = DateTime::from_timestamp(expiration.parse().ok()?, 0)?;
+    if expiration <= Utc::now() {
+        return None;
+    }
+    utilisateur_id.parse().ok()
+}
+
+// Les clients iCalendar n'envoient pas d'en-tête Authorization : le jeton d'abonnement est passé
+// dans l'URL du flux, et reste valable jusqu'à ce que l'utilisateur le renouvelle ou le révoque
+pub fn nouveau_jeton_calendrier() -> anyhow::Result<String> {
+    let mut octets = [0u8; 32];
+    SystemRandom::new().fill(&mut octets).map_err(|_| anyhow!("Générateur aléatoire indisponible"))?;
+    Ok(URL_SAFE_NO_PAD.encode(octets))
+}
+
+pub fn empreinte_jeton_calendrier(jeton: &str) -> String {
+    STANDARD_NO_PAD.encode(digest::digest(&digest::SHA256, jeton.as_bytes()))
+}
+
+// Utilisateur abonné au calendrier par ce jeton, toujours actif
+pub async fn abonne_calendrier(utilisateurs: &dyn UtilisateurRepository, jeton: &str) -> Result<Utilisateur, ApiError> {
+    let utilisateur = utilisateurs.find_by_jeton_calendrier(&empreinte_jeton_calendrier(jeton)).await?
+        .ok_or_else(|| ApiError::Unauthorized("Jeton de calendrier invalide ou révoqué".to_string()))?;
+    if !utilisateur.actif {
+        return Err(ApiError::Forbidden("Compte utilisateur désactivé".to_string()));
+    }
+    Ok(utilisateur)
+}
+
+// Utilisateur authentifié par `Authorization: Bearer <jeton>` et toujours actif
+#[derive(Debug, Clone)]
+pub struct UtilisateurCourant(pub Utilisateur);
+
+impl FromRequest for UtilisateurCourant {
+    type Error = ApiError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        Box::pin(async move {
+            let non_authentifie = || ApiError::Unauthorized("Authentification requise".to_string());
+
+            let (Some(config), Some(utilisateurs)) = (
+                req.app_data::<web::Data<Config>>(),
+                req.app_data::<web::Data<dyn UtilisateurRepository>>(),
+            ) else {
+                return Err(ApiError::Storage(anyhow!("Configuration d'authentification absente")));
+            };
+
+            let jeton = req.headers()
+                .get(actix_web::http::header::AUTHORIZATION)
+                .and_then(|valeur| valeur.to_str().ok())
+                .and_then(|valeur| valeur.strip_prefix("Bearer "))
+                .ok_or_else(non_authentifie)?;
+            let utilisateur_id = verifier_jeton(&config.cle_jetons, jeton.trim()).ok_or_else(non_authentifie)?;
+
+            let utilisateur = utilisateurs.find(utilisateur_id).await?.ok_or_else(non_authentifie)?;
+            if !utilisateur.actif {
+                return Err(ApiError::Forbidden("Compte utilisateur désactivé".to_string()));
+            }
+            Ok(UtilisateurCourant(utilisateur))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn mot_de_passe_hache_puis_verifie() {
+        let stocke = hacher_mot_de_passe("correct horse").unwrap();
+        assert!(stocke.starts_with("pbkdf2-sha256$100000$"));
+        assert!(verifier_mot_de_passe("correct horse", &stocke));
+        assert!(!verifier_mot_de_passe("correct hors", &stocke));
+        assert!(!verifier_mot_de_passe("correct horse", "md5$abc"));
+    }
+
+    #[test]
+    fn jeton_signe_et_borne_dans_le_temps() {
+        let cle = cle_aleatoire().unwrap();
+        let id = Uuid::new_v4();
+        let jeton = emettre_jeton(&cle, id, Utc::now() + Duration::hours(1));
+        assert_eq!(verifier_jeton(&cle, &jeton), Some(id));
+
+        // Autre clé (redémarrage), jeton expiré, contenu ou signature altérés
+        assert_eq!(verifier_jeton(&cle_aleatoire().unwrap(), &jeton), None);
+        assert_eq!(verifier_jeton(&cle, &emettre_jeton(&cle, id, Utc::now() - Duration::seconds(1))), None);
+        let (contenu, signature) = jeton.split_once('.').unwrap();
+        let autre = URL_SAFE_NO_PAD.encode(format!("{}.{}", Uuid::new_v4(), (Utc::now() + Duration::hours(1)).timestamp()));
+        assert_eq!(verifier_jeton(&cle, &format!("{}.{}", autre, signature)), None);
+        assert_eq!(verifier_jeton(&cle, &format!("{}.{}A", contenu, signature)), None);
+        assert_eq!(verifier_jeton(&cle, "pas-un-jeton"), None);
+    }
+
+    #[test]
+    fn mots_de_passe_et_jetons_aleatoires_distincts() {
+        let mot_de_passe = mot_de_passe_aleatoire().unwrap();
+        assert!(mot_de_passe.chars().count() >= 8);
+        assert_ne!(mot_de_passe, mot_de_passe_aleatoire().unwrap());
+        let jeton = nouveau_jeton_calendrier().unwrap();
+        assert_ne!(empreinte_jeton_calendrier(&jeton), empreinte_jeton_calendrier(&nouveau_jeton_calendrier().unwrap()));
+        assert_ne!(empreinte_jeton_calendrier(&jeton), jeton);
+    }
+}
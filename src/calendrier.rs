@@ -0,0 +1,230 @@
+// Export iCalendar (RFC 5545) des travaux, pour les abonnements depuis Outlook et autres agendas.
+// L'UID d'un événement dérive de l'id du travail : un client qui rafraîchit le flux remplace
+// l'événement existant au lieu d'en créer un nouveau.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{StatutTravail, Travail, Utilisateur};
+
+const PRODID: &str = "-//Gestion des travaux//Calendrier des travaux//FR";
+const DOMAINE_UID: &str = "travaux";
+// Longueur maximale d'une ligne, en octets, hors CRLF (RFC 5545 §3.1)
+const LONGUEUR_LIGNE: usize = 75;
+
+pub fn uid(travail: &Travail) -> String {
+    format!("{}@{}", travail.id, DOMAINE_UID)
+}
+
+pub fn resume(travail: &Travail) -> String {
+    format!(
+        "{} {} ({})",
+        travail.type_travail.as_str(),
+        travail.application.as_str(),
+        travail.environnement.as_str()
+    )
+}
+
+fn date(date: DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Échappement des valeurs TEXT (RFC 5545 §3.3.11)
+fn texte(valeur: &str) -> String {
+    let mut echappe = String::with_capacity(valeur.len());
+    for c in valeur.chars() {
+        match c {
+            '\\' => echappe.push_str("\\\\"),
+            ';' => echappe.push_str("\\;"),
+            ',' => echappe.push_str("\\,"),
+            '\n' => echappe.push_str("\\n"),
+            '\r' => {}
+            c => echappe.push(c),
+        }
+    }
+    echappe
+}
+
+// Valeur de paramètre toujours entre guillemets ; les guillemets et caractères de contrôle sont interdits
+fn parametre(valeur: &str) -> String {
+    let valeur: String = valeur.chars().filter(|c| *c != '"' && !c.is_control()).collect();
+    format!("\"{}\"", valeur)
+}
+
+// Pliage des lignes longues : CRLF suivi d'un espace, sans couper un caractère UTF-8
+fn ajouter_ligne(flux: &mut String, ligne: &str) {
+    let mut longueur = 0;
+    for c in ligne.chars() {
+        if longueur + c.len_utf8() > LONGUEUR_LIGNE {
+            flux.push_str("\r\n ");
+            // L'espace de continuation compte dans la longueur de la ligne suivante
+            longueur = 1;
+        }
+        flux.push(c);
+        longueur += c.len_utf8();
+    }
+    flux.push_str("\r\n");
+}
+
+fn statut(statut: &StatutTravail) -> &'static str {
+    match statut {
+        StatutTravail::Planifie | StatutTravail::Suspendu => "TENTATIVE",
+        StatutTravail::EnCours | StatutTravail::Termine => "CONFIRMED",
+        StatutTravail::Annule => "CANCELLED",
+    }
+}
+
+fn participant(flux: &mut String, utilisateur: &Utilisateur, role: &str) {
+    ajouter_ligne(
+        flux,
+        &format!(
+            "ATTENDEE;CN={};ROLE={}:mailto:{}",
+            parametre(&utilisateur.nom),
+            role,
+            utilisateur.email
+        ),
+    );
+}
+
+// `utilisateurs` sert à retrouver les adresses du responsable et de l'équipe ; les inconnus sont ignorés
+pub fn generer(
+    nom: &str,
+    travaux: &[Travail],
+    utilisateurs: &HashMap<Uuid, Utilisateur>,
+    maintenant: DateTime<Utc>,
+) -> String {
+    let mut flux = String::new();
+    ajouter_ligne(&mut flux, "BEGIN:VCALENDAR");
+    ajouter_ligne(&mut flux, "VERSION:2.0");
+    ajouter_ligne(&mut flux, &format!("PRODID:{}", PRODID));
+    ajouter_ligne(&mut flux, "CALSCALE:GREGORIAN");
+    ajouter_ligne(&mut flux, "METHOD:PUBLISH");
+    ajouter_ligne(&mut flux, &format!("X-WR-CALNAME:{}", texte(nom)));
+
+    for travail in travaux {
+        ajouter_ligne(&mut flux, "BEGIN:VEVENT");
+        ajouter_ligne(&mut flux, &format!("UID:{}", uid(travail)));
+        ajouter_ligne(&mut flux, &format!("DTSTAMP:{}", date(maintenant)));
+        // Chaque écriture incrémente la version : les clients remplacent l'événement modifié
+        ajouter_ligne(&mut flux, &format!("SEQUENCE:{}", travail.version));
+        ajouter_ligne(&mut flux, &format!("DTSTART:{}", date(travail.date_debut)));
+        ajouter_ligne(&mut flux, &format!("DTEND:{}", date(travail.date_fin_prevue)));
+        ajouter_ligne(&mut flux, &format!("SUMMARY:{}", texte(&resume(travail))));
+        if !travail.description.is_empty() {
+            ajouter_ligne(&mut flux, &format!("DESCRIPTION:{}", texte(&travail.description)));
+        }
+        ajouter_ligne(&mut flux, &format!("STATUS:{}", statut(&travail.statut)));
+        ajouter_ligne(&mut flux, &format!("CATEGORIES:{}", texte(travail.environnement.as_str())));
+
+        if let Some(responsable) = utilisateurs.get(&travail.responsable) {
+            ajouter_ligne(
+                &mut flux,
+                &format!("ORGANIZER;CN={}:mailto:{}", parametre(&responsable.nom), responsable.email),
+            );
+            participant(&mut flux, responsable, "CHAIR");
+        }
+        for membre in travail.equipe.iter().filter(|id| **id != travail.responsable) {
+            if let Some(membre) = utilisateurs.get(membre) {
+                participant(&mut flux, membre, "REQ-PARTICIPANT");
+            }
+        }
+        ajouter_ligne(&mut flux, "END:VEVENT");
+    }
+
+    ajouter_ligne(&mut flux, "END:VCALENDAR");
+    flux
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::models::test_support::{self, date};
+    use crate::models::{Environnement, TypeTravail};
+
+    fn travail(description: &str) -> Travail {
+        Travail {
+            type_travail: TypeTravail::CloneBd,
+            environnement: Environnement::Formation,
+            description: description.to_string(),
+            date_debut: date(10),
+            date_fin_prevue: date(14),
+            ..test_support::travail()
+        }
+    }
+
+    // Lignes logiques du flux, après dépliage (RFC 5545 §3.1)
+    fn deplier(flux: &str) -> Vec<String> {
+        flux.replace("\r\n ", "").split_terminator("\r\n").map(str::to_string).collect()
+    }
+
+    #[test]
+    fn lignes_terminees_par_crlf() {
+        let flux = generer("Travaux", &[travail("Première ligne\nseconde ligne")], &HashMap::new(), Utc::now());
+        assert!(flux.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(flux.matches('\n').count(), flux.matches("\r\n").count());
+        assert!(!flux.replace("\r\n", "").contains('\r'));
+    }
+
+    #[test]
+    fn pliage_a_75_octets_sans_couper_un_caractere() {
+        // 74 octets puis « é » (2 octets) : la ligne est pliée avant le caractère multi-octets
+        let ligne = format!("{}é{}", "a".repeat(74), "b".repeat(100));
+        let mut flux = String::new();
+        ajouter_ligne(&mut flux, &ligne);
+
+        let physiques: Vec<&str> = flux.split_terminator("\r\n").collect();
+        assert_eq!(physiques[0], "a".repeat(74));
+        assert!(physiques[1].starts_with(" é"));
+        assert!(physiques.iter().all(|l| l.len() <= LONGUEUR_LIGNE));
+        assert!(physiques[1..].iter().all(|l| l.starts_with(' ')));
+        assert_eq!(deplier(&flux), vec![ligne]);
+
+        // Exactement 75 octets : pas de pliage
+        let mut flux = String::new();
+        ajouter_ligne(&mut flux, &"a".repeat(75));
+        assert_eq!(flux, format!("{}\r\n", "a".repeat(75)));
+    }
+
+    #[test]
+    fn echappement_du_texte() {
+        assert_eq!(texte(r"a;b,c\d"), r"a\;b\,c\\d");
+        assert_eq!(texte("ligne 1\r\nligne 2\nligne 3"), r"ligne 1\nligne 2\nligne 3");
+
+        let flux = generer("Équipe; prod, nuit", &[travail("Arrêt; puis\nredémarrage")], &HashMap::new(), Utc::now());
+        let lignes = deplier(&flux);
+        assert!(lignes.contains(&r"X-WR-CALNAME:Équipe\; prod\, nuit".to_string()));
+        assert!(lignes.contains(&r"DESCRIPTION:Arrêt\; puis\nredémarrage".to_string()));
+    }
+
+    #[test]
+    fn uid_stable_d_un_export_a_l_autre() {
+        let travail = travail("Clone");
+        let uid_du_flux = |maintenant| {
+            deplier(&generer("Travaux", std::slice::from_ref(&travail), &HashMap::new(), maintenant))
+                .into_iter()
+                .find(|l| l.starts_with("UID:"))
+                .unwrap()
+        };
+        let premier = uid_du_flux(Utc::now());
+        assert_eq!(premier, format!("UID:{}@{}", travail.id, DOMAINE_UID));
+        assert_eq!(uid_du_flux(Utc::now() + Duration::days(1)), premier);
+    }
+
+    #[test]
+    fn sequence_suit_la_version() {
+        // Une modification incrémente SEQUENCE : les clients remplacent l'événement de même UID
+        let sequence = |travail: &Travail| {
+            deplier(&generer("Travaux", std::slice::from_ref(travail), &HashMap::new(), Utc::now()))
+                .into_iter()
+                .find(|l| l.starts_with("SEQUENCE:"))
+                .unwrap()
+        };
+        let travail = travail("Clone");
+        assert_eq!(sequence(&travail), format!("SEQUENCE:{}", travail.version));
+        assert_eq!(sequence(&Travail { version: travail.version + 1, ..travail.clone() }), format!("SEQUENCE:{}", travail.version + 1));
+    }
+}
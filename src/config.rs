@@ -0,0 +1,139 @@
+// Configuration applicative lue depuis l'environnement au démarrage
+
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use ring::hmac;
+
+use crate::auth;
+
+// Que faire des travaux (et checklists) qui référencent un projet ou un utilisateur supprimé
+// (suppression logique, voir corbeille_handlers)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolitiqueSuppression {
+    // Refuser la suppression (409) tant que des références existent
+    Rejeter,
+    // Supprimer aussi les éléments dépendants
+    Cascade,
+    // Reporter les références sur l'entité passée en `?reassigner_a=<id>`
+    Reassigner,
+}
+
+impl FromStr for PolitiqueSuppression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rejeter" => Ok(PolitiqueSuppression::Rejeter),
+            "cascade" => Ok(PolitiqueSuppression::Cascade),
+            "reassigner" => Ok(PolitiqueSuppression::Reassigner),
+            autre => Err(format!("Politique de suppression inconnue : {} (rejeter, cascade, reassigner)", autre)),
+        }
+    }
+}
+
+// Traitement des conflits de planning détectés à la création / modification d'un travail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolitiqueConflits {
+    // Enregistrer le travail et renvoyer les conflits dans la réponse
+    Avertir,
+    // Refuser (409) tout nouveau conflit
+    Bloquer,
+}
+
+impl FromStr for PolitiqueConflits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "avertir" => Ok(PolitiqueConflits::Avertir),
+            "bloquer" => Ok(PolitiqueConflits::Bloquer),
+            autre => Err(format!("Politique de conflits inconnue : {} (avertir, bloquer)", autre)),
+        }
+    }
+}
+
+// Plages acceptées : au-delà, chrono::Duration::hours / days paniquent (ou n'ont pas de sens)
+const DUREE_JETON_HEURES: RangeInclusive<i64> = 1..=24 * 365;
+const RETENTION_SUPPRESSIONS_JOURS: RangeInclusive<i64> = 0..=3650;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub suppression_projets: PolitiqueSuppression,
+    pub suppression_utilisateurs: PolitiqueSuppression,
+    pub conflits_planning: PolitiqueConflits,
+    // Clé HMAC des jetons d'authentification (AUTH_SECRET, sinon aléatoire au démarrage)
+    pub cle_jetons: hmac::Key,
+    pub duree_jetons: chrono::Duration,
+    // Nombre d'approbations requises avant de démarrer un travail en production
+    pub approbations_production: usize,
+    // Délai pendant lequel un élément supprimé reste restaurable avant de pouvoir être purgé
+    pub retention_suppressions: chrono::Duration,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Config {
+            suppression_projets: env_or("SUPPRESSION_PROJETS", PolitiqueSuppression::Rejeter)?,
+            suppression_utilisateurs: env_or("SUPPRESSION_UTILISATEURS", PolitiqueSuppression::Rejeter)?,
+            conflits_planning: env_or("CONFLITS_PLANNING", PolitiqueConflits::Avertir)?,
+            cle_jetons: match std::env::var("AUTH_SECRET") {
+                Ok(secret) if secret.len() >= 32 => hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()),
+                Ok(_) => return Err("AUTH_SECRET : 32 caractères minimum".to_string()),
+                Err(_) => {
+                    println!("⚠️  AUTH_SECRET absent : clé de jetons aléatoire, les sessions ne survivront pas au redémarrage");
+                    auth::cle_aleatoire().map_err(|e| e.to_string())?
+                }
+            },
+            duree_jetons: chrono::Duration::hours(borner(
+                "AUTH_DUREE_JETON_HEURES",
+                env_or("AUTH_DUREE_JETON_HEURES", 12)?,
+                DUREE_JETON_HEURES,
+            )?),
+            approbations_production: match env_or("APPROBATIONS_PRODUCTION", 1)? {
+                0 => return Err("APPROBATIONS_PRODUCTION : au moins 1 approbation".to_string()),
+                n => n,
+            },
+            retention_suppressions: chrono::Duration::days(borner(
+                "RETENTION_SUPPRESSIONS_JOURS",
+                env_or("RETENTION_SUPPRESSIONS_JOURS", 30)?,
+                RETENTION_SUPPRESSIONS_JOURS,
+            )?),
+        })
+    }
+}
+
+fn env_or<T>(nom: &str, defaut: T) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(nom) {
+        Ok(valeur) => valeur.parse().map_err(|e| format!("{} : {}", nom, e)),
+        Err(_) => Ok(defaut),
+    }
+}
+
+fn borner(nom: &str, valeur: i64, plage: RangeInclusive<i64>) -> Result<i64, String> {
+    if plage.contains(&valeur) {
+        Ok(valeur)
+    } else {
+        Err(format!("{} : {} hors de la plage {}..={}", nom, valeur, plage.start(), plage.end()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bornes_des_durees() {
+        assert_eq!(borner("AUTH_DUREE_JETON_HEURES", 12, DUREE_JETON_HEURES), Ok(12));
+        assert_eq!(borner("RETENTION_SUPPRESSIONS_JOURS", 0, RETENTION_SUPPRESSIONS_JOURS), Ok(0));
+        assert!(borner("AUTH_DUREE_JETON_HEURES", 0, DUREE_JETON_HEURES).is_err());
+        assert!(borner("AUTH_DUREE_JETON_HEURES", i64::MAX, DUREE_JETON_HEURES).is_err());
+        let erreur = borner("RETENTION_SUPPRESSIONS_JOURS", -1, RETENTION_SUPPRESSIONS_JOURS).unwrap_err();
+        assert!(erreur.starts_with("RETENTION_SUPPRESSIONS_JOURS : -1"));
+        assert!(borner("RETENTION_SUPPRESSIONS_JOURS", i64::MAX / 1000, RETENTION_SUPPRESSIONS_JOURS).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+// Détection des conflits de planning entre travaux actifs dont les périodes se chevauchent :
+// même application sur le même environnement (risque d'indisponibilité), ou même responsable
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::models::Travail;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum TypeConflit {
+    // Même application et même environnement
+    #[serde(rename = "environnement", alias = "Environnement")]
+    Environnement,
+    // Même responsable
+    #[serde(rename = "responsable", alias = "Responsable")]
+    Responsable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Conflit {
+    pub type_conflit: TypeConflit,
+    pub travail_id: Uuid,
+    pub autre_travail_id: Uuid,
+    // Période commune aux deux travaux
+    pub debut: DateTime<Utc>,
+    pub fin: DateTime<Utc>,
+}
+
+// Période commune ; des périodes qui se touchent seulement (fin de l'un = début de l'autre) ne se chevauchent pas
+fn chevauchement(a: &Travail, b: &Travail) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let debut = a.date_debut.max(b.date_debut);
+    let fin = a.date_fin_prevue.min(b.date_fin_prevue);
+    (debut < fin).then_some((debut, fin))
+}
+
+pub fn conflits_entre(a: &Travail, b: &Travail) -> Vec<Conflit> {
+    if a.id == b.id || !a.statut.est_actif() || !b.statut.est_actif() {
+        return Vec::new();
+    }
+    let Some((debut, fin)) = chevauchement(a, b) else {
+        return Vec::new();
+    };
+
+    let mut types = Vec::new();
+    if a.application == b.application && a.environnement == b.environnement {
+        types.push(TypeConflit::Environnement);
+    }
+    if a.responsable == b.responsable {
+        types.push(TypeConflit::Responsable);
+    }
+    types.into_iter()
+        .map(|type_conflit| Conflit {
+            type_conflit,
+            travail_id: a.id,
+            autre_travail_id: b.id,
+            debut,
+            fin,
+        })
+        .collect()
+}
+
+// Conflits d'un travail avec les autres travaux
+pub fn conflits_travail(travail: &Travail, autres: &[Travail]) -> Vec<Conflit> {
+    autres.iter().flat_map(|autre| conflits_entre(travail, autre)).collect()
+}
+
+// Tous les conflits d'un ensemble de travaux, chaque paire une seule fois, par date de début
+pub fn tous_les_conflits(travaux: &[Travail]) -> Vec<Conflit> {
+    let mut travaux: Vec<&Travail> = travaux.iter().filter(|t| t.statut.est_actif()).collect();
+    travaux.sort_by_key(|t| (t.date_debut, t.id));
+
+    let mut conflits = Vec::new();
+    for (i, travail) in travaux.iter().enumerate() {
+        // Triés par début : les suivants qui commencent après notre fin ne peuvent plus chevaucher
+        for autre in travaux[i + 1..].iter().take_while(|autre| autre.date_debut < travail.date_fin_prevue) {
+            conflits.extend(conflits_entre(travail, autre));
+        }
+    }
+    conflits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::{self, date};
+    use crate::models::{Application, Environnement, StatutTravail};
+
+    // Travail de `debut` à `fin` heures après une date de référence, chacun avec son responsable
+    fn travail(debut: i64, fin: i64, application: Application, environnement: Environnement) -> Travail {
+        Travail {
+            application,
+            environnement,
+            date_debut: date(debut),
+            date_fin_prevue: date(fin),
+            ..test_support::travail()
+        }
+    }
+
+    fn gfr_production(debut: i64, fin: i64) -> Travail {
+        travail(debut, fin, Application::EspressoGfr, Environnement::Production)
+    }
+
+    fn types(conflits: &[Conflit]) -> Vec<TypeConflit> {
+        conflits.iter().map(|c| c.type_conflit).collect()
+    }
+
+    #[test]
+    fn meme_application_et_environnement() {
+        let (a, b) = (gfr_production(0, 4), gfr_production(2, 6));
+        let conflits = conflits_entre(&a, &b);
+        assert_eq!(types(&conflits), vec![TypeConflit::Environnement]);
+        assert_eq!((conflits[0].debut, conflits[0].fin), (b.date_debut, a.date_fin_prevue));
+
+        // Autre environnement de la même application : pas de conflit
+        let formation = travail(2, 6, Application::EspressoGfr, Environnement::Formation);
+        assert!(conflits_entre(&a, &formation).is_empty());
+    }
+
+    #[test]
+    fn meme_responsable() {
+        let a = gfr_production(0, 4);
+        let b = Travail { responsable: a.responsable, ..travail(1, 2, Application::EspressoGrh, Environnement::Test) };
+        assert_eq!(types(&conflits_entre(&a, &b)), vec![TypeConflit::Responsable]);
+
+        let c = Travail { responsable: a.responsable, ..gfr_production(3, 5) };
+        assert_eq!(types(&conflits_entre(&a, &c)), vec![TypeConflit::Environnement, TypeConflit::Responsable]);
+    }
+
+    #[test]
+    fn periodes_qui_se_touchent() {
+        let a = gfr_production(0, 4);
+        let b = Travail { responsable: a.responsable, ..gfr_production(4, 8) };
+        assert!(conflits_entre(&a, &b).is_empty());
+        assert!(tous_les_conflits(&[a, b]).is_empty());
+    }
+
+    #[test]
+    fn travaux_termines_ou_annules_ignores() {
+        let a = gfr_production(0, 4);
+        for statut in [StatutTravail::Termine, StatutTravail::Annule] {
+            let b = Travail { statut: statut.clone(), ..gfr_production(1, 3) };
+            assert!(conflits_entre(&a, &b).is_empty());
+            assert!(conflits_entre(&b, &a).is_empty());
+            assert!(tous_les_conflits(&[a.clone(), b]).is_empty());
+        }
+        assert!(conflits_entre(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn arret_anticipe_ne_perd_aucun_conflit() {
+        // b finit avant le début de c : la recherche depuis b s'arrête, mais a, plus long,
+        // chevauche encore c
+        let (a, b, c) = (gfr_production(0, 10), gfr_production(1, 2), gfr_production(5, 6));
+        let d = gfr_production(12, 13);
+        let conflits = tous_les_conflits(&[d, c.clone(), b.clone(), a.clone()]);
+        let paires: Vec<(Uuid, Uuid)> = conflits.iter().map(|c| (c.travail_id, c.autre_travail_id)).collect();
+        assert_eq!(paires, vec![(a.id, b.id), (a.id, c.id)]);
+    }
+}
@@ -1,14 +1,24 @@
-// Module pour la gestion de la base de données
-// À implémenter avec SQLx pour PostgreSQL
+// Module pour la gestion de la base de données PostgreSQL via SQLx
 
+use sqlx::migrate::{Migrate, Migrator};
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
 use anyhow::Result;
 
+// Migrations versionnées du répertoire `migrations/`, embarquées à la compilation.
+// L'historique (version, checksum, date d'application) est tenu dans `_sqlx_migrations`.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
 pub struct Database {
     pub pool: Pool<Postgres>,
 }
 
+pub struct EtatMigration {
+    pub version: i64,
+    pub description: String,
+    pub appliquee: bool,
+}
+
 impl Database {
     pub async fn new(database_url: &str) -> Result<Self> {
         let pool = PgPoolOptions::new()
@@ -19,77 +29,30 @@ impl Database {
         Ok(Database { pool })
     }
 
-    pub async fn init_schema(&self) -> Result<()> {
-        // Création des tables
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS projets (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                code VARCHAR(50) UNIQUE NOT NULL,
-                nom VARCHAR(255) NOT NULL,
-                description TEXT,
-                date_debut TIMESTAMPTZ NOT NULL,
-                date_fin_prevue TIMESTAMPTZ NOT NULL,
-                statut VARCHAR(20) NOT NULL,
-                priorite VARCHAR(20) NOT NULL,
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW()
-            )
-            "#
-        ).execute(&self.pool).await?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS utilisateurs (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                nom VARCHAR(255) NOT NULL,
-                email VARCHAR(255) UNIQUE NOT NULL,
-                role VARCHAR(20) NOT NULL,
-                equipe VARCHAR(100) NOT NULL,
-                actif BOOLEAN DEFAULT TRUE,
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW()
-            )
-            "#
-        ).execute(&self.pool).await?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS travaux (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                projet_id UUID REFERENCES projets(id),
-                type_travail VARCHAR(20) NOT NULL,
-                application VARCHAR(20) NOT NULL,
-                environnement VARCHAR(20) NOT NULL,
-                description TEXT NOT NULL,
-                date_debut TIMESTAMPTZ NOT NULL,
-                date_fin_prevue TIMESTAMPTZ NOT NULL,
-                statut VARCHAR(20) NOT NULL,
-                responsable UUID REFERENCES utilisateurs(id),
-                equipe UUID[],
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW()
-            )
-            "#
-        ).execute(&self.pool).await?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS checklist_items (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                travail_id UUID REFERENCES travaux(id),
-                description TEXT NOT NULL,
-                statut VARCHAR(20) NOT NULL,
-                responsable UUID REFERENCES utilisateurs(id),
-                date_echeance TIMESTAMPTZ,
-                commentaires TEXT,
-                ordre INTEGER NOT NULL,
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW()
-            )
-            "#
-        ).execute(&self.pool).await?;
+    // Applique les migrations en attente ; échoue si le checksum d'une migration déjà appliquée a changé
+    pub async fn migrate(&self) -> Result<()> {
+        MIGRATOR.run(&self.pool).await?;
+        Ok(())
+    }
 
+    // Annule les migrations (scripts .down.sql) jusqu'à `version` exclue
+    pub async fn revert(&self, version: i64) -> Result<()> {
+        MIGRATOR.undo(&self.pool, version).await?;
         Ok(())
     }
+
+    pub async fn migration_status(&self) -> Result<Vec<EtatMigration>> {
+        let mut conn = self.pool.acquire().await?;
+        conn.ensure_migrations_table().await?;
+        let applied = conn.list_applied_migrations().await?;
+
+        Ok(MIGRATOR.iter()
+            .filter(|m| m.migration_type.is_up_migration())
+            .map(|m| EtatMigration {
+                version: m.version,
+                description: m.description.to_string(),
+                appliquee: applied.iter().any(|a| a.version == m.version),
+            })
+            .collect())
+    }
 }
@@ -0,0 +1,208 @@
+// Dépendances fin → début entre travaux d'un projet : détection des cycles et calcul du chemin
+// critique (méthode CPM) à partir des dates prévues. La date de début prévue d'un travail est une
+// contrainte « au plus tôt » ; sa durée est date_fin_prevue - date_debut.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::models::{Dependance, Travail};
+
+// Vrai si ajouter « travail_id dépend de depend_de » fermerait un cycle, c'est-à-dire si
+// depend_de dépend déjà (directement ou non) de travail_id
+pub fn cree_un_cycle(dependances: &[Dependance], travail_id: Uuid, depend_de: Uuid) -> bool {
+    let mut a_visiter = vec![depend_de];
+    let mut visites = HashSet::new();
+    while let Some(courant) = a_visiter.pop() {
+        if courant == travail_id {
+            return true;
+        }
+        if visites.insert(courant) {
+            a_visiter.extend(dependances.iter().filter(|d| d.travail_id == courant).map(|d| d.depend_de));
+        }
+    }
+    false
+}
+
+// Le travail commence après la fin prévue de son prédécesseur
+pub fn respecte(travail: &Travail, predecesseur: &Travail) -> bool {
+    predecesseur.date_fin_prevue <= travail.date_debut
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanningTravail {
+    pub travail_id: Uuid,
+    pub debut_au_plus_tot: DateTime<Utc>,
+    pub fin_au_plus_tot: DateTime<Utc>,
+    pub debut_au_plus_tard: DateTime<Utc>,
+    pub fin_au_plus_tard: DateTime<Utc>,
+    // Retard possible sans décaler la fin du projet
+    pub marge_minutes: i64,
+    pub critique: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheminCritique {
+    // Fin au plus tôt de l'ensemble des travaux (None s'il n'y en a aucun)
+    pub fin: Option<DateTime<Utc>>,
+    // Travaux à marge nulle, dans l'ordre de leur début au plus tôt
+    pub chemin: Vec<Uuid>,
+    pub travaux: Vec<PlanningTravail>,
+}
+
+// Ordre topologique (prédécesseurs d'abord) ; les travaux pris dans un cycle sont écartés
+fn ordre_topologique(travaux: &[Travail], dependances: &[&Dependance]) -> Vec<Uuid> {
+    let mut restants: HashMap<Uuid, usize> = travaux.iter().map(|t| (t.id, 0)).collect();
+    for dependance in dependances {
+        *restants.entry(dependance.travail_id).or_default() += 1;
+    }
+    // Ordre initial déterministe : date de début puis id
+    let mut prets: Vec<&Travail> = travaux.iter().filter(|t| restants[&t.id] == 0).collect();
+    prets.sort_by_key(|t| (t.date_debut, t.id));
+    let mut prets: Vec<Uuid> = prets.into_iter().rev().map(|t| t.id).collect();
+
+    let mut ordre = Vec::with_capacity(travaux.len());
+    while let Some(id) = prets.pop() {
+        ordre.push(id);
+        for dependance in dependances.iter().filter(|d| d.depend_de == id) {
+            let restant = restants.entry(dependance.travail_id).or_default();
+            *restant -= 1;
+            if *restant == 0 {
+                prets.push(dependance.travail_id);
+            }
+        }
+    }
+    ordre
+}
+
+pub fn chemin_critique(travaux: &[Travail], dependances: &[Dependance]) -> CheminCritique {
+    let par_id: HashMap<Uuid, &Travail> = travaux.iter().map(|t| (t.id, t)).collect();
+    // Seules comptent les dépendances entre travaux retenus
+    let dependances: Vec<&Dependance> = dependances.iter()
+        .filter(|d| par_id.contains_key(&d.travail_id) && par_id.contains_key(&d.depend_de))
+        .collect();
+    let ordre = ordre_topologique(travaux, &dependances);
+    let duree = |id: &Uuid| par_id[id].date_fin_prevue - par_id[id].date_debut;
+
+    // Passe avant : début au plus tôt = max(début prévu, fins au plus tôt des prédécesseurs)
+    let mut fin_tot: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+    let mut debut_tot: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+    for id in &ordre {
+        let debut = dependances.iter()
+            .filter(|d| d.travail_id == *id)
+            .filter_map(|d| fin_tot.get(&d.depend_de).copied())
+            .fold(par_id[id].date_debut, DateTime::max);
+        debut_tot.insert(*id, debut);
+        fin_tot.insert(*id, debut + duree(id));
+    }
+    let fin = fin_tot.values().copied().max();
+
+    // Passe arrière : fin au plus tard = min(fin du projet, débuts au plus tard des successeurs)
+    let mut debut_tard: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+    let mut planning = Vec::with_capacity(ordre.len());
+    for id in ordre.iter().rev() {
+        let fin_tard = dependances.iter()
+            .filter(|d| d.depend_de == *id)
+            .filter_map(|d| debut_tard.get(&d.travail_id).copied())
+            .fold(fin.unwrap_or(fin_tot[id]), DateTime::min);
+        let debut = fin_tard - duree(id);
+        debut_tard.insert(*id, debut);
+
+        let marge = debut - debut_tot[id];
+        planning.push(PlanningTravail {
+            travail_id: *id,
+            debut_au_plus_tot: debut_tot[id],
+            fin_au_plus_tot: fin_tot[id],
+            debut_au_plus_tard: debut,
+            fin_au_plus_tard: fin_tard,
+            marge_minutes: marge.num_minutes(),
+            critique: marge <= Duration::zero(),
+        });
+    }
+    planning.sort_by_key(|p| (p.debut_au_plus_tot, p.fin_au_plus_tot, p.travail_id));
+
+    CheminCritique {
+        fin,
+        chemin: planning.iter().filter(|p| p.critique).map(|p| p.travail_id).collect(),
+        travaux: planning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::{self, date};
+
+    // Travail du même projet, de `debut` à `fin` heures après une date de référence
+    fn travail(debut: i64, fin: i64) -> Travail {
+        Travail { date_debut: date(debut), date_fin_prevue: date(fin), ..test_support::travail() }
+    }
+
+    fn dependance(travail: &Travail, depend_de: &Travail) -> Dependance {
+        Dependance { id: Uuid::new_v4(), travail_id: travail.id, depend_de: depend_de.id }
+    }
+
+    fn planning<'a>(chemin: &'a CheminCritique, travail: &Travail) -> &'a PlanningTravail {
+        chemin.travaux.iter().find(|p| p.travail_id == travail.id).unwrap()
+    }
+
+    #[test]
+    fn cycle_direct() {
+        let (a, b) = (travail(0, 1), travail(1, 2));
+        let dependances = [dependance(&a, &b)];
+        assert!(cree_un_cycle(&dependances, b.id, a.id));
+        assert!(cree_un_cycle(&dependances, a.id, a.id));
+    }
+
+    #[test]
+    fn cycle_indirect() {
+        let (a, b, c) = (travail(0, 1), travail(1, 2), travail(2, 3));
+        // a dépend de b, qui dépend de c
+        let dependances = [dependance(&a, &b), dependance(&b, &c)];
+        assert!(cree_un_cycle(&dependances, c.id, a.id));
+        // Redondant mais sans cycle
+        assert!(!cree_un_cycle(&dependances, a.id, c.id));
+        assert!(!cree_un_cycle(&dependances, c.id, travail(0, 1).id));
+    }
+
+    #[test]
+    fn chaine_lineaire_entierement_critique() {
+        let (a, b, c) = (travail(0, 2), travail(2, 5), travail(5, 6));
+        let dependances = [dependance(&b, &a), dependance(&c, &b)];
+        let resultat = chemin_critique(&[c.clone(), a.clone(), b.clone()], &dependances);
+
+        assert_eq!(resultat.chemin, vec![a.id, b.id, c.id]);
+        assert_eq!(resultat.fin, Some(c.date_fin_prevue));
+        assert!(resultat.travaux.iter().all(|p| p.critique && p.marge_minutes == 0));
+    }
+
+    #[test]
+    fn branche_parallele_avec_marge() {
+        // c attend a (4 h) et b (1 h) : b peut glisser de 3 h sans retarder c
+        let (a, b, c) = (travail(0, 4), travail(0, 1), travail(4, 6));
+        let dependances = [dependance(&c, &a), dependance(&c, &b)];
+        let resultat = chemin_critique(&[a.clone(), b.clone(), c.clone()], &dependances);
+
+        assert_eq!(resultat.chemin, vec![a.id, c.id]);
+        let marge_b = planning(&resultat, &b);
+        assert!(!marge_b.critique);
+        assert_eq!(marge_b.marge_minutes, 180);
+        assert_eq!(marge_b.fin_au_plus_tard, c.date_debut);
+        assert_eq!(planning(&resultat, &a).marge_minutes, 0);
+    }
+
+    #[test]
+    fn predecesseur_en_retard_decale_le_successeur() {
+        // b prévu à 1 h mais a ne finit qu'à 3 h : b commence au plus tôt à 3 h
+        let (a, b) = (travail(0, 3), travail(1, 2));
+        let resultat = chemin_critique(&[a.clone(), b.clone()], &[dependance(&b, &a)]);
+        let planning_b = planning(&resultat, &b);
+        assert_eq!(planning_b.debut_au_plus_tot, a.date_fin_prevue);
+        assert_eq!(resultat.fin, Some(a.date_fin_prevue + Duration::hours(1)));
+    }
+}
@@ -0,0 +1,179 @@
+// Erreur applicative commune aux handlers, convertie en réponse JSON homogène :
+// {"error": <message>, "code": <code stable>, ...}
+
+use actix_web::{http::StatusCode, web, HttpResponse, ResponseError};
+use serde::Serialize;
+use sqlx::error::ErrorKind;
+use utoipa::ToSchema;
+
+use crate::conflits::Conflit;
+use crate::handlers::versions::etag;
+use crate::models::{PeriodeGel, StatutEntite};
+use crate::permissions::Permission;
+use crate::validation::ErreurChamp;
+
+pub type Result<T, E = ApiError> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("Requête invalide")]
+    Validation(Vec<ErreurChamp>),
+    #[error("{message}")]
+    Conflict { code: &'static str, message: String },
+    #[error("Transition de statut interdite : {de} → {vers}")]
+    TransitionInterdite { de: StatutEntite, vers: StatutEntite },
+    #[error("{} conflit(s) de planning", .0.len())]
+    ConflitsPlanning(Vec<Conflit>),
+    #[error("Travail planifié pendant une période de gel : {}", .0.iter().map(|g| g.raison.as_str()).collect::<Vec<_>>().join(", "))]
+    PeriodesGel(Vec<PeriodeGel>),
+    #[error("La ressource a été modifiée entre-temps (version actuelle : {actuelle})")]
+    VersionPerimee { actuelle: i32 },
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("Permission manquante : {}", .0.as_str())]
+    PermissionManquante(Permission),
+    #[error("Erreur de stockage")]
+    Storage(#[source] anyhow::Error),
+}
+
+impl ApiError {
+    // Code stable sur lequel le frontend peut s'appuyer (le message peut évoluer)
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "non_trouve",
+            ApiError::BadRequest(_) => "requete_invalide",
+            ApiError::Validation(_) => "validation",
+            ApiError::Conflict { code, .. } => code,
+            ApiError::TransitionInterdite { .. } => "transition_interdite",
+            ApiError::ConflitsPlanning(_) => "conflit_planning",
+            ApiError::PeriodesGel(_) => "periode_gel",
+            ApiError::VersionPerimee { .. } => "version_perimee",
+            ApiError::Unauthorized(_) => "non_authentifie",
+            ApiError::Forbidden(_) => "interdit",
+            ApiError::PermissionManquante(_) => "permission_manquante",
+            ApiError::Storage(_) => "erreur_stockage",
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Conflict { .. }
+            | ApiError::TransitionInterdite { .. }
+            | ApiError::ConflitsPlanning(_)
+            | ApiError::PeriodesGel(_) => StatusCode::CONFLICT,
+            ApiError::VersionPerimee { .. } => StatusCode::PRECONDITION_FAILED,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) | ApiError::PermissionManquante(_) => StatusCode::FORBIDDEN,
+            ApiError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut corps = CorpsErreur {
+            error: self.to_string(),
+            code: self.code(),
+            erreurs: None,
+            de: None,
+            vers: None,
+            conflits: None,
+            periodes: None,
+            version: None,
+            permission: None,
+        };
+        let mut reponse = HttpResponse::build(self.status_code());
+        match self {
+            ApiError::Validation(erreurs) => corps.erreurs = Some(erreurs.clone()),
+            ApiError::TransitionInterdite { de, vers } => {
+                corps.de = Some(de.clone());
+                corps.vers = Some(vers.clone());
+            }
+            ApiError::ConflitsPlanning(conflits) => corps.conflits = Some(conflits.clone()),
+            ApiError::PeriodesGel(gels) => corps.periodes = Some(gels.clone()),
+            // ETag de la version actuelle : le client peut relire puis réessayer
+            ApiError::VersionPerimee { actuelle } => {
+                corps.version = Some(*actuelle);
+                reponse.insert_header(etag(*actuelle));
+            }
+            ApiError::PermissionManquante(permission) => corps.permission = Some(permission.as_str()),
+            // Le détail technique reste dans les logs, pas dans la réponse
+            ApiError::Storage(err) => eprintln!("❌ Erreur de stockage : {:#}", err),
+            _ => {}
+        }
+        reponse.json(corps)
+    }
+}
+
+// Enveloppe JSON des erreurs ; les champs facultatifs dépendent du code
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorpsErreur {
+    pub error: String,
+    pub code: &'static str,
+    // validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub erreurs: Option<Vec<ErreurChamp>>,
+    // transition_interdite
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub de: Option<StatutEntite>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vers: Option<StatutEntite>,
+    // conflit_planning
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflits: Option<Vec<Conflit>>,
+    // periode_gel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub periodes: Option<Vec<PeriodeGel>>,
+    // version_perimee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>,
+    // permission_manquante
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<&'static str>,
+}
+
+// Les violations de contraintes PostgreSQL deviennent des erreurs client
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        let violation = match err.downcast_ref::<sqlx::Error>() {
+            Some(sqlx::Error::Database(db)) => {
+                Some((db.kind(), db.constraint().unwrap_or_default().to_string()))
+            }
+            _ => None,
+        };
+        match violation {
+            Some((ErrorKind::UniqueViolation, contrainte)) => ApiError::Conflict {
+                code: "deja_utilise",
+                message: format!("Valeur déjà utilisée (contrainte {})", contrainte),
+            },
+            Some((ErrorKind::ForeignKeyViolation, contrainte)) => ApiError::Validation(vec![ErreurChamp {
+                field: contrainte,
+                code: "reference_invalide",
+                message: "Référence vers une entité inexistante".to_string(),
+            }]),
+            _ => ApiError::Storage(err),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        ApiError::from(anyhow::Error::from(err))
+    }
+}
+
+// Les erreurs d'extraction (JSON, chemin, query string) utilisent la même enveloppe
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().error_handler(|err, _| ApiError::BadRequest(err.to_string()).into()))
+        .app_data(web::PathConfig::default().error_handler(|err, _| ApiError::BadRequest(err.to_string()).into()))
+        .app_data(web::QueryConfig::default().error_handler(|err, _| ApiError::BadRequest(err.to_string()).into()));
+}
@@ -0,0 +1,485 @@
+// Validation managériale des travaux de production : un travail en Production ne peut passer
+// EnCours qu'avec une demande d'approbation active ayant reçu assez d'approbations et aucun rejet.
+// Une fois le travail démarré, changer ses dates ou son périmètre exige une nouvelle demande,
+// qui remplace celle du démarrage
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::UtilisateurCourant;
+use crate::permissions::Permission;
+use crate::config::Config;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::models::{Approbation, DecisionApprobation, DemandeApprobation, StatutApprobation, StatutEntite, StatutTravail, Travail, TypeEntite};
+use crate::repositories::{ApprobationRepository, Repositories, TransitionRepository, TravailRepository};
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionRequest {
+    pub decision: DecisionApprobation,
+    pub commentaire: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprobationResponse {
+    pub id: Uuid,
+    pub approbateur: Uuid,
+    pub decision: DecisionApprobation,
+    pub commentaire: Option<String>,
+    pub date: DateTime<Utc>,
+}
+
+impl From<Approbation> for ApprobationResponse {
+    fn from(a: Approbation) -> Self {
+        ApprobationResponse {
+            id: a.id,
+            approbateur: a.approbateur,
+            decision: a.decision,
+            commentaire: a.commentaire,
+            date: a.date,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DemandeApprobationResponse {
+    pub id: Uuid,
+    pub demandeur: Uuid,
+    pub date: DateTime<Utc>,
+    pub invalidee_le: Option<DateTime<Utc>>,
+    pub statut: StatutApprobation,
+    pub decisions: Vec<ApprobationResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprobationsTravailResponse {
+    pub requise: bool,
+    pub approbations_requises: usize,
+    // Statut de la demande active, None s'il n'y en a pas
+    pub statut: Option<StatutApprobation>,
+    pub demandes: Vec<DemandeApprobationResponse>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/travaux/{travail_id}/approbations")
+            .route("", web::get().to(get_approbations))
+            .route("", web::post().to(demander_approbation))
+            .route("/decisions", web::post().to(decider_approbation))
+    );
+}
+
+fn travail_non_trouve() -> ApiError {
+    ApiError::NotFound("Travail non trouvé".to_string())
+}
+
+// La demande active est la plus récente non invalidée
+async fn demande_active(
+    approbations: &dyn ApprobationRepository,
+    travail_id: Uuid,
+) -> Result<Option<(DemandeApprobation, Vec<Approbation>)>> {
+    let Some(demande) = approbations.list_demandes(travail_id).await?
+        .into_iter()
+        .rfind(|d| d.invalidee_le.is_none())
+    else {
+        return Ok(None);
+    };
+    let decisions = approbations.list_decisions(demande.id).await?;
+    Ok(Some((demande, decisions)))
+}
+
+// Date du dernier passage EnCours du travail, None pour un travail démarré hors API (données initiales)
+async fn date_demarrage(transitions: &dyn TransitionRepository, travail_id: Uuid) -> Result<Option<DateTime<Utc>>> {
+    let demarrage = StatutEntite::Travail(StatutTravail::EnCours);
+    Ok(transitions.list_by_entite(&TypeEntite::Travail, travail_id).await?
+        .into_iter()
+        .rfind(|t| t.vers == demarrage)
+        .map(|t| t.date))
+}
+
+// Refuse le passage EnCours d'un travail de production sans approbation valide, et le changement
+// des dates ou du périmètre d'un travail déjà démarré sans approbation demandée depuis le démarrage.
+// `travail` est l'état visé, `depuis` le statut avant l'opération ; `perimetre_modifie` : la même
+// requête change les dates ou le périmètre approuvés
+pub async fn verifier_approbation(
+    travail: &Travail,
+    depuis: &StatutTravail,
+    perimetre_modifie: bool,
+    approbations: &dyn ApprobationRepository,
+    transitions: &dyn TransitionRepository,
+    config: &Config,
+) -> Result<()> {
+    if !travail.exige_approbation() || travail.statut != StatutTravail::EnCours {
+        return Ok(());
+    }
+    let demarre = *depuis == StatutTravail::EnCours;
+    if demarre && !perimetre_modifie {
+        return Ok(());
+    }
+    let approuve = match demande_active(approbations, travail.id).await? {
+        Some((demande, decisions)) if demande.statut(&decisions, config.approbations_production) == StatutApprobation::Approuvee => {
+            if !demarre {
+                !perimetre_modifie
+            } else {
+                // L'approbation du démarrage ne couvre pas le changement : il faut une demande postérieure
+                date_demarrage(transitions, travail.id).await?.is_none_or(|date| demande.date > date)
+            }
+        }
+        _ => false,
+    };
+    if approuve {
+        Ok(())
+    } else if demarre {
+        Err(ApiError::Conflict {
+            code: "approbation_requise",
+            message: "Travail de production déjà démarré : les nouvelles dates ou le nouveau périmètre doivent faire l'objet d'une nouvelle demande d'approbation".to_string(),
+        })
+    } else {
+        Err(ApiError::Conflict {
+            code: "approbation_requise",
+            message: format!(
+                "Un travail de production ne peut démarrer qu'après {} approbation(s) managériale(s)",
+                config.approbations_production
+            ),
+        })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{travail_id}/approbations",
+    tag = "approbations",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    responses(
+        (status = 200, description = "Demandes et décisions du travail", body = ApprobationsTravailResponse),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_approbations(
+    _courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    travaux: web::Data<dyn TravailRepository>,
+    approbations: web::Data<dyn ApprobationRepository>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    let travail_id = travail_id.into_inner();
+    let Some(travail) = travaux.find(travail_id).await? else {
+        return Err(travail_non_trouve());
+    };
+
+    let mut demandes = Vec::new();
+    let mut statut = None;
+    for demande in approbations.list_demandes(travail_id).await? {
+        let decisions = approbations.list_decisions(demande.id).await?;
+        let statut_demande = demande.statut(&decisions, config.approbations_production);
+        if demande.invalidee_le.is_none() {
+            statut = Some(statut_demande.clone());
+        }
+        demandes.push(DemandeApprobationResponse {
+            id: demande.id,
+            demandeur: demande.demandeur,
+            date: demande.date,
+            invalidee_le: demande.invalidee_le,
+            statut: statut_demande,
+            decisions: decisions.into_iter().map(ApprobationResponse::from).collect(),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(ApprobationsTravailResponse {
+        requise: travail.exige_approbation(),
+        approbations_requises: config.approbations_production,
+        statut,
+        demandes,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/travaux/{travail_id}/approbations",
+    tag = "approbations",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    responses(
+        (status = 201, description = "Demande créée", body = DemandeApprobationResponse),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 409, description = "Approbation inutile ou demande déjà en cours", body = CorpsErreur),
+    ),
+)]
+pub async fn demander_approbation(
+    courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    depots: Repositories,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, approbations, .. } = &*unite;
+    let Some(travail) = travaux.find(travail_id.into_inner()).await? else {
+        return Err(travail_non_trouve());
+    };
+    courant.exiger_modification(&travail)?;
+
+    if !travail.exige_approbation() {
+        return Err(ApiError::Conflict {
+            code: "approbation_inutile",
+            message: "Seuls les travaux de production nécessitent une approbation".to_string(),
+        });
+    }
+    // Une demande rejetée peut être renouvelée, tout comme l'approbation d'un travail déjà démarré
+    // dont on veut changer les dates ou le périmètre : la nouvelle demande la remplace. Une
+    // demande en attente, ou approuvée pour un démarrage à venir, reste valable
+    if let Some((demande, decisions)) = demande_active(approbations.as_ref(), travail.id).await? {
+        let remplacable = match demande.statut(&decisions, config.approbations_production) {
+            StatutApprobation::Rejetee => true,
+            StatutApprobation::Approuvee => travail.statut == StatutTravail::EnCours,
+            _ => false,
+        };
+        if !remplacable {
+            return Err(ApiError::Conflict {
+                code: "demande_en_cours",
+                message: "Une demande d'approbation est déjà en cours pour ce travail".to_string(),
+            });
+        }
+        approbations.invalider(travail.id).await?;
+    }
+
+    let demande = DemandeApprobation {
+        id: Uuid::new_v4(),
+        travail_id: travail.id,
+        demandeur: courant.0.id,
+        date: Utc::now(),
+        invalidee_le: None,
+    };
+    approbations.insert_demande(&demande).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::Created().json(DemandeApprobationResponse {
+        id: demande.id,
+        demandeur: demande.demandeur,
+        date: demande.date,
+        invalidee_le: None,
+        statut: StatutApprobation::EnAttente,
+        decisions: Vec::new(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/travaux/{travail_id}/approbations/decisions",
+    tag = "approbations",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    request_body = DecisionRequest,
+    responses(
+        (status = 201, description = "Décision enregistrée", body = DemandeApprobationResponse),
+        (status = 403, description = "Permission approuver_production manquante", body = CorpsErreur),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 409, description = "Aucune demande en cours, auto-approbation ou décision déjà rendue", body = CorpsErreur),
+    ),
+)]
+pub async fn decider_approbation(
+    courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    decision_req: web::Json<DecisionRequest>,
+    travaux: web::Data<dyn TravailRepository>,
+    approbations: web::Data<dyn ApprobationRepository>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::ApprouverProduction)?;
+    let Some(travail) = travaux.find(travail_id.into_inner()).await? else {
+        return Err(travail_non_trouve());
+    };
+    let Some((demande, decisions)) = demande_active(approbations.as_ref(), travail.id).await? else {
+        return Err(ApiError::Conflict {
+            code: "aucune_demande",
+            message: "Aucune demande d'approbation active pour ce travail".to_string(),
+        });
+    };
+
+    let decision_req = decision_req.into_inner();
+    let commentaire = decision_req.commentaire
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if decision_req.decision == DecisionApprobation::Rejete && commentaire.is_none() {
+        return Err(Validation::champ_invalide("commentaire", "requis", "Un rejet doit être motivé"));
+    }
+    if demande.demandeur == courant.0.id {
+        return Err(ApiError::Conflict {
+            code: "auto_approbation",
+            message: "Le demandeur ne peut pas statuer sur sa propre demande".to_string(),
+        });
+    }
+    if decisions.iter().any(|d| d.approbateur == courant.0.id) {
+        return Err(ApiError::Conflict {
+            code: "deja_decide",
+            message: "Vous avez déjà statué sur cette demande".to_string(),
+        });
+    }
+
+    let approbation = Approbation {
+        id: Uuid::new_v4(),
+        demande_id: demande.id,
+        approbateur: courant.0.id,
+        decision: decision_req.decision,
+        commentaire,
+        date: Utc::now(),
+    };
+    approbations.insert_decision(&approbation).await?;
+
+    let mut decisions = decisions;
+    decisions.push(approbation);
+    Ok(HttpResponse::Created().json(DemandeApprobationResponse {
+        id: demande.id,
+        demandeur: demande.demandeur,
+        date: demande.date,
+        invalidee_le: None,
+        statut: demande.statut(&decisions, config.approbations_production),
+        decisions: decisions.into_iter().map(ApprobationResponse::from).collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Environnement, Role};
+
+    // Travail de production de `statut`, sous la responsabilité d'un manager ; un second manager
+    // statue sur les demandes
+    struct Contexte {
+        depots: Repositories,
+        config: Config,
+        travail: Travail,
+        demandeur: String,
+        approbateur: String,
+    }
+
+    async fn contexte(statut: StatutTravail) -> Contexte {
+        let depots = Repositories::memoire();
+        let config = configuration();
+        let (responsable, demandeur) = connecter(&depots, &config, Role::Manager).await;
+        let (_, approbateur) = connecter(&depots, &config, Role::Manager).await;
+        let projet = test_support::projet();
+        depots.projets.insert(&projet).await.unwrap();
+        let travail = Travail {
+            projet_id: projet.id,
+            environnement: Environnement::Production,
+            statut,
+            responsable: responsable.id,
+            description: "Migration base de données".to_string(),
+            equipe: Vec::new(),
+            ..test_support::travail()
+        };
+        depots.travaux.insert(&travail).await.unwrap();
+        Contexte { depots, config, travail, demandeur, approbateur }
+    }
+
+    impl Contexte {
+        async fn appeler(&self, requete: TestRequest, authorization: &str) -> ServiceResponse {
+            appeler(&self.depots, &self.config, requete.insert_header(("Authorization", authorization.to_string()))).await
+        }
+
+        async fn demander(&self) -> ServiceResponse {
+            let uri = format!("/api/travaux/{}/approbations", self.travail.id);
+            self.appeler(TestRequest::post().uri(&uri), &self.demandeur).await
+        }
+
+        async fn decider(&self, authorization: &str) -> ServiceResponse {
+            let uri = format!("/api/travaux/{}/approbations/decisions", self.travail.id);
+            self.appeler(TestRequest::post().uri(&uri).set_json(json!({"decision": "approuvé"})), authorization).await
+        }
+
+        async fn demarrer(&self) -> ServiceResponse {
+            let uri = format!("/api/travaux/{}/transitions", self.travail.id);
+            self.appeler(TestRequest::post().uri(&uri).set_json(json!({"statut": "en_cours", "raison": "Fenêtre ouverte"})), &self.demandeur).await
+        }
+
+        async fn modifier(&self, patch: Value) -> ServiceResponse {
+            let uri = format!("/api/travaux/{}", self.travail.id);
+            self.appeler(TestRequest::patch().uri(&uri).set_json(patch), &self.demandeur).await
+        }
+    }
+
+    async fn code(reponse: ServiceResponse) -> String {
+        let corps: Value = test::read_body_json(reponse).await;
+        corps["code"].as_str().unwrap_or_default().to_string()
+    }
+
+    #[actix_web::test]
+    async fn nouvelle_approbation_apres_demarrage() {
+        let ctx = contexte(StatutTravail::Planifie).await;
+        assert_eq!(code(ctx.demarrer().await).await, "approbation_requise");
+        assert_eq!(ctx.demander().await.status(), StatusCode::CREATED);
+        assert_eq!(ctx.decider(&ctx.approbateur).await.status(), StatusCode::CREATED);
+        assert_eq!(ctx.demarrer().await.status(), StatusCode::OK);
+
+        // L'approbation du démarrage ne couvre pas de nouvelles dates ou un nouveau périmètre
+        let reponse = ctx.modifier(json!({"description": "Migration et réindexation"})).await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        assert_eq!(code(reponse).await, "approbation_requise");
+        // Hors périmètre approuvé, le travail démarré reste modifiable
+        assert_eq!(ctx.modifier(json!({"equipe": [ctx.travail.responsable]})).await.status(), StatusCode::OK);
+
+        // La nouvelle demande remplace l'approbation du démarrage
+        assert_eq!(ctx.demander().await.status(), StatusCode::CREATED);
+        let demandes = ctx.depots.approbations.list_demandes(ctx.travail.id).await.unwrap();
+        assert_eq!(demandes.len(), 2);
+        assert!(demandes[0].invalidee_le.is_some());
+        assert_eq!(code(ctx.modifier(json!({"description": "Migration et réindexation"})).await).await, "approbation_requise");
+
+        assert_eq!(ctx.decider(&ctx.approbateur).await.status(), StatusCode::CREATED);
+        assert_eq!(ctx.modifier(json!({"description": "Migration et réindexation"})).await.status(), StatusCode::OK);
+        // Une fois utilisée, l'approbation ne couvre pas un changement suivant
+        assert_eq!(code(ctx.modifier(json!({"description": "Réindexation seule"})).await).await, "approbation_requise");
+    }
+
+    #[actix_web::test]
+    async fn travail_demarre_hors_api() {
+        // Travail des données initiales : déjà EnCours, sans transition ni demande
+        let ctx = contexte(StatutTravail::EnCours).await;
+        assert_eq!(ctx.modifier(json!({"equipe": [ctx.travail.responsable]})).await.status(), StatusCode::OK);
+        assert_eq!(code(ctx.modifier(json!({"description": "Autre"})).await).await, "approbation_requise");
+        assert_eq!(ctx.demander().await.status(), StatusCode::CREATED);
+        assert_eq!(ctx.decider(&ctx.approbateur).await.status(), StatusCode::CREATED);
+        assert_eq!(ctx.modifier(json!({"description": "Autre"})).await.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn demandes_et_decisions_refusees() {
+        let ctx = contexte(StatutTravail::Planifie).await;
+        assert_eq!(code(ctx.decider(&ctx.approbateur).await).await, "aucune_demande");
+        assert_eq!(ctx.demander().await.status(), StatusCode::CREATED);
+        assert_eq!(code(ctx.demander().await).await, "demande_en_cours");
+
+        let (_, specialiste) = connecter(&ctx.depots, &ctx.config, Role::Specialiste).await;
+        assert_eq!(ctx.decider(&specialiste).await.status(), StatusCode::FORBIDDEN);
+        assert_eq!(code(ctx.decider(&ctx.demandeur).await).await, "auto_approbation");
+        assert_eq!(ctx.decider(&ctx.approbateur).await.status(), StatusCode::CREATED);
+        assert_eq!(code(ctx.decider(&ctx.approbateur).await).await, "deja_decide");
+
+        // Approuvée pour un démarrage à venir : la demande reste valable
+        assert_eq!(code(ctx.demander().await).await, "demande_en_cours");
+        let demandes = ctx.depots.approbations.list_demandes(ctx.travail.id).await.unwrap();
+        assert_eq!(demandes.len(), 1);
+        assert!(demandes[0].invalidee_le.is_none());
+    }
+
+    #[actix_web::test]
+    async fn approbation_inutile_hors_production() {
+        let ctx = contexte(StatutTravail::Planifie).await;
+        let travail = Travail { environnement: Environnement::Test, ..ctx.travail.clone() };
+        assert!(ctx.depots.travaux.update(&travail).await.unwrap());
+        let reponse = ctx.demander().await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        assert_eq!(code(reponse).await, "approbation_inutile");
+    }
+}
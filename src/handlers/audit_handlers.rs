@@ -0,0 +1,168 @@
+// Journal d'audit : chaque création, modification et suppression est tracée avec son auteur
+// et la différence champ par champ ; consultable globalement ou par entité (`/{id}/historique`)
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::UtilisateurCourant;
+use crate::error::{CorpsErreur, Result};
+use crate::models::{differences, ActionAudit, ChangementChamp, EvenementAudit, TypeEntite};
+use crate::handlers::pagination::{pagination, reponse_page};
+use crate::repositories::filtres::TRI_AUDIT;
+use crate::repositories::{AuditRepository, FiltreAudit, Page};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListeAuditQuery {
+    #[serde(alias = "entity")]
+    pub entite: Option<TypeEntite>,
+    pub entite_id: Option<Uuid>,
+    pub acteur: Option<Uuid>,
+    pub action: Option<ActionAudit>,
+    pub du: Option<DateTime<Utc>>,
+    pub au: Option<DateTime<Utc>>,
+    pub sort: Option<String>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoriqueQuery {
+    pub sort: Option<String>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EvenementAuditResponse {
+    pub id: Uuid,
+    pub acteur: Option<Uuid>,
+    pub date: DateTime<Utc>,
+    pub entite: TypeEntite,
+    pub entite_id: Uuid,
+    pub action: ActionAudit,
+    pub changements: Vec<ChangementChamp>,
+}
+
+impl From<EvenementAudit> for EvenementAuditResponse {
+    fn from(e: EvenementAudit) -> Self {
+        EvenementAuditResponse {
+            id: e.id,
+            acteur: e.acteur,
+            date: e.date,
+            entite: e.entite,
+            entite_id: e.entite_id,
+            action: e.action,
+            changements: e.changements,
+        }
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/audit")
+            .route("", web::get().to(get_audit))
+    );
+}
+
+// Enregistre l'opération : création si `avant` est None, suppression si `apres` est None.
+// Une modification qui ne change aucun champ n'est pas journalisée.
+pub async fn journaliser<T: Serialize>(
+    audit: &dyn AuditRepository,
+    acteur: Option<Uuid>,
+    entite: TypeEntite,
+    entite_id: Uuid,
+    avant: Option<&T>,
+    apres: Option<&T>,
+) -> Result<()> {
+    let action = match (avant, apres) {
+        (None, _) => ActionAudit::Creation,
+        (Some(_), None) => ActionAudit::Suppression,
+        (Some(_), Some(_)) => ActionAudit::Modification,
+    };
+    journaliser_action(audit, acteur, entite, entite_id, action, avant, apres).await
+}
+
+// Action explicite : suppression logique et restauration (l'entité existe avant et après, seul son
+// champ `suppression` change), purge
+pub async fn journaliser_action<T: Serialize>(
+    audit: &dyn AuditRepository,
+    acteur: Option<Uuid>,
+    entite: TypeEntite,
+    entite_id: Uuid,
+    action: ActionAudit,
+    avant: Option<&T>,
+    apres: Option<&T>,
+) -> Result<()> {
+    let changements = differences(avant, apres);
+    if action == ActionAudit::Modification && changements.is_empty() {
+        return Ok(());
+    }
+
+    audit.insert(&EvenementAudit {
+        id: Uuid::new_v4(),
+        acteur,
+        date: Utc::now(),
+        entite,
+        entite_id,
+        action,
+        changements,
+    }).await?;
+    Ok(())
+}
+
+// Événements d'une entité, y compris après sa suppression
+pub async fn historique(
+    audit: &dyn AuditRepository,
+    entite: TypeEntite,
+    entite_id: Uuid,
+    query: &HistoriqueQuery,
+) -> Result<Page<EvenementAudit>> {
+    let pagination = pagination(query.sort.as_deref(), query.offset, query.limit, TRI_AUDIT, "date")?;
+    let filtre = FiltreAudit {
+        entite: Some(entite),
+        entite_id: Some(entite_id),
+        ..FiltreAudit::default()
+    };
+    Ok(audit.list(&filtre, &pagination).await?)
+}
+
+pub fn reponse_historique(page: Page<EvenementAudit>) -> HttpResponse {
+    reponse_page(page, EvenementAuditResponse::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/audit",
+    tag = "audit",
+    params(ListeAuditQuery),
+    responses(
+        (status = 200, description = "Événements du journal d'audit", body = Vec<EvenementAuditResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn get_audit(
+    _courant: UtilisateurCourant,
+    query: web::Query<ListeAuditQuery>,
+    audit: web::Data<dyn AuditRepository>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let pagination = pagination(query.sort.as_deref(), query.offset, query.limit, TRI_AUDIT, "date")?;
+    let filtre = FiltreAudit {
+        entite: query.entite,
+        entite_id: query.entite_id,
+        acteur: query.acteur,
+        action: query.action,
+        du: query.du,
+        au: query.au,
+    };
+    let page = audit.list(&filtre, &pagination).await?;
+
+    Ok(reponse_historique(page))
+}
@@ -0,0 +1,288 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::auth::{self, UtilisateurCourant};
+use crate::config::Config;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::handlers::utilisateur_handlers::UtilisateurResponse;
+use crate::repositories::UtilisateurRepository;
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginRequest {
+    pub email: String,
+    #[serde(alias = "mot_de_passe")]
+    pub mot_de_passe: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResponse {
+    pub token: String,
+    pub expire_le: DateTime<Utc>,
+    pub utilisateur: UtilisateurResponse,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangementMotDePasseRequest {
+    pub actuel: String,
+    pub nouveau: String,
+}
+
+// Le jeton n'est renvoyé qu'à l'émission : seule son empreinte est conservée
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JetonCalendrierResponse {
+    pub jeton: String,
+    // Chemin du flux à ajouter à l'URL du serveur dans le client iCalendar
+    pub url: String,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/auth")
+            .route("/login", web::post().to(login))
+            .route("/moi", web::get().to(get_moi))
+            .route("/mot-de-passe", web::put().to(update_mot_de_passe))
+            .route("/jeton-calendrier", web::post().to(emettre_jeton_calendrier))
+            .route("/jeton-calendrier", web::delete().to(revoquer_jeton_calendrier))
+    );
+}
+
+fn identifiants_invalides() -> ApiError {
+    ApiError::Unauthorized("Email ou mot de passe invalide".to_string())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "authentification",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Jeton bearer et utilisateur connecté", body = LoginResponse),
+        (status = 401, description = "Identifiants invalides", body = CorpsErreur),
+    ),
+    security(()),
+)]
+pub async fn login(
+    login_req: web::Json<LoginRequest>,
+    repository: web::Data<dyn UtilisateurRepository>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    let login_req = login_req.into_inner();
+    let utilisateur = repository.find_by_email(&login_req.email).await?.ok_or_else(identifiants_invalides)?;
+    let hash = repository.mot_de_passe(utilisateur.id).await?.ok_or_else(identifiants_invalides)?;
+    if !auth::verifier(login_req.mot_de_passe, hash).await? {
+        return Err(identifiants_invalides());
+    }
+    if !utilisateur.actif {
+        return Err(ApiError::Forbidden("Compte utilisateur désactivé".to_string()));
+    }
+
+    let expire_le = Utc::now() + config.duree_jetons;
+    Ok(HttpResponse::Ok().json(LoginResponse {
+        token: auth::emettre_jeton(&config.cle_jetons, utilisateur.id, expire_le),
+        expire_le,
+        utilisateur: UtilisateurResponse::from(utilisateur),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/moi",
+    tag = "authentification",
+    responses(
+        (status = 200, description = "Utilisateur connecté", body = UtilisateurResponse),
+        (status = 401, description = "Jeton absent ou invalide", body = CorpsErreur),
+    ),
+)]
+pub async fn get_moi(courant: UtilisateurCourant) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(UtilisateurResponse::from(courant.0)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/auth/mot-de-passe",
+    tag = "authentification",
+    request_body = ChangementMotDePasseRequest,
+    responses(
+        (status = 204, description = "Mot de passe modifié"),
+        (status = 401, description = "Mot de passe actuel incorrect", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn update_mot_de_passe(
+    courant: UtilisateurCourant,
+    changement_req: web::Json<ChangementMotDePasseRequest>,
+    repository: web::Data<dyn UtilisateurRepository>,
+) -> Result<HttpResponse> {
+    let changement_req = changement_req.into_inner();
+    let id = courant.0.id;
+
+    let actuel_valide = match repository.mot_de_passe(id).await? {
+        Some(hash) => auth::verifier(changement_req.actuel, hash).await?,
+        None => false,
+    };
+    if !actuel_valide {
+        return Err(Validation::champ_invalide("actuel", "invalide", "Mot de passe actuel incorrect"));
+    }
+    let mut validation = Validation::new();
+    validation.mot_de_passe("nouveau", &changement_req.nouveau);
+    validation.verifier()?;
+
+    let hash = auth::hacher(changement_req.nouveau).await?;
+    repository.set_mot_de_passe(id, &hash).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// Émet un nouveau jeton d'abonnement au calendrier ; le précédent cesse aussitôt d'être accepté
+#[utoipa::path(
+    post,
+    path = "/api/auth/jeton-calendrier",
+    tag = "authentification",
+    responses(
+        (status = 201, description = "Jeton d'abonnement émis", body = JetonCalendrierResponse),
+        (status = 401, description = "Jeton absent ou invalide", body = CorpsErreur),
+    ),
+)]
+pub async fn emettre_jeton_calendrier(
+    courant: UtilisateurCourant,
+    repository: web::Data<dyn UtilisateurRepository>,
+) -> Result<HttpResponse> {
+    let jeton = auth::nouveau_jeton_calendrier()?;
+    repository.set_jeton_calendrier(courant.0.id, Some(&auth::empreinte_jeton_calendrier(&jeton))).await?;
+    Ok(HttpResponse::Created().json(JetonCalendrierResponse {
+        url: format!("/api/calendrier.ics?jeton={}", jeton),
+        jeton,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/auth/jeton-calendrier",
+    tag = "authentification",
+    responses(
+        (status = 204, description = "Abonnement révoqué"),
+        (status = 401, description = "Jeton absent ou invalide", body = CorpsErreur),
+    ),
+)]
+pub async fn revoquer_jeton_calendrier(
+    courant: UtilisateurCourant,
+    repository: web::Data<dyn UtilisateurRepository>,
+) -> Result<HttpResponse> {
+    repository.set_jeton_calendrier(courant.0.id, None).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use chrono::Duration;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{Role, Suppression, Utilisateur};
+    use crate::repositories::Repositories;
+
+    const MOT_DE_PASSE: &str = "correct horse";
+
+    async fn compte(depots: &Repositories, config: &Config, role: Role) -> (Utilisateur, String) {
+        let (utilisateur, authorization) = connecter(depots, config, role).await;
+        depots.utilisateurs.set_mot_de_passe(utilisateur.id, &auth::hacher_mot_de_passe(MOT_DE_PASSE).unwrap()).await.unwrap();
+        (utilisateur, authorization)
+    }
+
+    fn login(email: &str, mot_de_passe: &str) -> TestRequest {
+        TestRequest::post().uri("/api/auth/login").set_json(json!({"email": email, "motDePasse": mot_de_passe}))
+    }
+
+    fn moi(authorization: &str) -> TestRequest {
+        TestRequest::get().uri("/api/auth/moi").insert_header(("Authorization", authorization.to_string()))
+    }
+
+    #[actix_web::test]
+    async fn connexion() {
+        let depots = Repositories::memoire();
+        let config = configuration();
+        let (utilisateur, _) = compte(&depots, &config, Role::Specialiste).await;
+
+        let reponse = appeler(&depots, &config, login(&utilisateur.email, MOT_DE_PASSE)).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        let corps: Value = test::read_body_json(reponse).await;
+        let authorization = format!("Bearer {}", corps["token"].as_str().unwrap());
+        let reponse = appeler(&depots, &config, moi(&authorization)).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["email"], utilisateur.email.as_str());
+
+        // Mauvais mot de passe ou email inconnu : même réponse, sans indiquer lequel est faux
+        let reponse = appeler(&depots, &config, login(&utilisateur.email, "correct hors")).await;
+        assert_eq!(reponse.status(), StatusCode::UNAUTHORIZED);
+        let mauvais: Value = test::read_body_json(reponse).await;
+        let reponse = appeler(&depots, &config, login("inconnu@entreprise.com", MOT_DE_PASSE)).await;
+        assert_eq!(reponse.status(), StatusCode::UNAUTHORIZED);
+        let inconnu: Value = test::read_body_json(reponse).await;
+        assert_eq!(mauvais, inconnu);
+
+        let desactive = Utilisateur { actif: false, ..utilisateur };
+        assert!(depots.utilisateurs.update(&desactive).await.unwrap());
+        let reponse = appeler(&depots, &config, login(&desactive.email, MOT_DE_PASSE)).await;
+        assert_eq!(reponse.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn jetons_refuses() {
+        let depots = Repositories::memoire();
+        let config = configuration();
+        let (utilisateur, authorization) = compte(&depots, &config, Role::Specialiste).await;
+        assert_eq!(appeler(&depots, &config, moi(&authorization)).await.status(), StatusCode::OK);
+
+        assert_eq!(appeler(&depots, &config, TestRequest::get().uri("/api/auth/moi")).await.status(), StatusCode::UNAUTHORIZED);
+        let jeton = authorization.strip_prefix("Bearer ").unwrap();
+        assert_eq!(appeler(&depots, &config, moi(jeton)).await.status(), StatusCode::UNAUTHORIZED);
+        let altere = format!("Bearer {}A", jeton);
+        assert_eq!(appeler(&depots, &config, moi(&altere)).await.status(), StatusCode::UNAUTHORIZED);
+        let expire = auth::emettre_jeton(&config.cle_jetons, utilisateur.id, Utc::now() - Duration::seconds(1));
+        assert_eq!(appeler(&depots, &config, moi(&format!("Bearer {}", expire))).await.status(), StatusCode::UNAUTHORIZED);
+        let autre_cle = auth::emettre_jeton(&auth::cle_aleatoire().unwrap(), utilisateur.id, Utc::now() + Duration::hours(1));
+        assert_eq!(appeler(&depots, &config, moi(&format!("Bearer {}", autre_cle))).await.status(), StatusCode::UNAUTHORIZED);
+
+        // Jeton valide d'un compte désactivé depuis son émission, puis supprimé
+        let desactive = Utilisateur { actif: false, ..utilisateur };
+        assert!(depots.utilisateurs.update(&desactive).await.unwrap());
+        let reponse = appeler(&depots, &config, moi(&authorization)).await;
+        assert_eq!(reponse.status(), StatusCode::FORBIDDEN);
+        let supprime = Utilisateur { version: desactive.version + 1, suppression: Some(Suppression { le: Utc::now(), par: None }), ..desactive };
+        assert!(depots.utilisateurs.update(&supprime).await.unwrap());
+        assert_eq!(appeler(&depots, &config, moi(&authorization)).await.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn changement_de_mot_de_passe() {
+        let depots = Repositories::memoire();
+        let config = configuration();
+        let (utilisateur, authorization) = compte(&depots, &config, Role::Conseiller).await;
+        let changer = |actuel: &str, nouveau: &str| {
+            TestRequest::put()
+                .uri("/api/auth/mot-de-passe")
+                .insert_header(("Authorization", authorization.clone()))
+                .set_json(json!({"actuel": actuel, "nouveau": nouveau}))
+        };
+
+        let reponse = appeler(&depots, &config, changer("faux mot de passe", "battery staple")).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let reponse = appeler(&depots, &config, changer(MOT_DE_PASSE, "court")).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(appeler(&depots, &config, changer(MOT_DE_PASSE, "battery staple")).await.status(), StatusCode::NO_CONTENT);
+
+        assert_eq!(appeler(&depots, &config, login(&utilisateur.email, MOT_DE_PASSE)).await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(appeler(&depots, &config, login(&utilisateur.email, "battery staple")).await.status(), StatusCode::OK);
+    }
+}
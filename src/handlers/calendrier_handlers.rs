@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use utoipa::IntoParams;
+
+use crate::auth::{self, UtilisateurCourant};
+use crate::calendrier;
+use crate::error::{CorpsErreur, Result};
+use crate::models::{Application, Environnement};
+use crate::repositories::{FiltreTravaux, Pagination, TravailRepository, UtilisateurRepository};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CalendrierQuery {
+    pub projet_id: Option<Uuid>,
+    // Travaux dont l'utilisateur est responsable ou membre de l'équipe
+    pub utilisateur: Option<Uuid>,
+    pub application: Option<Application>,
+    pub environnement: Option<Environnement>,
+    pub du: Option<DateTime<Utc>>,
+    pub au: Option<DateTime<Utc>>,
+    // Jeton d'abonnement (POST /api/auth/jeton-calendrier), pour les clients iCalendar qui ne
+    // peuvent pas envoyer de jeton bearer
+    pub jeton: Option<String>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/calendrier.ics", web::get().to(get_calendrier));
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/calendrier.ics",
+    tag = "calendrier",
+    params(CalendrierQuery),
+    responses(
+        (status = 200, description = "Flux iCalendar des travaux", body = String, content_type = "text/calendar"),
+        (status = 401, description = "Jeton bearer ou d'abonnement absent, invalide ou révoqué", body = CorpsErreur),
+        (status = 403, description = "Compte utilisateur désactivé", body = CorpsErreur),
+    ),
+    security((), ("bearer" = [])),
+)]
+pub async fn get_calendrier(
+    req: HttpRequest,
+    query: web::Query<CalendrierQuery>,
+    travaux: web::Data<dyn TravailRepository>,
+    utilisateurs: web::Data<dyn UtilisateurRepository>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    match &query.jeton {
+        Some(jeton) => auth::abonne_calendrier(utilisateurs.as_ref(), jeton).await?,
+        None => UtilisateurCourant::extract(&req).await?.0,
+    };
+    let filtre = FiltreTravaux {
+        projet_id: query.projet_id,
+        application: query.application,
+        environnement: query.environnement,
+        du: query.du,
+        au: query.au,
+        ..FiltreTravaux::default()
+    };
+    let mut travaux = travaux.list(&filtre, &Pagination::tout("date_debut")).await?.elements;
+    if let Some(utilisateur) = query.utilisateur {
+        travaux.retain(|t| t.responsable == utilisateur || t.equipe.contains(&utilisateur));
+    }
+
+    let mut participants = HashMap::new();
+    for id in travaux.iter().flat_map(|t| std::iter::once(&t.responsable).chain(&t.equipe)) {
+        if participants.contains_key(id) {
+            continue;
+        }
+        if let Some(utilisateur) = utilisateurs.find(*id).await? {
+            participants.insert(*id, utilisateur);
+        }
+    }
+
+    let flux = calendrier::generer("Travaux", &travaux, &participants, Utc::now());
+    Ok(HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header(("Content-Disposition", "inline; filename=\"travaux.ics\""))
+        .body(flux))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::Value;
+
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Role, Travail, Utilisateur};
+    use crate::repositories::Repositories;
+
+    #[actix_web::test]
+    async fn abonnement_par_jeton() {
+        let depots = Repositories::memoire();
+        let configuration = configuration();
+        let (utilisateur, authorization) = connecter(&depots, &configuration, Role::Specialiste).await;
+        let emettre = || TestRequest::post().uri("/api/auth/jeton-calendrier").insert_header(("Authorization", authorization.clone()));
+        let flux = |url: &str| TestRequest::get().uri(url);
+
+        // Ni jeton bearer ni jeton d'abonnement
+        assert_eq!(appeler(&depots, &configuration, flux("/api/calendrier.ics")).await.status(), StatusCode::UNAUTHORIZED);
+
+        let reponse = appeler(&depots, &configuration, emettre()).await;
+        assert_eq!(reponse.status(), StatusCode::CREATED);
+        let premier: Value = test::read_body_json(reponse).await;
+        let premier = premier["url"].as_str().unwrap().to_string();
+        let reponse = appeler(&depots, &configuration, flux(&premier)).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        assert!(test::read_body(reponse).await.starts_with(b"BEGIN:VCALENDAR"));
+
+        // Un nouveau jeton remplace le précédent
+        let second: Value = test::read_body_json(appeler(&depots, &configuration, emettre()).await).await;
+        let second = second["url"].as_str().unwrap().to_string();
+        assert_eq!(appeler(&depots, &configuration, flux(&premier)).await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(appeler(&depots, &configuration, flux(&second)).await.status(), StatusCode::OK);
+
+        // Compte désactivé, puis abonnement révoqué
+        let desactive = Utilisateur { actif: false, ..utilisateur.clone() };
+        assert!(depots.utilisateurs.update(&desactive).await.unwrap());
+        assert_eq!(appeler(&depots, &configuration, flux(&second)).await.status(), StatusCode::FORBIDDEN);
+        let actif = Utilisateur { actif: true, version: desactive.version + 1, ..utilisateur };
+        assert!(depots.utilisateurs.update(&actif).await.unwrap());
+        let revoquer = TestRequest::delete().uri("/api/auth/jeton-calendrier").insert_header(("Authorization", authorization.clone()));
+        assert_eq!(appeler(&depots, &configuration, revoquer).await.status(), StatusCode::NO_CONTENT);
+        assert_eq!(appeler(&depots, &configuration, flux(&second)).await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(appeler(&depots, &configuration, flux("/api/calendrier.ics?jeton=inconnu")).await.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn sequence_dans_le_flux() {
+        let depots = Repositories::memoire();
+        let configuration = configuration();
+        let (_, authorization) = connecter(&depots, &configuration, Role::Conseiller).await;
+        let travail = Travail { version: 3, ..test_support::travail() };
+        depots.travaux.insert(&travail).await.unwrap();
+
+        let requete = TestRequest::get().uri("/api/calendrier.ics").insert_header(("Authorization", authorization));
+        let reponse = appeler(&depots, &configuration, requete).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        let corps = String::from_utf8(test::read_body(reponse).await.to_vec()).unwrap();
+        assert!(corps.contains("\r\nSEQUENCE:3\r\n"));
+    }
+}
@@ -0,0 +1,525 @@
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+use crate::auth::UtilisateurCourant;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::models::{ChecklistItem, StatutChecklist, Travail, TypeEntite};
+use crate::repositories::{AuditRepository, ChecklistRepository, Repositories, TravailRepository, UtilisateurRepository};
+use crate::handlers::audit_handlers::{historique, journaliser, reponse_historique, EvenementAuditResponse, HistoriqueQuery};
+use crate::handlers::references::verifier_utilisateur_actif;
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateChecklistItemRequest {
+    pub description: String,
+    pub statut: StatutChecklist,
+    pub responsable: Uuid,
+    #[serde(alias = "date_echeance")]
+    pub date_echeance: Option<DateTime<Utc>>,
+    pub commentaires: Option<String>,
+}
+
+impl CreateChecklistItemRequest {
+    async fn valider(&self, utilisateurs: &dyn UtilisateurRepository) -> Result<Validation> {
+        let mut validation = Validation::new();
+        validation.texte("description", &self.description, usize::MAX);
+        verifier_utilisateur_actif(&mut validation, utilisateurs, self.responsable, "responsable").await?;
+        Ok(validation)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateStatutChecklistRequest {
+    pub statut: StatutChecklist,
+    pub commentaires: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderChecklistRequest {
+    pub items: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItemResponse {
+    pub id: Uuid,
+    pub travail_id: Uuid,
+    pub description: String,
+    pub statut: StatutChecklist,
+    pub responsable: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_echeance: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commentaires: Option<String>,
+    pub ordre: i32,
+}
+
+impl From<ChecklistItem> for ChecklistItemResponse {
+    fn from(i: ChecklistItem) -> Self {
+        ChecklistItemResponse {
+            id: i.id,
+            travail_id: i.travail_id,
+            description: i.description,
+            statut: i.statut,
+            responsable: i.responsable,
+            date_echeance: i.date_echeance,
+            commentaires: i.commentaires,
+            ordre: i.ordre,
+        }
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/travaux/{travail_id}/checklist")
+            .route("", web::get().to(get_checklist))
+            .route("", web::post().to(create_checklist_item))
+            .route("/ordre", web::put().to(reorder_checklist))
+            .route("/{item_id}", web::put().to(update_checklist_item))
+            .route("/{item_id}", web::delete().to(delete_checklist_item))
+            .route("/{item_id}/statut", web::put().to(update_statut_checklist_item))
+            .route("/{item_id}/historique", web::get().to(get_historique_checklist_item))
+    );
+}
+
+fn travail_non_trouve() -> ApiError {
+    ApiError::NotFound("Travail non trouvé".to_string())
+}
+
+fn item_non_trouve() -> ApiError {
+    ApiError::NotFound("Item de checklist non trouvé".to_string())
+}
+
+// Charge le travail en vérifiant que l'utilisateur peut modifier sa checklist
+async fn travail_modifiable(
+    travaux: &dyn TravailRepository,
+    courant: &UtilisateurCourant,
+    travail_id: Uuid,
+) -> Result<Travail> {
+    let travail = travaux.find(travail_id).await?.ok_or_else(travail_non_trouve)?;
+    courant.exiger_modification(&travail)?;
+    Ok(travail)
+}
+
+// Charge l'item en vérifiant qu'il appartient bien au travail de l'URL
+async fn find_item(
+    checklist: &dyn ChecklistRepository,
+    travail_id: Uuid,
+    item_id: Uuid,
+) -> Result<Option<ChecklistItem>> {
+    Ok(checklist.find(item_id).await?
+        .filter(|i| i.travail_id == travail_id))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{travail_id}/checklist",
+    tag = "checklist",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    responses(
+        (status = 200, description = "Items de la checklist, dans l'ordre", body = Vec<ChecklistItemResponse>),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_checklist(
+    _courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    travaux: web::Data<dyn TravailRepository>,
+    checklist: web::Data<dyn ChecklistRepository>,
+) -> Result<HttpResponse> {
+    let travail_id = travail_id.into_inner();
+    if travaux.find(travail_id).await?.is_none() {
+        return Err(travail_non_trouve());
+    }
+
+    let items: Vec<ChecklistItemResponse> = checklist.list_by_travail(travail_id).await?
+        .into_iter()
+        .map(ChecklistItemResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(items))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/travaux/{travail_id}/checklist",
+    tag = "checklist",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    request_body = CreateChecklistItemRequest,
+    responses(
+        (status = 201, description = "Item créé", body = ChecklistItemResponse),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn create_checklist_item(
+    courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    item_req: web::Json<CreateChecklistItemRequest>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, checklist, utilisateurs, audit, .. } = &*unite;
+    let travail_id = travail_id.into_inner();
+    travail_modifiable(travaux.as_ref(), &courant, travail_id).await?;
+    item_req.valider(utilisateurs.as_ref()).await?.verifier()?;
+
+    // Le nouvel item est ajouté en fin de checklist
+    let ordre = checklist.list_by_travail(travail_id).await?
+        .iter()
+        .map(|i| i.ordre + 1)
+        .max()
+        .unwrap_or(0);
+
+    let item_req = item_req.into_inner();
+    let item = ChecklistItem {
+        id: Uuid::new_v4(),
+        travail_id,
+        description: item_req.description,
+        statut: item_req.statut,
+        responsable: item_req.responsable,
+        date_echeance: item_req.date_echeance,
+        commentaires: item_req.commentaires,
+        ordre,
+        suppression: None,
+    };
+
+    checklist.insert(&item).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item.id, None, Some(&item)).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::Created().json(ChecklistItemResponse::from(item)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/travaux/{travail_id}/checklist/{item_id}",
+    tag = "checklist",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail"), ("item_id" = Uuid, Path, description = "Identifiant de l'item")),
+    request_body = CreateChecklistItemRequest,
+    responses(
+        (status = 200, description = "Item modifié", body = ChecklistItemResponse),
+        (status = 404, description = "Item non trouvé", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn update_checklist_item(
+    courant: UtilisateurCourant,
+    path: web::Path<(Uuid, Uuid)>,
+    item_req: web::Json<CreateChecklistItemRequest>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, checklist, utilisateurs, audit, .. } = &*unite;
+    let (travail_id, item_id) = path.into_inner();
+    travail_modifiable(travaux.as_ref(), &courant, travail_id).await?;
+    let Some(existing) = find_item(checklist.as_ref(), travail_id, item_id).await? else {
+        return Err(item_non_trouve());
+    };
+    item_req.valider(utilisateurs.as_ref()).await?.verifier()?;
+
+    let item_req = item_req.into_inner();
+    let item = ChecklistItem {
+        description: item_req.description,
+        statut: item_req.statut,
+        responsable: item_req.responsable,
+        date_echeance: item_req.date_echeance,
+        commentaires: item_req.commentaires,
+        ..existing.clone()
+    };
+
+    checklist.update(&item).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item_id, Some(&existing), Some(&item)).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::Ok().json(ChecklistItemResponse::from(item)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/travaux/{travail_id}/checklist/{item_id}/statut",
+    tag = "checklist",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail"), ("item_id" = Uuid, Path, description = "Identifiant de l'item")),
+    request_body = UpdateStatutChecklistRequest,
+    responses(
+        (status = 200, description = "Statut modifié", body = ChecklistItemResponse),
+        (status = 404, description = "Item non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn update_statut_checklist_item(
+    courant: UtilisateurCourant,
+    path: web::Path<(Uuid, Uuid)>,
+    statut_req: web::Json<UpdateStatutChecklistRequest>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, checklist, audit, .. } = &*unite;
+    let (travail_id, item_id) = path.into_inner();
+    travail_modifiable(travaux.as_ref(), &courant, travail_id).await?;
+    let Some(mut item) = find_item(checklist.as_ref(), travail_id, item_id).await? else {
+        return Err(item_non_trouve());
+    };
+
+    let avant = item.clone();
+    let statut_req = statut_req.into_inner();
+    item.statut = statut_req.statut;
+    if statut_req.commentaires.is_some() {
+        item.commentaires = statut_req.commentaires;
+    }
+
+    checklist.update(&item).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item_id, Some(&avant), Some(&item)).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::Ok().json(ChecklistItemResponse::from(item)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/travaux/{travail_id}/checklist/{item_id}",
+    tag = "checklist",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail"), ("item_id" = Uuid, Path, description = "Identifiant de l'item")),
+    responses(
+        (status = 204, description = "Item supprimé"),
+        (status = 404, description = "Item non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn delete_checklist_item(
+    courant: UtilisateurCourant,
+    path: web::Path<(Uuid, Uuid)>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, checklist, audit, .. } = &*unite;
+    let (travail_id, item_id) = path.into_inner();
+    travail_modifiable(travaux.as_ref(), &courant, travail_id).await?;
+    let Some(item) = find_item(checklist.as_ref(), travail_id, item_id).await? else {
+        return Err(item_non_trouve());
+    };
+
+    checklist.delete(item_id).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item_id, Some(&item), None).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/travaux/{travail_id}/checklist/ordre",
+    tag = "checklist",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    request_body = ReorderChecklistRequest,
+    responses(
+        (status = 200, description = "Items dans le nouvel ordre", body = Vec<ChecklistItemResponse>),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn reorder_checklist(
+    courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    ordre_req: web::Json<ReorderChecklistRequest>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, checklist, audit, .. } = &*unite;
+    let travail_id = travail_id.into_inner();
+    travail_modifiable(travaux.as_ref(), &courant, travail_id).await?;
+
+    // La nouvelle liste doit contenir exactement les items existants, chacun une fois
+    let existants = checklist.list_by_travail(travail_id).await?;
+    let mut demandes = ordre_req.items.clone();
+    demandes.sort();
+    demandes.dedup();
+    let mut actuels: Vec<Uuid> = existants.iter().map(|i| i.id).collect();
+    actuels.sort();
+    if demandes.len() != ordre_req.items.len() || demandes != actuels {
+        return Err(ApiError::BadRequest(
+            "La liste doit contenir chaque item de la checklist exactement une fois".to_string()
+        ));
+    }
+
+    checklist.reorder(travail_id, &ordre_req.items).await?;
+
+    let items = checklist.list_by_travail(travail_id).await?;
+    for item in &items {
+        let avant = existants.iter().find(|e| e.id == item.id);
+        journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item.id, avant, Some(item)).await?;
+    }
+    unite.valider().await?;
+
+    let items: Vec<ChecklistItemResponse> = items.into_iter().map(ChecklistItemResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(items))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{travail_id}/checklist/{item_id}/historique",
+    tag = "checklist",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail"), ("item_id" = Uuid, Path, description = "Identifiant de l'item"), HistoriqueQuery),
+    responses(
+        (status = 200, description = "Historique des modifications", body = Vec<EvenementAuditResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 404, description = "Item non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_historique_checklist_item(
+    _courant: UtilisateurCourant,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<HistoriqueQuery>,
+    checklist: web::Data<dyn ChecklistRepository>,
+    audit: web::Data<dyn AuditRepository>,
+) -> Result<HttpResponse> {
+    let (travail_id, item_id) = path.into_inner();
+    let page = historique(audit.as_ref(), TypeEntite::ChecklistItem, item_id, &query).await?;
+    if page.total == 0 && find_item(checklist.as_ref(), travail_id, item_id).await?.is_none() {
+        return Err(item_non_trouve());
+    }
+
+    Ok(reponse_historique(page))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::config::Config;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Role, Utilisateur};
+
+    // Travail vide et deux utilisateurs connectés : un admin et un spécialiste non affecté
+    struct Contexte {
+        depots: Repositories,
+        config: Config,
+        admin: Utilisateur,
+        authorization: String,
+        specialiste: Utilisateur,
+        authorization_specialiste: String,
+        travail: Travail,
+    }
+
+    impl Contexte {
+        async fn nouveau() -> Self {
+            let depots = Repositories::memoire();
+            let config = configuration();
+            let (admin, authorization) = connecter(&depots, &config, Role::Admin).await;
+            let (specialiste, authorization_specialiste) = connecter(&depots, &config, Role::Specialiste).await;
+            let travail = test_support::travail();
+            depots.travaux.insert(&travail).await.unwrap();
+            Contexte { depots, config, admin, authorization, specialiste, authorization_specialiste, travail }
+        }
+
+        async fn appeler(&self, requete: TestRequest) -> ServiceResponse {
+            appeler(&self.depots, &self.config, requete.insert_header(("Authorization", self.authorization.clone()))).await
+        }
+
+        async fn creer(&self, description: &str) -> ChecklistItem {
+            let uri = format!("/api/travaux/{}/checklist", self.travail.id);
+            let corps = json!({"description": description, "statut": "non_démarré", "responsable": self.admin.id});
+            let reponse = self.appeler(TestRequest::post().uri(&uri).set_json(corps)).await;
+            assert_eq!(reponse.status(), StatusCode::CREATED);
+            test::read_body_json(reponse).await
+        }
+
+        async fn descriptions(&self) -> Vec<String> {
+            let uri = format!("/api/travaux/{}/checklist", self.travail.id);
+            let items: Vec<ChecklistItem> = test::read_body_json(self.appeler(TestRequest::get().uri(&uri)).await).await;
+            items.into_iter().map(|i| i.description).collect()
+        }
+    }
+
+    #[actix_web::test]
+    async fn cycle_de_vie_de_la_checklist() {
+        let ctx = Contexte::nouveau().await;
+        let sauvegarde = ctx.creer("Sauvegarde").await;
+        let arret = ctx.creer("Arrêt des services").await;
+        let controle = ctx.creer("Contrôle").await;
+        assert_eq!((sauvegarde.ordre, arret.ordre, controle.ordre), (0, 1, 2));
+
+        let uri = format!("/api/travaux/{}/checklist/{}/statut", ctx.travail.id, sauvegarde.id);
+        let reponse = ctx.appeler(TestRequest::put().uri(&uri).set_json(json!({"statut": "terminé", "commentaires": "OK"}))).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        let item: ChecklistItem = test::read_body_json(reponse).await;
+        assert_eq!((item.statut, item.commentaires.as_deref()), (StatutChecklist::Termine, Some("OK")));
+
+        let uri = format!("/api/travaux/{}/checklist/ordre", ctx.travail.id);
+        let reponse = ctx.appeler(TestRequest::put().uri(&uri).set_json(json!({"items": [arret.id, sauvegarde.id, controle.id]}))).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        assert_eq!(ctx.descriptions().await, ["Arrêt des services", "Sauvegarde", "Contrôle"]);
+
+        let uri = format!("/api/travaux/{}/checklist/{}", ctx.travail.id, arret.id);
+        assert_eq!(ctx.appeler(TestRequest::delete().uri(&uri)).await.status(), StatusCode::NO_CONTENT);
+        assert_eq!(ctx.descriptions().await, ["Sauvegarde", "Contrôle"]);
+
+        // L'historique survit à la suppression : création, réordonnancement, suppression
+        let uri = format!("/api/travaux/{}/checklist/{}/historique", ctx.travail.id, arret.id);
+        let reponse = ctx.appeler(TestRequest::get().uri(&uri)).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        let evenements: Vec<Value> = test::read_body_json(reponse).await;
+        assert_eq!(evenements.len(), 3);
+
+        // Un membre de l'équipe du travail peut aussi tenir la checklist
+        let travail = Travail { equipe: vec![ctx.specialiste.id], ..ctx.travail.clone() };
+        ctx.depots.travaux.update(&travail).await.unwrap();
+        let uri = format!("/api/travaux/{}/checklist/{}/statut", ctx.travail.id, controle.id);
+        let requete = TestRequest::put().uri(&uri).set_json(json!({"statut": "en_cours"}));
+        let reponse = appeler(&ctx.depots, &ctx.config, requete.insert_header(("Authorization", ctx.authorization_specialiste.clone()))).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn modifications_refusees() {
+        let ctx = Contexte::nouveau().await;
+        let item = ctx.creer("Sauvegarde").await;
+        let uri = format!("/api/travaux/{}/checklist", ctx.travail.id);
+
+        // Toutes les erreurs de champ sont renvoyées ensemble
+        let corps = json!({"description": " ", "statut": "non_démarré", "responsable": Uuid::new_v4()});
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(corps)).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let corps: Value = test::read_body_json(reponse).await;
+        let champs: Vec<&str> = corps["erreurs"].as_array().unwrap().iter().map(|e| e["field"].as_str().unwrap()).collect();
+        assert_eq!(champs, ["description", "responsable"]);
+
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(json!({"description": "Sans statut"}))).await;
+        assert_eq!(reponse.status(), StatusCode::BAD_REQUEST);
+
+        // Spécialiste qui n'est ni responsable ni membre de l'équipe du travail
+        let corps = json!({"description": "Intrus", "statut": "non_démarré", "responsable": ctx.specialiste.id});
+        let requete = TestRequest::post().uri(&uri).set_json(corps).insert_header(("Authorization", ctx.authorization_specialiste.clone()));
+        let reponse = appeler(&ctx.depots, &ctx.config, requete).await;
+        assert_eq!(reponse.status(), StatusCode::FORBIDDEN);
+
+        // Un item n'est accessible que sous son propre travail
+        let autre = test_support::travail();
+        ctx.depots.travaux.insert(&autre).await.unwrap();
+        let uri = format!("/api/travaux/{}/checklist/{}/statut", autre.id, item.id);
+        let reponse = ctx.appeler(TestRequest::put().uri(&uri).set_json(json!({"statut": "terminé"}))).await;
+        assert_eq!(reponse.status(), StatusCode::NOT_FOUND);
+        let uri = format!("/api/travaux/{}/checklist", Uuid::new_v4());
+        assert_eq!(ctx.appeler(TestRequest::get().uri(&uri)).await.status(), StatusCode::NOT_FOUND);
+
+        // Le nouvel ordre doit reprendre chaque item exactement une fois
+        let second = ctx.creer("Contrôle").await;
+        let uri = format!("/api/travaux/{}/checklist/ordre", ctx.travail.id);
+        for items in [vec![item.id], vec![item.id, item.id, second.id], vec![item.id, Uuid::new_v4()]] {
+            let reponse = ctx.appeler(TestRequest::put().uri(&uri).set_json(json!({"items": items}))).await;
+            assert_eq!(reponse.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(ctx.descriptions().await, ["Sauvegarde", "Contrôle"]);
+        assert_eq!(ctx.depots.checklist.find(item.id).await.unwrap().unwrap().statut, StatutChecklist::NonDemarre);
+    }
+}
@@ -0,0 +1,109 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::auth::UtilisateurCourant;
+use crate::config::{Config, PolitiqueConflits};
+use crate::conflits::{conflits_travail, tous_les_conflits, Conflit, TypeConflit};
+use crate::error::{ApiError, Result};
+use crate::models::{Application, Environnement, Travail};
+use crate::repositories::{FiltreTravaux, Pagination, TravailRepository};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListeConflitsQuery {
+    pub type_conflit: Option<TypeConflit>,
+    // Restreint l'analyse aux travaux de cette application / de cet environnement
+    pub application: Option<Application>,
+    pub environnement: Option<Environnement>,
+    // Restreint l'analyse aux travaux qui chevauchent [du, au]
+    pub du: Option<DateTime<Utc>>,
+    pub au: Option<DateTime<Utc>>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/conflits")
+            .route("", web::get().to(get_conflits))
+    );
+}
+
+// Conflits du travail (pas encore enregistré ou modifié) avec les travaux existants
+pub async fn detecter_conflits(travail: &Travail, travaux: &dyn TravailRepository) -> Result<Vec<Conflit>> {
+    if !travail.statut.est_actif() {
+        return Ok(Vec::new());
+    }
+    let meme_environnement = FiltreTravaux {
+        application: Some(travail.application.clone()),
+        environnement: Some(travail.environnement.clone()),
+        du: Some(travail.date_debut),
+        au: Some(travail.date_fin_prevue),
+        ..FiltreTravaux::default()
+    };
+    let meme_responsable = FiltreTravaux {
+        responsable: Some(travail.responsable),
+        du: Some(travail.date_debut),
+        au: Some(travail.date_fin_prevue),
+        ..FiltreTravaux::default()
+    };
+
+    let tout = Pagination::tout("date_debut");
+    let mut candidats = travaux.list(&meme_environnement, &tout).await?.elements;
+    candidats.extend(travaux.list(&meme_responsable, &tout).await?.elements);
+    candidats.sort_by_key(|t| t.id);
+    candidats.dedup_by_key(|t| t.id);
+
+    let mut conflits = conflits_travail(travail, &candidats);
+    conflits.sort_by_key(|c| (c.debut, c.autre_travail_id));
+    Ok(conflits)
+}
+
+// Applique la politique configurée : en mode Bloquer, seuls les conflits absents de la version
+// précédente (`avant`) sont refusés, pour ne pas empêcher de corriger un travail déjà en conflit
+pub fn appliquer_politique(config: &Config, conflits: &[Conflit], avant: &[Conflit]) -> Result<()> {
+    if config.conflits_planning == PolitiqueConflits::Avertir {
+        return Ok(());
+    }
+    let nouveaux: Vec<Conflit> = conflits.iter()
+        .filter(|c| !avant.iter().any(|a| a.type_conflit == c.type_conflit && a.autre_travail_id == c.autre_travail_id))
+        .cloned()
+        .collect();
+    if nouveaux.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::ConflitsPlanning(nouveaux))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/conflits",
+    tag = "conflits",
+    params(ListeConflitsQuery),
+    responses(
+        (status = 200, description = "Conflits de planning entre travaux actifs", body = Vec<Conflit>),
+    ),
+)]
+pub async fn get_conflits(
+    _courant: UtilisateurCourant,
+    query: web::Query<ListeConflitsQuery>,
+    travaux: web::Data<dyn TravailRepository>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let filtre = FiltreTravaux {
+        application: query.application,
+        environnement: query.environnement,
+        du: query.du,
+        au: query.au,
+        ..FiltreTravaux::default()
+    };
+    let travaux = travaux.list(&filtre, &Pagination::tout("date_debut")).await?.elements;
+
+    let conflits: Vec<Conflit> = tous_les_conflits(&travaux)
+        .into_iter()
+        .filter(|c| query.type_conflit.is_none_or(|t| t == c.type_conflit))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(conflits))
+}
@@ -0,0 +1,157 @@
+// Corbeille : les projets, travaux, utilisateurs et items de checklist supprimés le sont
+// logiquement et restent restaurables ; la purge les supprime définitivement une fois la période
+// de rétention écoulée
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::UtilisateurCourant;
+use crate::config::Config;
+use crate::error::{CorpsErreur, Result};
+use crate::handlers::audit_handlers::{journaliser, journaliser_action};
+use crate::handlers::dependance_handlers::supprimer_dependances;
+use crate::models::{ActionAudit, Travail, TypeEntite};
+use crate::permissions::Permission;
+use crate::repositories::{FiltreProjets, FiltreTravaux, FiltreUtilisateurs, Pagination, Repositories, Supprimes};
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeResponse {
+    // Seuls les éléments supprimés avant cette date (maintenant moins la rétention) sont purgés
+    pub supprimes_avant: DateTime<Utc>,
+    pub travaux: usize,
+    pub projets: usize,
+    pub utilisateurs: usize,
+    pub items_checklist: usize,
+    // Éléments échus conservés car encore référencés par des travaux ou des items de checklist
+    pub conserves: usize,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/corbeille/purge", web::post().to(purger));
+}
+
+// `?inclure_supprimes=true` sur une liste : réservé aux détenteurs de GererCorbeille
+pub fn supprimes_listes(courant: &UtilisateurCourant, inclure_supprimes: Option<bool>) -> Result<Supprimes> {
+    if inclure_supprimes == Some(true) {
+        courant.exiger(Permission::GererCorbeille)?;
+        Ok(Supprimes::Inclus)
+    } else {
+        Ok(Supprimes::Exclus)
+    }
+}
+
+// Supprime définitivement un travail avec sa checklist, ses approbations, ses dérogations et ses
+// dépendances, et en détache les travaux (même supprimés) qui en étaient la promotion
+pub async fn purger_travail(
+    travail: &Travail,
+    acteur: Uuid,
+    depots: &Repositories,
+) -> Result<bool> {
+    let travaux = depots.travaux.as_ref();
+    let checklist = depots.checklist.as_ref();
+    let approbations = depots.approbations.as_ref();
+    let gels = depots.gels.as_ref();
+    let dependances = depots.dependances.as_ref();
+    let audit = depots.audit.as_ref();
+    for item in checklist.list_by_travail(travail.id).await? {
+        journaliser(audit, Some(acteur), TypeEntite::ChecklistItem, item.id, Some(&item), None).await?;
+    }
+    checklist.delete_by_travail(travail.id).await?;
+    approbations.delete_by_travail(travail.id).await?;
+    gels.delete_derogations_by_travail(travail.id).await?;
+    supprimer_dependances(travail.id, acteur, dependances, audit).await?;
+    let promotions = FiltreTravaux {
+        promotion_de: Some(travail.id),
+        supprimes: Supprimes::Inclus,
+        ..FiltreTravaux::default()
+    };
+    for promotion in travaux.list(&promotions, &Pagination::tout("date_debut")).await?.elements {
+        let detache = Travail { promotion_de: None, ..promotion.clone() };
+        travaux.update(&detache).await?;
+        journaliser(audit, Some(acteur), TypeEntite::Travail, detache.id, Some(&promotion), Some(&detache)).await?;
+    }
+
+    let purge = travaux.delete(travail.id).await?;
+    if purge {
+        journaliser_action(audit, Some(acteur), TypeEntite::Travail, travail.id, ActionAudit::Purge, Some(travail), None).await?;
+    }
+    Ok(purge)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/corbeille/purge",
+    tag = "corbeille",
+    responses(
+        (status = 200, description = "Nombre d'éléments supprimés définitivement", body = PurgeResponse),
+        (status = 403, description = "Permission gerer_corbeille manquante", body = CorpsErreur),
+    ),
+)]
+pub async fn purger(
+    courant: UtilisateurCourant,
+    depots: Repositories,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererCorbeille)?;
+    let unite = depots.transaction().await?;
+    let Repositories { projets, travaux, utilisateurs, checklist, audit, .. } = &*unite;
+    let acteur = courant.0.id;
+    let supprimes_avant = Utc::now() - config.retention_suppressions;
+    let echus = Supprimes::Avant(supprimes_avant);
+    let mut reponse = PurgeResponse { supprimes_avant, travaux: 0, projets: 0, utilisateurs: 0, items_checklist: 0, conserves: 0 };
+
+    // Items supprimés avec leur responsable : ils référencent encore l'utilisateur
+    for item in checklist.list_supprimes_avant(supprimes_avant).await? {
+        if checklist.delete(item.id).await? {
+            journaliser_action(audit.as_ref(), Some(acteur), TypeEntite::ChecklistItem, item.id, ActionAudit::Purge, Some(&item), None).await?;
+            reponse.items_checklist += 1;
+        }
+    }
+
+    // Les travaux d'abord : ce sont eux qui référencent les projets et les utilisateurs
+    let filtre = FiltreTravaux { supprimes: echus, ..FiltreTravaux::default() };
+    for travail in travaux.list(&filtre, &Pagination::tout("date_debut")).await?.elements {
+        if purger_travail(&travail, acteur, &unite).await? {
+            reponse.travaux += 1;
+        }
+    }
+
+    let premier = Pagination { limit: Some(1), ..Pagination::tout("date_debut") };
+    let filtre = FiltreProjets { supprimes: echus, ..FiltreProjets::default() };
+    for projet in projets.list(&filtre, &Pagination::tout("code")).await?.elements {
+        let references = FiltreTravaux {
+            projet_id: Some(projet.id),
+            supprimes: Supprimes::Inclus,
+            ..FiltreTravaux::default()
+        };
+        if travaux.list(&references, &premier).await?.total > 0 {
+            reponse.conserves += 1;
+        } else if projets.delete(projet.id).await? {
+            journaliser_action(audit.as_ref(), Some(acteur), TypeEntite::Projet, projet.id, ActionAudit::Purge, Some(&projet), None).await?;
+            reponse.projets += 1;
+        }
+    }
+
+    let filtre = FiltreUtilisateurs { supprimes: echus, ..FiltreUtilisateurs::default() };
+    for utilisateur in utilisateurs.list(&filtre, &Pagination::tout("nom")).await?.elements {
+        let references = FiltreTravaux {
+            responsable: Some(utilisateur.id),
+            supprimes: Supprimes::Inclus,
+            ..FiltreTravaux::default()
+        };
+        let items = checklist.list_by_responsable(utilisateur.id).await?.len() + checklist.list_supprimes_by_responsable(utilisateur.id).await?.len();
+        if travaux.list(&references, &premier).await?.total > 0 || items > 0 {
+            reponse.conserves += 1;
+        } else if utilisateurs.delete(utilisateur.id).await? {
+            journaliser_action(audit.as_ref(), Some(acteur), TypeEntite::Utilisateur, utilisateur.id, ActionAudit::Purge, Some(&utilisateur), None).await?;
+            reponse.utilisateurs += 1;
+        }
+    }
+    unite.valider().await?;
+
+    Ok(HttpResponse::Ok().json(reponse))
+}
@@ -0,0 +1,349 @@
+// Dépendances fin → début entre travaux d'un même projet et chemin critique du projet
+
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::UtilisateurCourant;
+use crate::dependances::{chemin_critique, cree_un_cycle, respecte, CheminCritique};
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::handlers::audit_handlers::journaliser;
+use crate::models::{Dependance, StatutTravail, Travail, TypeEntite};
+use crate::repositories::{AuditRepository, DependanceRepository, ProjetRepository, Repositories, TravailRepository};
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DependanceRequest {
+    #[serde(alias = "depend_de")]
+    pub depend_de: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DependancesTravailResponse {
+    // Travaux qui doivent être terminés avant le début de celui-ci
+    pub predecesseurs: Vec<Dependance>,
+    // Travaux qui attendent la fin de celui-ci
+    pub successeurs: Vec<Dependance>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/travaux/{travail_id}/dependances")
+            .route("", web::get().to(get_dependances))
+            .route("", web::post().to(create_dependance))
+            .route("/{depend_de}", web::delete().to(delete_dependance))
+    )
+    .route("/api/projets/{projet_id}/chemin-critique", web::get().to(get_chemin_critique));
+}
+
+fn travail_non_trouve() -> ApiError {
+    ApiError::NotFound("Travail non trouvé".to_string())
+}
+
+// Modification d'un travail : ses dépendances doivent rester dans le même projet et ses dates
+// respecter l'ordre fin → début avec ses prédécesseurs et ses successeurs
+pub async fn verifier_dependances(
+    validation: &mut Validation,
+    travail: &Travail,
+    dependances: &dyn DependanceRepository,
+    travaux: &dyn TravailRepository,
+) -> Result<()> {
+    for dependance in dependances.list_by_travaux(&[travail.id]).await? {
+        let (autre_id, predecesseur) = if dependance.travail_id == travail.id {
+            (dependance.depend_de, true)
+        } else {
+            (dependance.travail_id, false)
+        };
+        let Some(autre) = travaux.find(autre_id).await? else {
+            continue;
+        };
+        if autre.projet_id != travail.projet_id {
+            validation.ajouter(
+                "projetId",
+                "dependances_existantes",
+                format!("Le travail dépend du travail {} d'un autre projet", autre.id),
+            );
+            continue;
+        }
+        let respectee = if predecesseur { respecte(travail, &autre) } else { respecte(&autre, travail) };
+        if !respectee && travail.statut != StatutTravail::Annule && autre.statut != StatutTravail::Annule {
+            let (champ, message) = if predecesseur {
+                ("dateDebut", format!("Le travail doit commencer après la fin du travail {} ({})", autre.id, autre.date_fin_prevue))
+            } else {
+                ("dateFinPrevue", format!("Le travail doit finir avant le début du travail {} ({})", autre.id, autre.date_debut))
+            };
+            validation.ajouter(champ, "dependance_non_respectee", message);
+        }
+    }
+    Ok(())
+}
+
+// Refuse le passage EnCours tant qu'un prédécesseur n'est pas terminé. Un prédécesseur annulé
+// ne bloque plus rien, comme pour l'ordre des dates (verifier_dependances) et le chemin critique.
+pub async fn verifier_dependances_terminees(
+    travail: &Travail,
+    vers: &StatutTravail,
+    dependances: &dyn DependanceRepository,
+    travaux: &dyn TravailRepository,
+) -> Result<()> {
+    if *vers != StatutTravail::EnCours {
+        return Ok(());
+    }
+    let mut non_termines = Vec::new();
+    for dependance in dependances.list_by_travaux(&[travail.id]).await? {
+        if dependance.travail_id != travail.id {
+            continue;
+        }
+        if let Some(predecesseur) = travaux.find(dependance.depend_de).await? {
+            if !matches!(predecesseur.statut, StatutTravail::Termine | StatutTravail::Annule) {
+                non_termines.push(format!("{} ({})", predecesseur.id, predecesseur.statut.as_str()));
+            }
+        }
+    }
+    if non_termines.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::Conflict {
+            code: "dependances_non_terminees",
+            message: format!(
+                "Le travail ne peut démarrer qu'une fois ses prédécesseurs terminés : {}",
+                non_termines.join(", ")
+            ),
+        })
+    }
+}
+
+// Supprime les dépendances vers et depuis le travail, en les journalisant
+pub async fn supprimer_dependances(
+    travail_id: Uuid,
+    acteur: Uuid,
+    dependances: &dyn DependanceRepository,
+    audit: &dyn AuditRepository,
+) -> Result<()> {
+    for dependance in dependances.list_by_travaux(&[travail_id]).await? {
+        if dependances.delete(dependance.id).await? {
+            journaliser(audit, Some(acteur), TypeEntite::Dependance, dependance.id, Some(&dependance), None).await?;
+        }
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{travail_id}/dependances",
+    tag = "dependances",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    responses(
+        (status = 200, description = "Prédécesseurs et successeurs du travail", body = DependancesTravailResponse),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_dependances(
+    _courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    travaux: web::Data<dyn TravailRepository>,
+    dependances: web::Data<dyn DependanceRepository>,
+) -> Result<HttpResponse> {
+    let travail_id = travail_id.into_inner();
+    if travaux.find(travail_id).await?.is_none() {
+        return Err(travail_non_trouve());
+    }
+
+    let (predecesseurs, successeurs) = dependances.list_by_travaux(&[travail_id]).await?
+        .into_iter()
+        .partition(|d| d.travail_id == travail_id);
+    Ok(HttpResponse::Ok().json(DependancesTravailResponse { predecesseurs, successeurs }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/travaux/{travail_id}/dependances",
+    tag = "dependances",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    request_body = DependanceRequest,
+    responses(
+        (status = 201, description = "Dépendance créée", body = Dependance),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 409, description = "Dépendance existante ou cycle", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn create_dependance(
+    courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    dependance_req: web::Json<DependanceRequest>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, dependances, audit, .. } = &*unite;
+    let Some(travail) = travaux.find(travail_id.into_inner()).await? else {
+        return Err(travail_non_trouve());
+    };
+    courant.exiger_modification(&travail)?;
+
+    let depend_de = dependance_req.into_inner().depend_de;
+    let mut validation = Validation::new();
+    let predecesseur = if depend_de == travail.id {
+        validation.ajouter("dependDe", "auto_reference", "Un travail ne peut pas dépendre de lui-même");
+        None
+    } else {
+        travaux.find(depend_de).await?
+    };
+    match &predecesseur {
+        None if depend_de != travail.id => {
+            validation.ajouter("dependDe", "inexistant", format!("Travail {} inexistant", depend_de));
+        }
+        Some(predecesseur) if predecesseur.projet_id != travail.projet_id => {
+            validation.ajouter("dependDe", "projet_different", "Les dépendances sont limitées aux travaux d'un même projet");
+        }
+        _ => {}
+    }
+    validation.verifier()?;
+
+    let ids: Vec<Uuid> = travaux.list_by_projet(travail.projet_id).await?.iter().map(|t| t.id).collect();
+    let existantes = dependances.list_by_travaux(&ids).await?;
+    if existantes.iter().any(|d| d.travail_id == travail.id && d.depend_de == depend_de) {
+        return Err(ApiError::Conflict {
+            code: "dependance_existante",
+            message: format!("Le travail dépend déjà du travail {}", depend_de),
+        });
+    }
+    if cree_un_cycle(&existantes, travail.id, depend_de) {
+        return Err(ApiError::Conflict {
+            code: "cycle_dependances",
+            message: format!("Le travail {} dépend déjà (indirectement) de ce travail", depend_de),
+        });
+    }
+    if let Some(predecesseur) = predecesseur.filter(|p| !respecte(&travail, p)) {
+        return Err(Validation::champ_invalide(
+            "dependDe",
+            "dependance_non_respectee",
+            format!("Le travail commence avant la fin du travail {} ({})", predecesseur.id, predecesseur.date_fin_prevue),
+        ));
+    }
+
+    let dependance = Dependance {
+        id: Uuid::new_v4(),
+        travail_id: travail.id,
+        depend_de,
+    };
+    dependances.insert(&dependance).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Dependance, dependance.id, None, Some(&dependance)).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::Created().json(dependance))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/travaux/{travail_id}/dependances/{depend_de}",
+    tag = "dependances",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail"), ("depend_de" = Uuid, Path, description = "Identifiant du prédécesseur")),
+    responses(
+        (status = 204, description = "Dépendance supprimée"),
+        (status = 404, description = "Dépendance non trouvée", body = CorpsErreur),
+    ),
+)]
+pub async fn delete_dependance(
+    courant: UtilisateurCourant,
+    path: web::Path<(Uuid, Uuid)>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, dependances, audit, .. } = &*unite;
+    let (travail_id, depend_de) = path.into_inner();
+    let Some(travail) = travaux.find(travail_id).await? else {
+        return Err(travail_non_trouve());
+    };
+    courant.exiger_modification(&travail)?;
+
+    let dependance = dependances.list_by_travaux(&[travail_id]).await?
+        .into_iter()
+        .find(|d| d.travail_id == travail_id && d.depend_de == depend_de);
+    match dependance {
+        Some(dependance) if dependances.delete(dependance.id).await? => {
+            journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Dependance, dependance.id, Some(&dependance), None).await?;
+            unite.valider().await?;
+            Ok(HttpResponse::NoContent().finish())
+        }
+        _ => Err(ApiError::NotFound("Dépendance non trouvée".to_string())),
+    }
+}
+
+// Chemin critique des travaux non annulés du projet
+#[utoipa::path(
+    get,
+    path = "/api/projets/{projet_id}/chemin-critique",
+    tag = "dependances",
+    params(("projet_id" = Uuid, Path, description = "Identifiant du projet")),
+    responses(
+        (status = 200, description = "Planning au plus tôt / au plus tard et chemin critique", body = CheminCritique),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_chemin_critique(
+    _courant: UtilisateurCourant,
+    projet_id: web::Path<Uuid>,
+    projets: web::Data<dyn ProjetRepository>,
+    travaux: web::Data<dyn TravailRepository>,
+    dependances: web::Data<dyn DependanceRepository>,
+) -> Result<HttpResponse> {
+    let projet_id = projet_id.into_inner();
+    if projets.find(projet_id).await?.is_none() {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    }
+
+    let travaux_projet: Vec<Travail> = travaux.list_by_projet(projet_id).await?
+        .into_iter()
+        .filter(|t| t.statut != StatutTravail::Annule)
+        .collect();
+    let ids: Vec<Uuid> = travaux_projet.iter().map(|t| t.id).collect();
+    let dependances = dependances.list_by_travaux(&ids).await?;
+
+    Ok(HttpResponse::Ok().json(chemin_critique(&travaux_projet, &dependances)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::{self, date};
+    use crate::repositories::Repositories;
+
+    #[actix_web::test]
+    async fn demarrage_selon_le_statut_des_predecesseurs() {
+        let depots = Repositories::memoire();
+        let successeur = Travail { date_debut: date(4), date_fin_prevue: date(6), ..test_support::travail() };
+        depots.travaux.insert(&successeur).await.unwrap();
+        let mut predecesseur = test_support::travail();
+        depots.travaux.insert(&predecesseur).await.unwrap();
+        depots
+            .dependances
+            .insert(&Dependance { id: Uuid::new_v4(), travail_id: successeur.id, depend_de: predecesseur.id })
+            .await
+            .unwrap();
+        let verifier = |vers: StatutTravail| {
+            let depots = depots.clone();
+            let successeur = successeur.clone();
+            async move {
+                verifier_dependances_terminees(&successeur, &vers, depots.dependances.as_ref(), depots.travaux.as_ref()).await
+            }
+        };
+
+        // Prédécesseur planifié : démarrage refusé, les autres transitions restent libres
+        match verifier(StatutTravail::EnCours).await {
+            Err(ApiError::Conflict { code, .. }) => assert_eq!(code, "dependances_non_terminees"),
+            autre => panic!("conflit attendu : {:?}", autre),
+        }
+        assert!(verifier(StatutTravail::Annule).await.is_ok());
+
+        for statut in [StatutTravail::Termine, StatutTravail::Annule] {
+            predecesseur.statut = statut;
+            assert!(depots.travaux.update(&predecesseur).await.unwrap());
+            predecesseur.version += 1;
+            assert!(verifier(StatutTravail::EnCours).await.is_ok(), "prédécesseur {:?}", predecesseur.statut);
+        }
+    }
+}
@@ -0,0 +1,559 @@
+// Périodes de gel : aucun travail ne peut être planifié sur les applications / environnements
+// visés, sauf dérogation justifiée accordée par un Admin
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::UtilisateurCourant;
+use crate::permissions::Permission;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::models::{Application, DerogationGel, Environnement, PeriodeGel, Travail, TypeEntite};
+use crate::repositories::{AuditRepository, FiltreTravaux, GelRepository, Pagination, Repositories, TravailRepository};
+use crate::handlers::audit_handlers::{historique, journaliser, reponse_historique, EvenementAuditResponse, HistoriqueQuery};
+use crate::handlers::travail_handlers::TravailResponse;
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePeriodeGelRequest {
+    #[serde(alias = "date_debut")]
+    pub date_debut: DateTime<Utc>,
+    #[serde(alias = "date_fin")]
+    pub date_fin: DateTime<Utc>,
+    #[serde(default)]
+    pub applications: Vec<Application>,
+    #[serde(default)]
+    pub environnements: Vec<Environnement>,
+    pub raison: String,
+    pub actif: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodeGelResponse {
+    pub id: Uuid,
+    pub date_debut: DateTime<Utc>,
+    pub date_fin: DateTime<Utc>,
+    pub applications: Vec<Application>,
+    pub environnements: Vec<Environnement>,
+    pub raison: String,
+    pub actif: bool,
+}
+
+impl From<PeriodeGel> for PeriodeGelResponse {
+    fn from(g: PeriodeGel) -> Self {
+        PeriodeGelResponse {
+            id: g.id,
+            date_debut: g.date_debut,
+            date_fin: g.date_fin,
+            applications: g.applications,
+            environnements: g.environnements,
+            raison: g.raison,
+            actif: g.actif,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DerogationGelResponse {
+    pub id: Uuid,
+    pub auteur: Uuid,
+    pub justification: String,
+    pub date: DateTime<Utc>,
+}
+
+impl From<DerogationGel> for DerogationGelResponse {
+    fn from(d: DerogationGel) -> Self {
+        DerogationGelResponse {
+            id: d.id,
+            auteur: d.auteur,
+            justification: d.justification,
+            date: d.date,
+        }
+    }
+}
+
+// Travail touché par un gel ; sans dérogation, il est en infraction (gel créé après sa planification)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TravailGeleResponse {
+    pub travail: TravailResponse,
+    pub derogation: Option<DerogationGelResponse>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListeGelsQuery {
+    pub actif: Option<bool>,
+}
+
+impl CreatePeriodeGelRequest {
+    fn valider(&self) -> Validation {
+        let mut validation = Validation::new();
+        validation.texte("raison", &self.raison, usize::MAX);
+        if self.date_fin <= self.date_debut {
+            validation.ajouter("dateFin", "avant_date_debut", "La date de fin doit suivre la date de début");
+        }
+        validation
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/gels")
+            .route("", web::get().to(get_gels))
+            .route("", web::post().to(create_gel))
+            .route("/{id}", web::get().to(get_gel))
+            .route("/{id}", web::put().to(update_gel))
+            .route("/{id}", web::delete().to(delete_gel))
+            .route("/{id}/travaux", web::get().to(get_travaux_gel))
+            .route("/{id}/historique", web::get().to(get_historique_gel))
+    );
+}
+
+fn gel_non_trouve() -> ApiError {
+    ApiError::NotFound("Période de gel non trouvée".to_string())
+}
+
+// Gels que le travail enfreint sans dérogation. `avant` : version enregistrée du travail,
+// dont les gels déjà chevauchés ne bloquent pas (gel créé après la planification).
+// Avec une justification, un Admin obtient les dérogations à enregistrer une fois le travail sauvegardé.
+pub async fn verifier_gels(
+    travail: &Travail,
+    avant: Option<&Travail>,
+    justification: Option<&str>,
+    courant: &UtilisateurCourant,
+    gels: &dyn GelRepository,
+) -> Result<Vec<DerogationGel>> {
+    let derogations = match avant {
+        Some(avant) => gels.list_derogations_by_travail(avant.id).await?,
+        None => Vec::new(),
+    };
+    let enfreints: Vec<PeriodeGel> = gels.list().await?
+        .into_iter()
+        .filter(|g| g.concerne(travail))
+        .filter(|g| !avant.is_some_and(|avant| g.concerne(avant)))
+        .filter(|g| !derogations.iter().any(|d| d.periode_gel_id == g.id))
+        .collect();
+    if enfreints.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let Some(justification) = justification else {
+        return Err(ApiError::PeriodesGel(enfreints));
+    };
+    courant.exiger(Permission::DerogerGel)?;
+    let mut validation = Validation::new();
+    validation.texte("justificationGel", justification, usize::MAX);
+    validation.verifier()?;
+
+    Ok(enfreints.into_iter()
+        .map(|gel| DerogationGel {
+            id: Uuid::new_v4(),
+            periode_gel_id: gel.id,
+            travail_id: travail.id,
+            auteur: courant.0.id,
+            justification: justification.trim().to_string(),
+            date: Utc::now(),
+        })
+        .collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/gels",
+    tag = "gels",
+    params(ListeGelsQuery),
+    responses(
+        (status = 200, description = "Périodes de gel", body = Vec<PeriodeGelResponse>),
+    ),
+)]
+pub async fn get_gels(
+    _courant: UtilisateurCourant,
+    query: web::Query<ListeGelsQuery>,
+    repository: web::Data<dyn GelRepository>,
+) -> Result<HttpResponse> {
+    let gels: Vec<PeriodeGelResponse> = repository.list().await?
+        .into_iter()
+        .filter(|g| query.actif.is_none_or(|actif| actif == g.actif))
+        .map(PeriodeGelResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(gels))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/gels/{id}",
+    tag = "gels",
+    params(("id" = Uuid, Path, description = "Identifiant de la période de gel")),
+    responses(
+        (status = 200, description = "Période de gel", body = PeriodeGelResponse),
+        (status = 404, description = "Période de gel non trouvée", body = CorpsErreur),
+    ),
+)]
+pub async fn get_gel(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn GelRepository>,
+) -> Result<HttpResponse> {
+    match repository.find(id.into_inner()).await? {
+        Some(gel) => Ok(HttpResponse::Ok().json(PeriodeGelResponse::from(gel))),
+        None => Err(gel_non_trouve())
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/gels",
+    tag = "gels",
+    request_body = CreatePeriodeGelRequest,
+    responses(
+        (status = 201, description = "Période créée", body = PeriodeGelResponse),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn create_gel(
+    courant: UtilisateurCourant,
+    gel_req: web::Json<CreatePeriodeGelRequest>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererGels)?;
+    let unite = depots.transaction().await?;
+    let Repositories { gels: repository, audit, .. } = &*unite;
+    let gel_req = gel_req.into_inner();
+    gel_req.valider().verifier()?;
+
+    let gel = PeriodeGel {
+        id: Uuid::new_v4(),
+        date_debut: gel_req.date_debut,
+        date_fin: gel_req.date_fin,
+        applications: gel_req.applications,
+        environnements: gel_req.environnements,
+        raison: gel_req.raison,
+        actif: gel_req.actif,
+    };
+
+    repository.insert(&gel).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::PeriodeGel, gel.id, None, Some(&gel)).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::Created().json(PeriodeGelResponse::from(gel)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/gels/{id}",
+    tag = "gels",
+    params(("id" = Uuid, Path, description = "Identifiant de la période de gel")),
+    request_body = CreatePeriodeGelRequest,
+    responses(
+        (status = 200, description = "Période modifiée", body = PeriodeGelResponse),
+        (status = 404, description = "Période de gel non trouvée", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn update_gel(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    gel_req: web::Json<CreatePeriodeGelRequest>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererGels)?;
+    let unite = depots.transaction().await?;
+    let Repositories { gels: repository, audit, .. } = &*unite;
+    let Some(existing) = repository.find(id.into_inner()).await? else {
+        return Err(gel_non_trouve());
+    };
+    let gel_req = gel_req.into_inner();
+    gel_req.valider().verifier()?;
+
+    let gel = PeriodeGel {
+        id: existing.id,
+        date_debut: gel_req.date_debut,
+        date_fin: gel_req.date_fin,
+        applications: gel_req.applications,
+        environnements: gel_req.environnements,
+        raison: gel_req.raison,
+        actif: gel_req.actif,
+    };
+
+    if repository.update(&gel).await? {
+        journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::PeriodeGel, gel.id, Some(&existing), Some(&gel)).await?;
+        unite.valider().await?;
+        Ok(HttpResponse::Ok().json(PeriodeGelResponse::from(gel)))
+    } else {
+        Err(gel_non_trouve())
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/gels/{id}",
+    tag = "gels",
+    params(("id" = Uuid, Path, description = "Identifiant de la période de gel")),
+    responses(
+        (status = 204, description = "Période supprimée"),
+        (status = 404, description = "Période de gel non trouvée", body = CorpsErreur),
+    ),
+)]
+pub async fn delete_gel(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererGels)?;
+    let unite = depots.transaction().await?;
+    let Repositories { gels: repository, audit, .. } = &*unite;
+    let Some(gel) = repository.find(id.into_inner()).await? else {
+        return Err(gel_non_trouve());
+    };
+
+    if repository.delete(gel.id).await? {
+        journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::PeriodeGel, gel.id, Some(&gel), None).await?;
+        unite.valider().await?;
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(gel_non_trouve())
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/gels/{id}/travaux",
+    tag = "gels",
+    params(("id" = Uuid, Path, description = "Identifiant de la période de gel")),
+    responses(
+        (status = 200, description = "Travaux planifiés pendant la période et leurs dérogations", body = Vec<TravailGeleResponse>),
+        (status = 404, description = "Période de gel non trouvée", body = CorpsErreur),
+    ),
+)]
+pub async fn get_travaux_gel(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn GelRepository>,
+    travaux: web::Data<dyn TravailRepository>,
+) -> Result<HttpResponse> {
+    let Some(gel) = repository.find(id.into_inner()).await? else {
+        return Err(gel_non_trouve());
+    };
+
+    let filtre = FiltreTravaux {
+        du: Some(gel.date_debut),
+        au: Some(gel.date_fin),
+        ..FiltreTravaux::default()
+    };
+    let derogations = repository.list_derogations_by_gel(gel.id).await?;
+    let touches: Vec<TravailGeleResponse> = travaux.list(&filtre, &Pagination::tout("date_debut")).await?
+        .elements
+        .into_iter()
+        .filter(|t| gel.concerne(t))
+        .map(|t| TravailGeleResponse {
+            derogation: derogations.iter()
+                .find(|d| d.travail_id == t.id)
+                .cloned()
+                .map(DerogationGelResponse::from),
+            travail: TravailResponse::from(t),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(touches))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/gels/{id}/historique",
+    tag = "gels",
+    params(("id" = Uuid, Path, description = "Identifiant de la période de gel"), HistoriqueQuery),
+    responses(
+        (status = 200, description = "Historique des modifications", body = Vec<EvenementAuditResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 404, description = "Période de gel non trouvée", body = CorpsErreur),
+    ),
+)]
+pub async fn get_historique_gel(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    query: web::Query<HistoriqueQuery>,
+    repository: web::Data<dyn GelRepository>,
+    audit: web::Data<dyn AuditRepository>,
+) -> Result<HttpResponse> {
+    let id = id.into_inner();
+    let page = historique(audit.as_ref(), TypeEntite::PeriodeGel, id, &query).await?;
+    if page.total == 0 && repository.find(id).await?.is_none() {
+        return Err(gel_non_trouve());
+    }
+
+    Ok(reponse_historique(page))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::config::Config;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Projet, Role, Utilisateur};
+
+    // Gel de l'application espresso_gfr en formation sur les deux premiers jours de test_support::date
+    struct Contexte {
+        depots: Repositories,
+        config: Config,
+        admin: Utilisateur,
+        projet: Projet,
+        gel: Value,
+    }
+
+    impl Contexte {
+        async fn nouveau() -> Self {
+            let depots = Repositories::memoire();
+            let config = configuration();
+            let (admin, authorization) = connecter(&depots, &config, Role::Admin).await;
+            let projet = test_support::projet();
+            depots.projets.insert(&projet).await.unwrap();
+            let corps = json!({
+                "dateDebut": test_support::date(0),
+                "dateFin": test_support::date(48),
+                "applications": ["espresso_gfr"],
+                "environnements": ["formation"],
+                "raison": "Clôture comptable",
+                "actif": true,
+            });
+            let requete = TestRequest::post().uri("/api/gels").set_json(corps).insert_header(("Authorization", authorization));
+            let reponse = appeler(&depots, &config, requete).await;
+            assert_eq!(reponse.status(), StatusCode::CREATED);
+            let gel = test::read_body_json(reponse).await;
+            Contexte { depots, config, admin, projet, gel }
+        }
+
+        async fn appeler(&self, role: Role, requete: TestRequest) -> ServiceResponse {
+            let (_, authorization) = connecter(&self.depots, &self.config, role).await;
+            appeler(&self.depots, &self.config, requete.insert_header(("Authorization", authorization))).await
+        }
+
+        // Création d'un travail de deux heures commençant à `heure`
+        fn travail(&self, environnement: &str, heure: i64, justification: Option<&str>) -> TestRequest {
+            TestRequest::post().uri("/api/travaux").set_json(json!({
+                "projetId": self.projet.id,
+                "type": "migration",
+                "application": "espresso_gfr",
+                "environnement": environnement,
+                "description": "Migration",
+                "dateDebut": test_support::date(heure),
+                "dateFinPrevue": test_support::date(heure + 2),
+                "statut": "planifié",
+                "responsable": self.admin.id,
+                "equipe": [],
+                "justificationGel": justification,
+            }))
+        }
+
+        async fn travaux_touches(&self) -> Vec<Value> {
+            let uri = format!("/api/gels/{}/travaux", self.gel["id"].as_str().unwrap());
+            test::read_body_json(self.appeler(Role::Conseiller, TestRequest::get().uri(&uri)).await).await
+        }
+    }
+
+    #[actix_web::test]
+    async fn derogation_accordee_par_un_admin() {
+        let ctx = Contexte::nouveau().await;
+
+        // Hors du périmètre du gel : environnement différent, ou après sa fin
+        assert_eq!(ctx.appeler(Role::Manager, ctx.travail("test", 10, None)).await.status(), StatusCode::CREATED);
+        assert_eq!(ctx.appeler(Role::Manager, ctx.travail("formation", 48, None)).await.status(), StatusCode::CREATED);
+
+        let reponse = ctx.appeler(Role::Admin, ctx.travail("formation", 10, Some("  Correctif urgent  "))).await;
+        assert_eq!(reponse.status(), StatusCode::CREATED);
+        let travail: Value = test::read_body_json(reponse).await;
+
+        let touches = ctx.travaux_touches().await;
+        assert_eq!(touches.len(), 1);
+        assert_eq!(touches[0]["travail"]["id"], travail["id"]);
+        assert_eq!(touches[0]["derogation"]["justification"], "Correctif urgent");
+
+        // Dérogation acquise : le travail se modifie ensuite sans nouvelle justification
+        let uri = format!("/api/travaux/{}", travail["id"].as_str().unwrap());
+        let reponse = ctx.appeler(Role::Manager, TestRequest::patch().uri(&uri).set_json(json!({"description": "Migration corrigée"}))).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn gel_cree_apres_la_planification() {
+        let ctx = Contexte::nouveau().await;
+        let travail = Travail {
+            projet_id: ctx.projet.id,
+            application: Application::EspressoGfr,
+            environnement: Environnement::Formation,
+            description: "Planifié avant le gel".to_string(),
+            date_debut: test_support::date(60),
+            date_fin_prevue: test_support::date(62),
+            responsable: ctx.admin.id,
+            equipe: Vec::new(),
+            ..test_support::travail()
+        };
+        ctx.depots.travaux.insert(&travail).await.unwrap();
+        let uri = format!("/api/gels/{}", ctx.gel["id"].as_str().unwrap());
+        let corps = json!({"dateDebut": test_support::date(0), "dateFin": test_support::date(72), "environnements": ["formation"], "raison": "Prolongé", "actif": true});
+        assert_eq!(ctx.appeler(Role::Admin, TestRequest::put().uri(&uri).set_json(corps)).await.status(), StatusCode::OK);
+
+        // En infraction (sans dérogation), mais modifiable tant qu'il ne s'enfonce pas dans un autre gel
+        let touches = ctx.travaux_touches().await;
+        assert_eq!((touches.len(), &touches[0]["derogation"]), (1, &Value::Null));
+        let uri = format!("/api/travaux/{}", travail.id);
+        let reponse = ctx.appeler(Role::Manager, TestRequest::patch().uri(&uri).set_json(json!({"description": "Toujours prévu"}))).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+
+        let corps = json!({"dateDebut": test_support::date(80), "dateFin": test_support::date(90), "raison": "Inventaire", "actif": true});
+        assert_eq!(ctx.appeler(Role::Admin, TestRequest::post().uri("/api/gels").set_json(corps)).await.status(), StatusCode::CREATED);
+        let deplacement = json!({"dateDebut": test_support::date(80), "dateFinPrevue": test_support::date(82)});
+        let reponse = ctx.appeler(Role::Manager, TestRequest::patch().uri(&uri).set_json(deplacement)).await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+    }
+
+    #[actix_web::test]
+    async fn gels_et_derogations_refuses() {
+        let ctx = Contexte::nouveau().await;
+
+        let reponse = ctx.appeler(Role::Manager, ctx.travail("formation", 10, None)).await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["code"], "periode_gel");
+        assert_eq!(corps["periodes"][0]["id"], ctx.gel["id"]);
+
+        // Dérogation réservée à DerogerGel, et justifiée
+        let reponse = ctx.appeler(Role::Manager, ctx.travail("formation", 10, Some("Urgent"))).await;
+        assert_eq!(reponse.status(), StatusCode::FORBIDDEN);
+        let reponse = ctx.appeler(Role::Admin, ctx.travail("formation", 10, Some("   "))).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["erreurs"][0]["field"], "justificationGel");
+        assert!(ctx.travaux_touches().await.is_empty());
+
+        // Gestion des gels : permission GererGels, raison et période valides
+        let corps = json!({"dateDebut": test_support::date(10), "dateFin": test_support::date(10), "raison": " ", "actif": true});
+        let reponse = ctx.appeler(Role::Manager, TestRequest::post().uri("/api/gels").set_json(&corps)).await;
+        assert_eq!(reponse.status(), StatusCode::FORBIDDEN);
+        let reponse = ctx.appeler(Role::Admin, TestRequest::post().uri("/api/gels").set_json(&corps)).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let corps: Value = test::read_body_json(reponse).await;
+        let champs: Vec<&str> = corps["erreurs"].as_array().unwrap().iter().map(|e| e["field"].as_str().unwrap()).collect();
+        assert_eq!(champs, ["raison", "dateFin"]);
+        let inconnu = format!("/api/gels/{}", Uuid::new_v4());
+        assert_eq!(ctx.appeler(Role::Admin, TestRequest::delete().uri(&inconnu)).await.status(), StatusCode::NOT_FOUND);
+
+        // Un gel désactivé ne bloque plus
+        let uri = format!("/api/gels/{}", ctx.gel["id"].as_str().unwrap());
+        let corps = json!({"dateDebut": test_support::date(0), "dateFin": test_support::date(48), "raison": "Levé", "actif": false});
+        assert_eq!(ctx.appeler(Role::Admin, TestRequest::put().uri(&uri).set_json(corps)).await.status(), StatusCode::OK);
+        assert_eq!(ctx.appeler(Role::Manager, ctx.travail("formation", 10, None)).await.status(), StatusCode::CREATED);
+    }
+}
@@ -1,3 +1,42 @@
+pub mod approbation_handlers;
+pub mod audit_handlers;
+pub mod auth_handlers;
+pub mod calendrier_handlers;
+pub mod checklist_handlers;
+pub mod conflit_handlers;
+pub mod corbeille_handlers;
+pub mod dependance_handlers;
+pub mod gel_handlers;
+pub mod modele_checklist_handlers;
+pub mod pagination;
+pub mod patch;
+pub mod promotion_handlers;
 pub mod projet_handlers;
+pub mod references;
+#[cfg(test)]
+pub mod test_support;
+pub mod transitions;
 pub mod travail_handlers;
 pub mod utilisateur_handlers;
+pub mod versions;
+
+use actix_web::web;
+
+// Routes de l'API, dans l'ordre où actix doit les essayer
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.configure(audit_handlers::config)
+        .configure(auth_handlers::config)
+        .configure(calendrier_handlers::config)
+        .configure(conflit_handlers::config)
+        .configure(corbeille_handlers::config)
+        .configure(gel_handlers::config)
+        // Routes imbriquées sous /api/travaux/{id} avant le scope /api/travaux
+        .configure(approbation_handlers::config)
+        .configure(dependance_handlers::config)
+        .configure(promotion_handlers::config)
+        .configure(checklist_handlers::config)
+        .configure(modele_checklist_handlers::config)
+        .configure(projet_handlers::config)
+        .configure(travail_handlers::config)
+        .configure(utilisateur_handlers::config);
+}
@@ -0,0 +1,344 @@
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::UtilisateurCourant;
+use crate::permissions::Permission;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::models::{Application, Environnement, EtapeModele, ModeleChecklist, TypeTravail};
+use crate::repositories::ModeleChecklistRepository;
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateModeleChecklistRequest {
+    pub nom: String,
+    #[serde(alias = "type_travail")]
+    pub type_travail: TypeTravail,
+    pub application: Option<Application>,
+    pub environnement: Option<Environnement>,
+    pub etapes: Vec<EtapeModele>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModeleChecklistResponse {
+    pub id: Uuid,
+    pub nom: String,
+    pub type_travail: TypeTravail,
+    pub application: Option<Application>,
+    pub environnement: Option<Environnement>,
+    pub etapes: Vec<EtapeModele>,
+}
+
+impl From<ModeleChecklist> for ModeleChecklistResponse {
+    fn from(m: ModeleChecklist) -> Self {
+        ModeleChecklistResponse {
+            id: m.id,
+            nom: m.nom,
+            type_travail: m.type_travail,
+            application: m.application,
+            environnement: m.environnement,
+            etapes: m.etapes,
+        }
+    }
+}
+
+impl CreateModeleChecklistRequest {
+    fn valider(&self) -> Validation {
+        let mut validation = Validation::new();
+        validation.texte("nom", &self.nom, 255);
+        for (index, etape) in self.etapes.iter().enumerate() {
+            validation.texte(&format!("etapes[{}].description", index), &etape.description, usize::MAX);
+        }
+        validation
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/modeles-checklist")
+            .route("", web::get().to(get_modeles))
+            .route("", web::post().to(create_modele))
+            .route("/{id}", web::get().to(get_modele))
+            .route("/{id}", web::put().to(update_modele))
+            .route("/{id}", web::delete().to(delete_modele))
+    );
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/modeles-checklist",
+    tag = "modeles-checklist",
+    responses(
+        (status = 200, description = "Modèles de checklist", body = Vec<ModeleChecklistResponse>),
+    ),
+)]
+pub async fn get_modeles(
+    _courant: UtilisateurCourant,
+    repository: web::Data<dyn ModeleChecklistRepository>,
+) -> Result<HttpResponse> {
+    let modeles_list: Vec<ModeleChecklistResponse> = repository.list().await?
+        .into_iter()
+        .map(ModeleChecklistResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(modeles_list))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/modeles-checklist/{id}",
+    tag = "modeles-checklist",
+    params(("id" = Uuid, Path, description = "Identifiant du modèle")),
+    responses(
+        (status = 200, description = "Modèle de checklist", body = ModeleChecklistResponse),
+        (status = 404, description = "Modèle non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_modele(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn ModeleChecklistRepository>,
+) -> Result<HttpResponse> {
+    match repository.find(id.into_inner()).await? {
+        Some(modele) => Ok(HttpResponse::Ok().json(ModeleChecklistResponse::from(modele))),
+        None => Err(ApiError::NotFound("Modèle de checklist non trouvé".to_string()))
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/modeles-checklist",
+    tag = "modeles-checklist",
+    request_body = CreateModeleChecklistRequest,
+    responses(
+        (status = 201, description = "Modèle créé", body = ModeleChecklistResponse),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn create_modele(
+    courant: UtilisateurCourant,
+    modele_req: web::Json<CreateModeleChecklistRequest>,
+    repository: web::Data<dyn ModeleChecklistRepository>,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererModelesChecklist)?;
+    let modele_req = modele_req.into_inner();
+    modele_req.valider().verifier()?;
+
+    let modele = ModeleChecklist {
+        id: Uuid::new_v4(),
+        nom: modele_req.nom,
+        type_travail: modele_req.type_travail,
+        application: modele_req.application,
+        environnement: modele_req.environnement,
+        etapes: modele_req.etapes,
+    };
+
+    repository.insert(&modele).await?;
+
+    Ok(HttpResponse::Created().json(ModeleChecklistResponse::from(modele)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/modeles-checklist/{id}",
+    tag = "modeles-checklist",
+    params(("id" = Uuid, Path, description = "Identifiant du modèle")),
+    request_body = CreateModeleChecklistRequest,
+    responses(
+        (status = 200, description = "Modèle modifié", body = ModeleChecklistResponse),
+        (status = 404, description = "Modèle non trouvé", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn update_modele(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    modele_req: web::Json<CreateModeleChecklistRequest>,
+    repository: web::Data<dyn ModeleChecklistRepository>,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererModelesChecklist)?;
+    let modele_req = modele_req.into_inner();
+    modele_req.valider().verifier()?;
+
+    let modele = ModeleChecklist {
+        id: id.into_inner(),
+        nom: modele_req.nom,
+        type_travail: modele_req.type_travail,
+        application: modele_req.application,
+        environnement: modele_req.environnement,
+        etapes: modele_req.etapes,
+    };
+
+    if repository.update(&modele).await? {
+        Ok(HttpResponse::Ok().json(ModeleChecklistResponse::from(modele)))
+    } else {
+        Err(ApiError::NotFound("Modèle de checklist non trouvé".to_string()))
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/modeles-checklist/{id}",
+    tag = "modeles-checklist",
+    params(("id" = Uuid, Path, description = "Identifiant du modèle")),
+    responses(
+        (status = 204, description = "Modèle supprimé"),
+        (status = 404, description = "Modèle non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn delete_modele(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn ModeleChecklistRepository>,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererModelesChecklist)?;
+    if repository.delete(id.into_inner()).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Modèle de checklist non trouvé".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use chrono::Duration;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::config::Config;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, ChecklistItem, Role, Utilisateur};
+    use crate::repositories::Repositories;
+
+    struct Contexte {
+        depots: Repositories,
+        config: Config,
+        manager: Utilisateur,
+        authorization: String,
+    }
+
+    impl Contexte {
+        async fn nouveau() -> Self {
+            let depots = Repositories::memoire();
+            let config = configuration();
+            let (manager, authorization) = connecter(&depots, &config, Role::Manager).await;
+            Contexte { depots, config, manager, authorization }
+        }
+
+        async fn appeler(&self, requete: TestRequest) -> ServiceResponse {
+            appeler(&self.depots, &self.config, requete.insert_header(("Authorization", self.authorization.clone()))).await
+        }
+
+        async fn creer(&self, corps: Value) -> Value {
+            let reponse = self.appeler(TestRequest::post().uri("/api/modeles-checklist").set_json(corps)).await;
+            assert_eq!(reponse.status(), StatusCode::CREATED);
+            test::read_body_json(reponse).await
+        }
+    }
+
+    fn id(modele: &Value) -> Uuid {
+        modele["id"].as_str().unwrap().parse().unwrap()
+    }
+
+    fn uri_modele(modele: &Value) -> String {
+        format!("/api/modeles-checklist/{}", id(modele))
+    }
+
+    #[actix_web::test]
+    async fn modele_le_plus_specifique_instancie() {
+        let ctx = Contexte::nouveau().await;
+        ctx.creer(json!({"nom": "Migration", "typeTravail": "migration", "etapes": [{"description": "Générique"}]})).await;
+        let specifique = ctx
+            .creer(json!({
+                "nom": "Migration GFR en test",
+                "typeTravail": "migration",
+                "application": "espresso_gfr",
+                "environnement": "test",
+                "etapes": [{"description": "Sauvegarde", "decalageJours": -1}, {"description": "Contrôle"}],
+            }))
+            .await;
+        ctx.creer(json!({"nom": "Clone", "typeTravail": "clonebd", "etapes": [{"description": "Clone"}]})).await;
+
+        let lu: Value = test::read_body_json(ctx.appeler(TestRequest::get().uri(&uri_modele(&specifique))).await).await;
+        assert_eq!(lu, specifique);
+        let liste: Vec<Value> = test::read_body_json(ctx.appeler(TestRequest::get().uri("/api/modeles-checklist")).await).await;
+        assert_eq!(liste.len(), 3);
+
+        let projet = test_support::projet();
+        ctx.depots.projets.insert(&projet).await.unwrap();
+        let debut = test_support::date(0);
+        let reponse = ctx
+            .appeler(TestRequest::post().uri("/api/travaux").set_json(json!({
+                "projetId": projet.id,
+                "type": "migration",
+                "application": "espresso_gfr",
+                "environnement": "test",
+                "description": "Migration",
+                "dateDebut": debut,
+                "dateFinPrevue": test_support::date(48),
+                "statut": "planifié",
+                "responsable": ctx.manager.id,
+                "equipe": [],
+            })))
+            .await;
+        assert_eq!(reponse.status(), StatusCode::CREATED);
+        let travail: Value = test::read_body_json(reponse).await;
+
+        let uri = format!("/api/travaux/{}/checklist", travail["id"].as_str().unwrap());
+        let items: Vec<ChecklistItem> = test::read_body_json(ctx.appeler(TestRequest::get().uri(&uri)).await).await;
+        let etapes: Vec<_> = items.iter().map(|i| (i.description.as_str(), i.ordre, i.date_echeance)).collect();
+        assert_eq!(etapes, [("Sauvegarde", 0, Some(debut - Duration::days(1))), ("Contrôle", 1, None)]);
+
+        // Modification puis suppression : un modèle disparu ne s'applique plus
+        let corps = json!({"nom": "Renommé", "typeTravail": "migration", "etapes": []});
+        let reponse = ctx.appeler(TestRequest::put().uri(&uri_modele(&specifique)).set_json(corps)).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        assert!(ctx.depots.modeles_checklist.find(id(&specifique)).await.unwrap().unwrap().etapes.is_empty());
+        assert_eq!(ctx.appeler(TestRequest::delete().uri(&uri_modele(&specifique))).await.status(), StatusCode::NO_CONTENT);
+        assert!(ctx.depots.modeles_checklist.find(id(&specifique)).await.unwrap().is_none());
+    }
+
+    #[actix_web::test]
+    async fn modeles_refuses() {
+        let ctx = Contexte::nouveau().await;
+
+        // Toutes les erreurs sont renvoyées ensemble, étape fautive comprise
+        let corps = json!({"nom": "", "typeTravail": "migration", "etapes": [{"description": "Sauvegarde"}, {"description": " "}]});
+        let reponse = ctx.appeler(TestRequest::post().uri("/api/modeles-checklist").set_json(corps)).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let corps: Value = test::read_body_json(reponse).await;
+        let champs: Vec<&str> = corps["erreurs"].as_array().unwrap().iter().map(|e| e["field"].as_str().unwrap()).collect();
+        assert_eq!(champs, ["nom", "etapes[1].description"]);
+
+        let corps = json!({"nom": "Modèle", "typeTravail": "inconnu", "etapes": []});
+        let reponse = ctx.appeler(TestRequest::post().uri("/api/modeles-checklist").set_json(corps)).await;
+        assert_eq!(reponse.status(), StatusCode::BAD_REQUEST);
+
+        // Réservé à GererModelesChecklist ; la lecture reste ouverte
+        let modele = ctx.creer(json!({"nom": "Modèle", "typeTravail": "migration", "etapes": []})).await;
+        let (_, authorization) = connecter(&ctx.depots, &ctx.config, Role::Specialiste).await;
+        let corps = json!({"nom": "Pris", "typeTravail": "migration", "etapes": []});
+        for requete in [
+            TestRequest::post().uri("/api/modeles-checklist").set_json(&corps),
+            TestRequest::put().uri(&uri_modele(&modele)).set_json(&corps),
+            TestRequest::delete().uri(&uri_modele(&modele)),
+        ] {
+            let reponse = appeler(&ctx.depots, &ctx.config, requete.insert_header(("Authorization", authorization.clone()))).await;
+            assert_eq!(reponse.status(), StatusCode::FORBIDDEN);
+        }
+        let requete = TestRequest::get().uri(&uri_modele(&modele)).insert_header(("Authorization", authorization));
+        assert_eq!(appeler(&ctx.depots, &ctx.config, requete).await.status(), StatusCode::OK);
+
+        let inconnu = format!("/api/modeles-checklist/{}", Uuid::new_v4());
+        assert_eq!(ctx.appeler(TestRequest::get().uri(&inconnu)).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(ctx.appeler(TestRequest::put().uri(&inconnu).set_json(&corps)).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(ctx.appeler(TestRequest::delete().uri(&inconnu)).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(ctx.depots.modeles_checklist.find(id(&modele)).await.unwrap().unwrap().nom, "Modèle");
+    }
+}
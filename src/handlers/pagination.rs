@@ -0,0 +1,87 @@
+// Paramètres communs aux listes : `sort=champ` (ou `-champ` pour l'ordre décroissant), `offset`, `limit`.
+// Le champ s'écrit comme dans le JSON (`dateDebut`) ; l'ancienne graphie `date_debut` reste acceptée
+
+use actix_web::HttpResponse;
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::repositories::{Page, Pagination, Tri};
+use crate::validation::Validation;
+
+pub const LIMIT_MAX: i64 = 500;
+
+pub fn pagination(
+    sort: Option<&str>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+    champs: &'static [&'static str],
+    defaut: &'static str,
+) -> Result<Pagination> {
+    let mut validation = Validation::new();
+
+    let (nom, descendant) = match sort {
+        Some(sort) => match sort.strip_prefix('-') {
+            Some(nom) => (nom, true),
+            None => (sort, false),
+        },
+        None => (defaut, false),
+    };
+    let nom = snake_case(nom);
+    let champ = champs.iter().copied().find(|c| *c == nom).unwrap_or(defaut);
+    if champ != nom {
+        let noms: Vec<String> = champs.iter().map(|c| camel_case(c)).collect();
+        validation.ajouter("sort", "tri_invalide", format!("Tri possible sur : {}", noms.join(", ")));
+    }
+
+    let offset = offset.unwrap_or(0);
+    if offset < 0 {
+        validation.ajouter("offset", "hors_limites", "L'offset doit être positif");
+    }
+    if let Some(limit) = limit {
+        if !(1..=LIMIT_MAX).contains(&limit) {
+            validation.ajouter("limit", "hors_limites", format!("La limite doit être comprise entre 1 et {}", LIMIT_MAX));
+        }
+    }
+    validation.verifier()?;
+
+    Ok(Pagination {
+        tri: Tri { champ, descendant },
+        offset,
+        limit,
+    })
+}
+
+// `dateDebut` -> `date_debut` ; un nom déjà en snake_case est inchangé
+fn snake_case(nom: &str) -> String {
+    let mut resultat = String::with_capacity(nom.len() + 4);
+    for c in nom.chars() {
+        if c.is_ascii_uppercase() {
+            resultat.push('_');
+            resultat.push(c.to_ascii_lowercase());
+        } else {
+            resultat.push(c);
+        }
+    }
+    resultat
+}
+
+fn camel_case(champ: &str) -> String {
+    let mut mots = champ.split('_');
+    let mut resultat = mots.next().unwrap_or_default().to_string();
+    for mot in mots {
+        let mut lettres = mot.chars();
+        if let Some(premiere) = lettres.next() {
+            resultat.push(premiere.to_ascii_uppercase());
+            resultat.extend(lettres);
+        }
+    }
+    resultat
+}
+
+// Le corps reste la liste des éléments ; le total (toutes pages confondues) est dans X-Total-Count
+pub fn reponse_page<T, R: Serialize>(page: Page<T>, convertir: impl Fn(T) -> R) -> HttpResponse {
+    let elements: Vec<R> = page.elements.into_iter().map(convertir).collect();
+    HttpResponse::Ok()
+        .insert_header(("X-Total-Count", page.total.to_string()))
+        .json(elements)
+}
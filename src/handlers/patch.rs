@@ -0,0 +1,72 @@
+// Modifications partielles au format JSON Merge Patch (RFC 7386) : les membres du patch remplacent
+// ceux de la représentation actuelle, un membre null est retiré (champ facultatif remis à vide)
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::{ApiError, Result};
+
+pub fn fusionner(cible: &mut Value, patch: Value) {
+    let Value::Object(membres) = patch else {
+        *cible = patch;
+        return;
+    };
+    if !cible.is_object() {
+        *cible = Value::Object(Map::new());
+    }
+    if let Value::Object(champs) = cible {
+        for (nom, valeur) in membres {
+            if valeur.is_null() {
+                champs.remove(&nom);
+            } else {
+                fusionner(champs.entry(nom).or_insert(Value::Null), valeur);
+            }
+        }
+    }
+}
+
+// Applique le patch à la requête de modification complète équivalente à l'état actuel ; les
+// membres suivent le contrat (camelCase), les anciennes graphies snake_case n'y sont pas acceptées
+pub fn appliquer<T: Serialize + DeserializeOwned>(actuelle: &T, patch: Value) -> Result<T> {
+    if !patch.is_object() {
+        return Err(ApiError::BadRequest("Le patch doit être un objet JSON".to_string()));
+    }
+    let mut valeur = serde_json::to_value(actuelle).map_err(|err| ApiError::Storage(err.into()))?;
+    fusionner(&mut valeur, patch);
+    serde_json::from_value(valeur).map_err(|err| ApiError::BadRequest(format!("Patch invalide : {}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    // Exemples de l'annexe A de la RFC 7386
+    #[test]
+    fn exemples_rfc_7386() {
+        let cas = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "b"}), json!({"b": "c"}), json!({"a": "b", "b": "c"})),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (json!({"a": "b", "b": "c"}), json!({"a": null}), json!({"b": "c"})),
+            (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "c"}), json!({"a": ["b"]}), json!({"a": ["b"]})),
+            (json!({"a": {"b": "c"}}), json!({"a": {"b": "d", "c": null}}), json!({"a": {"b": "d"}})),
+            (json!({"a": [{"b": "c"}]}), json!({"a": [1]}), json!({"a": [1]})),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+            (json!({"a": "foo"}), json!(null), json!(null)),
+            (json!({"a": "foo"}), json!("bar"), json!("bar")),
+            (json!({"e": null}), json!({"a": 1}), json!({"e": null, "a": 1})),
+            (json!([1, 2]), json!({"a": "b", "c": null}), json!({"a": "b"})),
+            (json!({}), json!({"a": {"bb": {"ccc": null}}}), json!({"a": {"bb": {}}})),
+        ];
+        for (cible, patch, attendu) in cas {
+            let mut resultat = cible.clone();
+            fusionner(&mut resultat, patch.clone());
+            assert_eq!(resultat, attendu, "{} + {}", cible, patch);
+        }
+    }
+}
@@ -1,24 +1,45 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use std::collections::HashMap;
-use std::sync::Mutex;
+use utoipa::{IntoParams, ToSchema};
 use chrono::{DateTime, Utc};
 
-use crate::models::{Projet, StatutProjet, Priorite};
+use crate::auth::UtilisateurCourant;
+use crate::permissions::Permission;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::models::{ActionAudit, Projet, StatutProjet, Priorite, Suppression, Travail, VERSION_INITIALE};
+use crate::models::TypeEntite;
+use crate::config::{Config, PolitiqueSuppression};
+use crate::repositories::{AuditRepository, ChecklistRepository, ProjetRepository, TransitionRepository, TravailRepository};
+use crate::handlers::references::{cible_reassignation_manquante, restauration_refusee, suppression_refusee, SuppressionQuery};
+use crate::validation::Validation;
+use crate::handlers::pagination::{pagination, reponse_page};
+use crate::repositories::filtres::TRI_PROJETS;
+use crate::repositories::{FiltreProjets, FiltreTravaux, Pagination, Repositories, Supprimes};
+use crate::sante::{self, SanteProjet};
+use crate::handlers::audit_handlers::{historique, journaliser, journaliser_action, reponse_historique, EvenementAuditResponse, HistoriqueQuery};
+use crate::handlers::corbeille_handlers::supprimes_listes;
+use crate::handlers::travail_handlers::{restauration_impossible, restaurer, supprimer_travail};
+use crate::handlers::transitions::{nouvelle_transition, raison_manquante, transition_interdite, TransitionRequest, TransitionStatutResponse};
+use crate::handlers::patch;
+use crate::handlers::versions::{ecriture_refusee, etag, VersionAttendue};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct CreateProjetRequest {
     pub code: String,
     pub nom: String,
     pub description: String,
+    #[serde(alias = "date_debut")]
     pub date_debut: DateTime<Utc>,
+    #[serde(alias = "date_fin_prevue")]
     pub date_fin_prevue: DateTime<Utc>,
     pub statut: StatutProjet,
     pub priorite: Priorite,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct ProjetResponse {
     pub id: Uuid,
     pub code: String,
@@ -28,6 +49,101 @@ pub struct ProjetResponse {
     pub date_fin_prevue: DateTime<Utc>,
     pub statut: StatutProjet,
     pub priorite: Priorite,
+    pub version: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
+    pub sante: SanteProjet,
+}
+
+impl ProjetResponse {
+    fn new(p: Projet, sante: SanteProjet) -> Self {
+        ProjetResponse {
+            id: p.id,
+            code: p.code,
+            nom: p.nom,
+            description: p.description,
+            date_debut: p.date_debut,
+            date_fin_prevue: p.date_fin_prevue,
+            statut: p.statut,
+            priorite: p.priorite,
+            version: p.version,
+            suppression: p.suppression,
+            sante,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListeProjetsQuery {
+    pub statut: Option<StatutProjet>,
+    pub priorite: Option<Priorite>,
+    // Admin : inclure les projets supprimés (restaurables)
+    pub inclure_supprimes: Option<bool>,
+    pub sort: Option<String>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// Requête de modification complète équivalente au projet, base d'un PATCH
+impl From<&Projet> for CreateProjetRequest {
+    fn from(p: &Projet) -> Self {
+        CreateProjetRequest {
+            code: p.code.clone(),
+            nom: p.nom.clone(),
+            description: p.description.clone(),
+            date_debut: p.date_debut,
+            date_fin_prevue: p.date_fin_prevue,
+            statut: p.statut.clone(),
+            priorite: p.priorite.clone(),
+        }
+    }
+}
+
+impl CreateProjetRequest {
+    fn valider(&self) -> Validation {
+        let mut validation = Validation::new();
+        validation.texte("code", &self.code, 50);
+        validation.texte("nom", &self.nom, 255);
+        validation.periode(self.date_debut, self.date_fin_prevue);
+        validation
+    }
+}
+
+// Le code projet est unique (contrainte UNIQUE de la table projets)
+async fn verifier_code_unique(
+    validation: &mut Validation,
+    projets: &dyn ProjetRepository,
+    code: &str,
+    id: Option<Uuid>,
+) -> Result<()> {
+    if let Some(existant) = projets.find_by_code(code).await? {
+        if Some(existant.id) != id {
+            validation.ajouter("code", "deja_utilise", format!("Le code projet {} est déjà utilisé", code));
+        }
+    }
+    Ok(())
+}
+
+// Avancement et santé calculés à la volée à partir des travaux et checklists du projet
+pub async fn sante_projet(
+    projet: &Projet,
+    travaux: &dyn TravailRepository,
+    checklist: &dyn ChecklistRepository,
+) -> Result<SanteProjet> {
+    let travaux_projet = travaux.list_by_projet(projet.id).await?;
+    let ids: Vec<Uuid> = travaux_projet.iter().map(|t| t.id).collect();
+    let items = checklist.list_by_travaux(&ids).await?;
+    Ok(sante::calculer(projet, &travaux_projet, &items, Utc::now()))
+}
+
+async fn projet_response(
+    projet: Projet,
+    travaux: &dyn TravailRepository,
+    checklist: &dyn ChecklistRepository,
+) -> Result<ProjetResponse> {
+    let sante = sante_projet(&projet, travaux, checklist).await?;
+    Ok(ProjetResponse::new(projet, sante))
 }
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -37,132 +153,692 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("", web::post().to(create_projet))
             .route("/{id}", web::get().to(get_projet))
             .route("/{id}", web::put().to(update_projet))
+            .route("/{id}", web::patch().to(patch_projet))
             .route("/{id}", web::delete().to(delete_projet))
+            .route("/{id}/transitions", web::get().to(get_transitions_projet))
+            .route("/{id}/transitions", web::post().to(transition_projet))
+            .route("/{id}/historique", web::get().to(get_historique_projet))
+            .route("/{id}/restauration", web::post().to(restaurer_projet))
+            .route("/{id}/sante", web::get().to(get_sante_projet))
     );
 }
 
-pub async fn get_projets(projets: web::Data<Mutex<HashMap<Uuid, Projet>>>) -> Result<HttpResponse> {
-    let projets_map = projets.lock().unwrap();
-    let projets_list: Vec<ProjetResponse> = projets_map.values()
-        .map(|p| ProjetResponse {
-            id: p.id,
-            code: p.code.clone(),
-            nom: p.nom.clone(),
-            description: p.description.clone(),
-            date_debut: p.date_debut,
-            date_fin_prevue: p.date_fin_prevue,
-            statut: p.statut.clone(),
-            priorite: p.priorite.clone(),
-        })
-        .collect();
+#[utoipa::path(
+    get,
+    path = "/api/projets",
+    tag = "projets",
+    params(ListeProjetsQuery),
+    responses(
+        (status = 200, description = "Projets de la page demandée, avec leur santé", body = Vec<ProjetResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 403, description = "inclure_supprimes : permission gerer_corbeille manquante", body = CorpsErreur),
+    ),
+)]
+pub async fn get_projets(
+    courant: UtilisateurCourant,
+    query: web::Query<ListeProjetsQuery>,
+    repository: web::Data<dyn ProjetRepository>,
+    travaux: web::Data<dyn TravailRepository>,
+    checklist: web::Data<dyn ChecklistRepository>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let pagination = pagination(query.sort.as_deref(), query.offset, query.limit, TRI_PROJETS, "date_debut")?;
+    let filtre = FiltreProjets {
+        statut: query.statut,
+        priorite: query.priorite,
+        supprimes: supprimes_listes(&courant, query.inclure_supprimes)?,
+    };
+    let page = repository.list(&filtre, &pagination).await?;
 
-    Ok(HttpResponse::Ok().json(projets_list))
+    // Travaux et items de toute la page chargés en deux requêtes, puis répartis par projet
+    let projet_ids: Vec<Uuid> = page.elements.iter().map(|p| p.id).collect();
+    let travaux_page = travaux.list_by_projets(&projet_ids).await?;
+    let travail_ids: Vec<Uuid> = travaux_page.iter().map(|t| t.id).collect();
+    let items_page = checklist.list_by_travaux(&travail_ids).await?;
+    let maintenant = Utc::now();
+    Ok(reponse_page(page, |projet| {
+        let travaux_projet: Vec<Travail> = travaux_page.iter().filter(|t| t.projet_id == projet.id).cloned().collect();
+        let sante = sante::calculer(&projet, &travaux_projet, &items_page, maintenant);
+        ProjetResponse::new(projet, sante)
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projets/{id}",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet")),
+    responses(
+        (status = 200, description = "Projet et sa santé", body = ProjetResponse, headers(("ETag" = String, description = "Version du projet"))),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+    ),
+)]
 pub async fn get_projet(
-    web::Path(id): web::Path<Uuid>,
-    projets: web::Data<Mutex<HashMap<Uuid, Projet>>>,
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn ProjetRepository>,
+    travaux: web::Data<dyn TravailRepository>,
+    checklist: web::Data<dyn ChecklistRepository>,
 ) -> Result<HttpResponse> {
-    let projets_map = projets.lock().unwrap();
-    
-    match projets_map.get(&id) {
-        Some(projet) => {
-            let response = ProjetResponse {
-                id: projet.id,
-                code: projet.code.clone(),
-                nom: projet.nom.clone(),
-                description: projet.description.clone(),
-                date_debut: projet.date_debut,
-                date_fin_prevue: projet.date_fin_prevue,
-                statut: projet.statut.clone(),
-                priorite: projet.priorite.clone(),
-            };
-            Ok(HttpResponse::Ok().json(response))
-        }
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Projet non trouvé"
-        })))
+    match repository.find(id.into_inner()).await? {
+        Some(projet) => Ok(HttpResponse::Ok()
+            .insert_header(etag(projet.version))
+            .json(projet_response(projet, travaux.as_ref(), checklist.as_ref()).await?)),
+        None => Err(ApiError::NotFound("Projet non trouvé".to_string()))
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projets/{id}/sante",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet")),
+    responses(
+        (status = 200, description = "Avancement et indicateur de santé", body = SanteProjet),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_sante_projet(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn ProjetRepository>,
+    travaux: web::Data<dyn TravailRepository>,
+    checklist: web::Data<dyn ChecklistRepository>,
+) -> Result<HttpResponse> {
+    let Some(projet) = repository.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    };
+
+    Ok(HttpResponse::Ok().json(sante_projet(&projet, travaux.as_ref(), checklist.as_ref()).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/projets",
+    tag = "projets",
+    request_body = CreateProjetRequest,
+    responses(
+        (status = 201, description = "Projet créé", body = ProjetResponse),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
 pub async fn create_projet(
+    courant: UtilisateurCourant,
     projet_req: web::Json<CreateProjetRequest>,
-    projets: web::Data<Mutex<HashMap<Uuid, Projet>>>,
+    depots: Repositories,
 ) -> Result<HttpResponse> {
-    let mut projets_map = projets.lock().unwrap();
-    
+    courant.exiger(Permission::GererProjets)?;
+    let unite = depots.transaction().await?;
+    let Repositories { projets: repository, audit, .. } = &*unite;
+    let projet_req = projet_req.into_inner();
+    let mut validation = projet_req.valider();
+    verifier_code_unique(&mut validation, repository.as_ref(), &projet_req.code, None).await?;
+    validation.verifier()?;
+
     let projet = Projet {
         id: Uuid::new_v4(),
-        code: projet_req.code.clone(),
-        nom: projet_req.nom.clone(),
-        description: projet_req.description.clone(),
+        code: projet_req.code,
+        nom: projet_req.nom,
+        description: projet_req.description,
         date_debut: projet_req.date_debut,
         date_fin_prevue: projet_req.date_fin_prevue,
-        statut: projet_req.statut.clone(),
-        priorite: projet_req.priorite.clone(),
+        statut: projet_req.statut,
+        priorite: projet_req.priorite,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
 
-    projets_map.insert(projet.id, projet.clone());
-
-    let response = ProjetResponse {
-        id: projet.id,
-        code: projet.code,
-        nom: projet.nom,
-        description: projet.description,
-        date_debut: projet.date_debut,
-        date_fin_prevue: projet.date_fin_prevue,
-        statut: projet.statut,
-        priorite: projet.priorite,
-    };
+    repository.insert(&projet).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Projet, projet.id, None, Some(&projet)).await?;
+    unite.valider().await?;
 
-    Ok(HttpResponse::Created().json(response))
+    // Projet neuf : aucun travail, santé calculée sans lecture du stockage
+    let sante = sante::calculer(&projet, &[], &[], Utc::now());
+    Ok(HttpResponse::Created().insert_header(etag(projet.version)).json(ProjetResponse::new(projet, sante)))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/projets/{id}",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la modification est refusée si le projet a changé depuis")),
+    request_body = CreateProjetRequest,
+    responses(
+        (status = 200, description = "Projet modifié", body = ProjetResponse, headers(("ETag" = String, description = "Nouvelle version du projet"))),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+        (status = 409, description = "Transition de statut interdite", body = CorpsErreur),
+        (status = 412, description = "Projet modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
 pub async fn update_projet(
-    web::Path(id): web::Path<Uuid>,
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
     projet_req: web::Json<CreateProjetRequest>,
-    projets: web::Data<Mutex<HashMap<Uuid, Projet>>>,
+    depots: Repositories,
 ) -> Result<HttpResponse> {
-    let mut projets_map = projets.lock().unwrap();
-    
-    if let Some(existing_projet) = projets_map.get_mut(&id) {
-        existing_projet.code = projet_req.code.clone();
-        existing_projet.nom = projet_req.nom.clone();
-        existing_projet.description = projet_req.description.clone();
-        existing_projet.date_debut = projet_req.date_debut;
-        existing_projet.date_fin_prevue = projet_req.date_fin_prevue;
-        existing_projet.statut = projet_req.statut.clone();
-        existing_projet.priorite = projet_req.priorite.clone();
-
-        let response = ProjetResponse {
-            id: existing_projet.id,
-            code: existing_projet.code.clone(),
-            nom: existing_projet.nom.clone(),
-            description: existing_projet.description.clone(),
-            date_debut: existing_projet.date_debut,
-            date_fin_prevue: existing_projet.date_fin_prevue,
-            statut: existing_projet.statut.clone(),
-            priorite: existing_projet.priorite.clone(),
-        };
+    courant.exiger(Permission::GererProjets)?;
+    let unite = depots.transaction().await?;
+    let Some(existing) = unite.projets.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    };
+    version_attendue.verifier(existing.version)?;
+
+    let reponse = modifier_projet(&courant, existing, projet_req.into_inner(), &unite).await?;
+    unite.valider().await?;
+    Ok(reponse)
+}
 
-        Ok(HttpResponse::Ok().json(response))
-    } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Projet non trouvé"
-        })))
+// Modification partielle : seuls les champs présents dans le patch (JSON Merge Patch) changent
+#[utoipa::path(
+    patch,
+    path = "/api/projets/{id}",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la modification est refusée si le projet a changé depuis")),
+    request_body(content = CreateProjetRequest, content_type = "application/merge-patch+json", description = "Champs à modifier, tous facultatifs"),
+    responses(
+        (status = 200, description = "Projet modifié", body = ProjetResponse, headers(("ETag" = String, description = "Nouvelle version du projet"))),
+        (status = 400, description = "Patch invalide", body = CorpsErreur),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+        (status = 409, description = "Transition de statut interdite", body = CorpsErreur),
+        (status = 412, description = "Projet modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn patch_projet(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    patch: web::Json<serde_json::Value>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererProjets)?;
+    let unite = depots.transaction().await?;
+    let Some(existing) = unite.projets.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    };
+    version_attendue.verifier(existing.version)?;
+
+    let projet_req = patch::appliquer(&CreateProjetRequest::from(&existing), patch.into_inner())?;
+    let reponse = modifier_projet(&courant, existing, projet_req, &unite).await?;
+    unite.valider().await?;
+    Ok(reponse)
+}
+
+// Validation et enregistrement communs à PUT et PATCH, dans l'unité de travail de l'appelant ;
+// l'écriture échoue si le projet a changé depuis la lecture de `existing`
+async fn modifier_projet(
+    courant: &UtilisateurCourant,
+    existing: Projet,
+    projet_req: CreateProjetRequest,
+    depots: &Repositories,
+) -> Result<HttpResponse> {
+    let repository = depots.projets.as_ref();
+    let transitions = depots.transitions.as_ref();
+    let travaux = depots.travaux.as_ref();
+    let checklist = depots.checklist.as_ref();
+    let audit = depots.audit.as_ref();
+    let id = existing.id;
+    let mut validation = projet_req.valider();
+    verifier_code_unique(&mut validation, repository, &projet_req.code, Some(id)).await?;
+    validation.verifier()?;
+
+    if projet_req.statut != existing.statut && !existing.statut.peut_passer_a(&projet_req.statut) {
+        return Err(transition_interdite(existing.statut.clone(), projet_req.statut.clone()));
+    }
+
+    let mut projet = Projet {
+        id,
+        code: projet_req.code,
+        nom: projet_req.nom,
+        description: projet_req.description,
+        date_debut: projet_req.date_debut,
+        date_fin_prevue: projet_req.date_fin_prevue,
+        statut: projet_req.statut,
+        priorite: projet_req.priorite,
+        version: existing.version,
+        suppression: existing.suppression.clone(),
+    };
+
+    if !repository.update(&projet).await? {
+        return Err(ecriture_refusee(repository.find(id).await?.map(|p| p.version), "Projet non trouvé"));
+    }
+    projet.version += 1;
+    journaliser(audit, Some(courant.0.id), TypeEntite::Projet, id, Some(&existing), Some(&projet)).await?;
+    if projet.statut != existing.statut {
+        let transition = nouvelle_transition(TypeEntite::Projet, id, existing.statut.clone(), projet.statut.clone(), None);
+        transitions.insert(&transition).await?;
     }
+    Ok(HttpResponse::Ok().insert_header(etag(projet.version)).json(projet_response(projet, travaux, checklist).await?))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/projets/{id}",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la suppression est refusée si le projet a changé depuis"), SuppressionQuery),
+    responses(
+        (status = 204, description = "Projet supprimé (restaurable jusqu'à sa purge)"),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+        (status = 409, description = "Suppression refusée : le projet a des travaux", body = CorpsErreur),
+        (status = 412, description = "Projet modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
 pub async fn delete_projet(
-    web::Path(id): web::Path<Uuid>,
-    projets: web::Data<Mutex<HashMap<Uuid, Projet>>>,
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    query: web::Query<SuppressionQuery>,
+    depots: Repositories,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererProjets)?;
+    // Projet, travaux et journal : tout ou rien, quelle que soit la politique
+    let unite = depots.transaction().await?;
+    let Repositories { projets: repository, travaux, approbations, audit, .. } = &*unite;
+    let id = id.into_inner();
+    let Some(projet) = repository.find(id).await? else {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    };
+    version_attendue.verifier(projet.version)?;
+    // Partagée par le projet et ses travaux supprimés en cascade, restaurés avec lui
+    let suppression = Suppression { le: Utc::now(), par: Some(courant.0.id) };
+
+    let travaux_projet = travaux.list_by_projet(id).await?;
+    if !travaux_projet.is_empty() {
+        match config.suppression_projets {
+            PolitiqueSuppression::Rejeter => {
+                return Err(suppression_refusee(format!(
+                    "Le projet est référencé par {} travaux", travaux_projet.len()
+                )));
+            }
+            PolitiqueSuppression::Cascade => {
+                for travail in &travaux_projet {
+                    if !supprimer_travail(travail, &suppression, travaux.as_ref(), audit.as_ref()).await? {
+                        return Err(ecriture_refusee(travaux.find(travail.id).await?.map(|t| t.version), "Travail non trouvé"));
+                    }
+                }
+            }
+            PolitiqueSuppression::Reassigner => {
+                let Some(cible) = query.reassigner_a else {
+                    return Err(cible_reassignation_manquante());
+                };
+                if cible == id || repository.find(cible).await?.is_none() {
+                    return Err(Validation::champ_invalide(
+                        "reassigner_a",
+                        "inexistant",
+                        format!("Projet {} invalide pour la réassignation", cible),
+                    ));
+                }
+                for travail in travaux_projet {
+                    let reassigne = Travail { projet_id: cible, ..travail.clone() };
+                    if !travaux.update(&reassigne).await? {
+                        return Err(ecriture_refusee(travaux.find(travail.id).await?.map(|t| t.version), "Travail non trouvé"));
+                    }
+                    approbations.invalider(travail.id).await?;
+                    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Travail, travail.id, Some(&travail), Some(&reassigne)).await?;
+                }
+            }
+        }
+    }
+
+    let supprime = Projet { suppression: Some(suppression), ..projet.clone() };
+    if !repository.update(&supprime).await? {
+        return Err(ecriture_refusee(repository.find(id).await?.map(|p| p.version), "Projet non trouvé"));
+    }
+    journaliser_action(audit.as_ref(), Some(courant.0.id), TypeEntite::Projet, id, ActionAudit::Suppression, Some(&projet), Some(&supprime)).await?;
+    unite.valider().await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/projets/{id}/restauration",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet supprimé"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la restauration est refusée si le projet a changé depuis")),
+    responses(
+        (status = 200, description = "Projet restauré, avec les travaux supprimés en même temps que lui", body = ProjetResponse, headers(("ETag" = String, description = "Nouvelle version du projet"))),
+        (status = 404, description = "Aucun projet supprimé avec cet identifiant", body = CorpsErreur),
+        (status = 409, description = "Restauration refusée : code projet réutilisé depuis", body = CorpsErreur),
+        (status = 412, description = "Projet modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
+pub async fn restaurer_projet(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    depots: Repositories,
 ) -> Result<HttpResponse> {
-    let mut projets_map = projets.lock().unwrap();
-    
-    match projets_map.remove(&id) {
-        Some(_) => Ok(HttpResponse::NoContent().finish()),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Projet non trouvé"
-        })))
+    courant.exiger(Permission::GererProjets)?;
+    let unite = depots.transaction().await?;
+    let Repositories { projets: repository, travaux, utilisateurs, checklist, audit, .. } = &*unite;
+    let id = id.into_inner();
+    let Some(projet) = repository.find_supprime(id).await? else {
+        return Err(ApiError::NotFound("Projet supprimé non trouvé".to_string()));
+    };
+    version_attendue.verifier(projet.version)?;
+    if repository.find_by_code(&projet.code).await?.is_some() {
+        return Err(restauration_refusee(format!("Le code projet {} a été réutilisé depuis la suppression", projet.code)));
+    }
+
+    let mut restaure = Projet { suppression: None, ..projet.clone() };
+    if !repository.update(&restaure).await? {
+        return Err(ecriture_refusee(repository.find_supprime(id).await?.map(|p| p.version), "Projet supprimé non trouvé"));
+    }
+    restaure.version += 1;
+    journaliser_action(audit.as_ref(), Some(courant.0.id), TypeEntite::Projet, id, ActionAudit::Restauration, Some(&projet), Some(&restaure)).await?;
+
+    // Travaux supprimés en cascade avec le projet (même suppression), sauf ceux qui ne peuvent plus
+    // revenir tels quels : ils restent dans la corbeille
+    let filtre = FiltreTravaux {
+        projet_id: Some(id),
+        supprimes: Supprimes::Inclus,
+        ..FiltreTravaux::default()
+    };
+    for travail in travaux.list(&filtre, &Pagination::tout("date_debut")).await?.elements {
+        if travail.suppression == projet.suppression
+            && restauration_impossible(&travail, repository.as_ref(), utilisateurs.as_ref(), travaux.as_ref()).await?.is_none()
+        {
+            restaurer(&travail, courant.0.id, travaux.as_ref(), audit.as_ref()).await?;
+        }
+    }
+
+    let reponse = projet_response(restaure, travaux.as_ref(), checklist.as_ref()).await?;
+    unite.valider().await?;
+    Ok(HttpResponse::Ok().insert_header(etag(reponse.version)).json(reponse))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/projets/{id}/historique",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet"), HistoriqueQuery),
+    responses(
+        (status = 200, description = "Historique des modifications", body = Vec<EvenementAuditResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_historique_projet(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    query: web::Query<HistoriqueQuery>,
+    repository: web::Data<dyn ProjetRepository>,
+    audit: web::Data<dyn AuditRepository>,
+) -> Result<HttpResponse> {
+    let id = id.into_inner();
+    let page = historique(audit.as_ref(), TypeEntite::Projet, id, &query).await?;
+    if page.total == 0 && repository.find(id).await?.is_none() {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    }
+
+    Ok(reponse_historique(page))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/projets/{id}/transitions",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet")),
+    responses(
+        (status = 200, description = "Changements de statut du projet", body = Vec<TransitionStatutResponse>),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_transitions_projet(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn ProjetRepository>,
+    transitions: web::Data<dyn TransitionRepository>,
+) -> Result<HttpResponse> {
+    let id = id.into_inner();
+    if repository.find(id).await?.is_none() {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    }
+
+    let historique: Vec<TransitionStatutResponse> = transitions.list_by_entite(&TypeEntite::Projet, id).await?
+        .into_iter()
+        .map(TransitionStatutResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(historique))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/projets/{id}/transitions",
+    tag = "projets",
+    params(("id" = Uuid, Path, description = "Identifiant du projet"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la transition est refusée si le projet a changé depuis")),
+    request_body = TransitionRequest<StatutProjet>,
+    responses(
+        (status = 200, description = "Statut modifié", body = ProjetResponse, headers(("ETag" = String, description = "Nouvelle version du projet"))),
+        (status = 400, description = "Raison manquante", body = CorpsErreur),
+        (status = 404, description = "Projet non trouvé", body = CorpsErreur),
+        (status = 409, description = "Transition de statut interdite", body = CorpsErreur),
+        (status = 412, description = "Projet modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
+pub async fn transition_projet(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    transition_req: web::Json<TransitionRequest<StatutProjet>>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererProjets)?;
+    let unite = depots.transaction().await?;
+    let Repositories { projets: repository, transitions, travaux, checklist, audit, .. } = &*unite;
+    let Some(mut projet) = repository.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Projet non trouvé".to_string()));
+    };
+    version_attendue.verifier(projet.version)?;
+
+    let transition_req = transition_req.into_inner();
+    if transition_req.raison.trim().is_empty() {
+        return Err(raison_manquante());
+    }
+    if !projet.statut.peut_passer_a(&transition_req.statut) {
+        return Err(transition_interdite(projet.statut.clone(), transition_req.statut.clone()));
+    }
+
+    let transition = nouvelle_transition(
+        TypeEntite::Projet,
+        projet.id,
+        projet.statut.clone(),
+        transition_req.statut.clone(),
+        Some(transition_req.raison),
+    );
+    let avant = projet.clone();
+    projet.statut = transition_req.statut;
+
+    if !repository.update(&projet).await? {
+        return Err(ecriture_refusee(repository.find(projet.id).await?.map(|p| p.version), "Projet non trouvé"));
+    }
+    projet.version += 1;
+    transitions.insert(&transition).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Projet, projet.id, Some(&avant), Some(&projet)).await?;
+
+    let reponse = projet_response(projet, travaux.as_ref(), checklist.as_ref()).await?;
+    unite.valider().await?;
+    Ok(HttpResponse::Ok().insert_header(etag(reponse.version)).json(reponse))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Role};
+    use crate::repositories::FiltreAudit;
+
+    // Projet avec un travail, supprimé par un admin selon `politique`
+    struct Resultat {
+        depots: Repositories,
+        projet: Projet,
+        travail: Travail,
+        reponse: actix_web::dev::ServiceResponse,
+    }
+
+    async fn supprimer(politique: PolitiqueSuppression, reassigner_a: Option<Uuid>, cible: Option<&Projet>) -> Resultat {
+        let depots = Repositories::memoire();
+        let configuration = Config { suppression_projets: politique, ..configuration() };
+        let (_, authorization) = connecter(&depots, &configuration, Role::Admin).await;
+        let projet = test_support::projet();
+        depots.projets.insert(&projet).await.unwrap();
+        if let Some(cible) = cible {
+            depots.projets.insert(cible).await.unwrap();
+        }
+        let travail = Travail { projet_id: projet.id, ..test_support::travail() };
+        depots.travaux.insert(&travail).await.unwrap();
+
+        let uri = match reassigner_a {
+            Some(cible) => format!("/api/projets/{}?reassigner_a={}", projet.id, cible),
+            None => format!("/api/projets/{}", projet.id),
+        };
+        let requete = TestRequest::delete().uri(&uri).insert_header(("Authorization", authorization));
+        let reponse = appeler(&depots, &configuration, requete).await;
+        Resultat { depots, projet, travail, reponse }
+    }
+
+    // Corps de création d'un projet reprenant le code et les dates de `modele`
+    fn creation(modele: &Projet, nom: &str) -> Value {
+        serde_json::json!({
+            "code": modele.code,
+            "nom": nom,
+            "description": modele.description,
+            "dateDebut": modele.date_debut,
+            "dateFinPrevue": modele.date_fin_prevue,
+            "statut": modele.statut,
+            "priorite": modele.priorite,
+        })
+    }
+
+    async fn code(reponse: actix_web::dev::ServiceResponse) -> String {
+        let corps: Value = test::read_body_json(reponse).await;
+        corps["code"].as_str().unwrap_or_default().to_string()
+    }
+
+    // Rien n'a changé : projet et travail intacts, aucune opération journalisée
+    async fn inchange(resultat: &Resultat) {
+        let depots = &resultat.depots;
+        assert!(depots.projets.find(resultat.projet.id).await.unwrap().is_some());
+        let travail = depots.travaux.find(resultat.travail.id).await.unwrap().unwrap();
+        assert_eq!(travail.projet_id, resultat.projet.id);
+        assert_eq!(travail.version, VERSION_INITIALE);
+        assert_eq!(depots.audit.list(&FiltreAudit::default(), &Pagination::tout("date")).await.unwrap().total, 0);
+    }
+
+    #[actix_web::test]
+    async fn politique_rejeter() {
+        let resultat = supprimer(PolitiqueSuppression::Rejeter, None, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::CONFLICT);
+        inchange(&resultat).await;
+        assert_eq!(code(resultat.reponse).await, "suppression_refusee");
+    }
+
+    #[actix_web::test]
+    async fn politique_cascade() {
+        let resultat = supprimer(PolitiqueSuppression::Cascade, None, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        let depots = &resultat.depots;
+        let projet = depots.projets.find_supprime(resultat.projet.id).await.unwrap().unwrap();
+        let travail = depots.travaux.find_supprime(resultat.travail.id).await.unwrap().unwrap();
+        assert_eq!(travail.suppression, projet.suppression);
+        assert_eq!(depots.audit.list(&FiltreAudit::default(), &Pagination::tout("date")).await.unwrap().total, 2);
+    }
+
+    #[actix_web::test]
+    async fn politique_reassigner() {
+        let cible = test_support::projet();
+        let resultat = supprimer(PolitiqueSuppression::Reassigner, Some(cible.id), Some(&cible)).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        let depots = &resultat.depots;
+        assert!(depots.projets.find_supprime(resultat.projet.id).await.unwrap().is_some());
+        assert_eq!(depots.travaux.find(resultat.travail.id).await.unwrap().unwrap().projet_id, cible.id);
+    }
+
+    #[actix_web::test]
+    async fn politique_reassigner_cible_invalide() {
+        // Cible absente du paramètre, inexistante ou supprimée : 400 / 422 sans aucune écriture
+        let resultat = supprimer(PolitiqueSuppression::Reassigner, None, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::BAD_REQUEST);
+        inchange(&resultat).await;
+
+        let resultat = supprimer(PolitiqueSuppression::Reassigner, Some(Uuid::new_v4()), None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        inchange(&resultat).await;
+
+        let cible = Projet { suppression: Some(Suppression { le: Utc::now(), par: None }), ..test_support::projet() };
+        let resultat = supprimer(PolitiqueSuppression::Reassigner, Some(cible.id), Some(&cible)).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        inchange(&resultat).await;
+    }
+
+    #[actix_web::test]
+    async fn journal_avant_apres() {
+        // Création, modification puis suppression : chaque écriture laisse son instantané avant / après
+        let depots = Repositories::memoire();
+        let configuration = configuration();
+        let (_, authorization) = connecter(&depots, &configuration, Role::Admin).await;
+        let corps = creation(&test_support::projet(), "Migration");
+        let requete = TestRequest::post().uri("/api/projets").insert_header(("Authorization", authorization.clone())).set_json(&corps);
+        let reponse = appeler(&depots, &configuration, requete).await;
+        assert_eq!(reponse.status(), StatusCode::CREATED);
+        let cree: Value = test::read_body_json(reponse).await;
+        let id: Uuid = serde_json::from_value(cree["id"].clone()).unwrap();
+
+        let requete = TestRequest::patch()
+            .uri(&format!("/api/projets/{}", id))
+            .insert_header(("Authorization", authorization.clone()))
+            .set_json(serde_json::json!({ "nom": "Migration v2" }));
+        assert_eq!(appeler(&depots, &configuration, requete).await.status(), StatusCode::OK);
+        let requete = TestRequest::delete().uri(&format!("/api/projets/{}", id)).insert_header(("Authorization", authorization));
+        assert_eq!(appeler(&depots, &configuration, requete).await.status(), StatusCode::NO_CONTENT);
+
+        let filtre = FiltreAudit { entite_id: Some(id), ..FiltreAudit::default() };
+        let evenements = depots.audit.list(&filtre, &Pagination::tout("date")).await.unwrap().elements;
+        let actions: Vec<_> = evenements.iter().map(|e| e.action.clone()).collect();
+        assert_eq!(actions, [ActionAudit::Creation, ActionAudit::Modification, ActionAudit::Suppression]);
+        let changement = |i: usize, champ: &str| {
+            evenements[i].changements.iter().find(|c| c.champ == champ).map(|c| (c.avant.clone(), c.apres.clone()))
+        };
+        assert_eq!(changement(0, "nom"), Some((Value::Null, "Migration".into())));
+        assert_eq!(changement(1, "nom"), Some(("Migration".into(), "Migration v2".into())));
+        assert_eq!(changement(1, "code"), None);
+        let (avant, apres) = changement(2, "suppression").unwrap();
+        assert_eq!(avant, Value::Null);
+        assert!(apres.is_object());
+    }
+
+    #[actix_web::test]
+    async fn code_reutilise_apres_suppression() {
+        // L'unicité du code ne porte que sur les projets non supprimés (index partiel) : le code
+        // libéré peut être repris, et le projet supprimé ne peut alors plus être restauré
+        let resultat = supprimer(PolitiqueSuppression::Cascade, None, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        let depots = &resultat.depots;
+        let configuration = configuration();
+        let (_, authorization) = connecter(depots, &configuration, Role::Admin).await;
+        let creer = || {
+            TestRequest::post()
+                .uri("/api/projets")
+                .insert_header(("Authorization", authorization.clone()))
+                .set_json(creation(&resultat.projet, "Reprise du code"))
+        };
+        assert_eq!(appeler(depots, &configuration, creer()).await.status(), StatusCode::CREATED);
+        assert_eq!(appeler(depots, &configuration, creer()).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let uri = format!("/api/projets/{}/restauration", resultat.projet.id);
+        let requete = TestRequest::post().uri(&uri).insert_header(("Authorization", authorization.clone()));
+        let reponse = appeler(depots, &configuration, requete).await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        assert_eq!(code(reponse).await, "restauration_refusee");
+        assert!(depots.travaux.find_supprime(resultat.travail.id).await.unwrap().is_some());
     }
 }
@@ -0,0 +1,471 @@
+// Chaîne de promotion Test → Formation → Production : un travail peut être déclaré comme la promotion
+// d'un travail de même application sur l'environnement précédent, et ne peut démarrer qu'une fois
+// tous ses prédécesseurs terminés
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::auth::UtilisateurCourant;
+use crate::config::Config;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::handlers::audit_handlers::journaliser;
+use crate::handlers::conflit_handlers::{appliquer_politique, detecter_conflits};
+use crate::handlers::gel_handlers::verifier_gels;
+use crate::handlers::references::{verifier_utilisateur_actif, verifier_utilisateurs_actifs};
+use crate::handlers::travail_handlers::{TravailEnregistreResponse, TravailResponse};
+use crate::models::{Application, ChecklistItem, Environnement, StatutChecklist, StatutTravail, Travail, TypeEntite, VERSION_INITIALE};
+use crate::permissions::Permission;
+use crate::repositories::{FiltreTravaux, Pagination, Repositories, TravailRepository};
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PromotionRequest {
+    #[serde(alias = "date_debut")]
+    pub date_debut: DateTime<Utc>,
+    #[serde(alias = "date_fin_prevue")]
+    pub date_fin_prevue: DateTime<Utc>,
+    // Admin uniquement : dérogation aux périodes de gel chevauchées par le nouveau travail
+    #[serde(alias = "justification_gel", default)]
+    pub justification_gel: Option<String>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/travaux/{travail_id}/promotions")
+            .route("", web::get().to(get_chaine_promotion))
+            .route("", web::post().to(promouvoir_travail))
+    );
+}
+
+fn travail_non_trouve() -> ApiError {
+    ApiError::NotFound("Travail non trouvé".to_string())
+}
+
+// Prédécesseurs du travail, du plus proche au plus lointain
+pub async fn predecesseurs(travail: &Travail, travaux: &dyn TravailRepository) -> Result<Vec<Travail>> {
+    let mut chaine: Vec<Travail> = Vec::new();
+    let mut suivant = travail.promotion_de;
+    while let Some(id) = suivant {
+        // Protection contre une chaîne rebouclée par des modifications d'environnement successives
+        if id == travail.id || chaine.iter().any(|t| t.id == id) {
+            break;
+        }
+        let Some(predecesseur) = travaux.find(id).await? else {
+            break;
+        };
+        suivant = predecesseur.promotion_de;
+        chaine.push(predecesseur);
+    }
+    Ok(chaine)
+}
+
+// Travail promu à partir de celui-ci (au plus un)
+pub async fn successeur(travail_id: Uuid, travaux: &dyn TravailRepository) -> Result<Option<Travail>> {
+    let filtre = FiltreTravaux {
+        promotion_de: Some(travail_id),
+        ..FiltreTravaux::default()
+    };
+    Ok(travaux.list(&filtre, &Pagination::tout("date_debut")).await?.elements.into_iter().next())
+}
+
+// Le travail promu doit exister, porter sur la même application et l'environnement précédent,
+// et ne pas avoir déjà été promu par un autre travail que `travail_id`
+pub async fn verifier_promotion_de(
+    validation: &mut Validation,
+    travaux: &dyn TravailRepository,
+    promotion_de: Option<Uuid>,
+    travail_id: Option<Uuid>,
+    application: &Application,
+    environnement: &Environnement,
+) -> Result<()> {
+    let Some(promotion_de) = promotion_de else {
+        return Ok(());
+    };
+    let Some(predecesseur) = travaux.find(promotion_de).await? else {
+        validation.ajouter("promotionDe", "inexistant", format!("Travail {} inexistant", promotion_de));
+        return Ok(());
+    };
+
+    match environnement.precedent() {
+        Some(precedent) if precedent == predecesseur.environnement => {}
+        Some(precedent) => validation.ajouter(
+            "promotionDe",
+            "environnement_invalide",
+            format!("Un travail {} ne peut promouvoir qu'un travail {}", environnement.as_str(), precedent.as_str()),
+        ),
+        None => validation.ajouter(
+            "promotionDe",
+            "environnement_invalide",
+            format!("Un travail {} ne peut pas être une promotion", environnement.as_str()),
+        ),
+    }
+    if predecesseur.application != *application {
+        validation.ajouter(
+            "promotionDe",
+            "application_differente",
+            format!("Le travail promu porte sur l'application {}", predecesseur.application.as_str()),
+        );
+    }
+    if let Some(existant) = successeur(promotion_de, travaux).await? {
+        if Some(existant.id) != travail_id {
+            validation.ajouter(
+                "promotionDe",
+                "deja_promu",
+                format!("Le travail {} est déjà promu par le travail {}", promotion_de, existant.id),
+            );
+        }
+    }
+    Ok(())
+}
+
+// Refuse le passage EnCours tant qu'un prédécesseur de la chaîne n'est pas terminé
+pub async fn verifier_predecesseurs_termines(
+    travail: &Travail,
+    vers: &StatutTravail,
+    travaux: &dyn TravailRepository,
+) -> Result<()> {
+    if *vers != StatutTravail::EnCours {
+        return Ok(());
+    }
+    let non_termines: Vec<String> = predecesseurs(travail, travaux).await?
+        .into_iter()
+        .rev()
+        .filter(|t| t.statut != StatutTravail::Termine)
+        .map(|t| format!("{} ({})", t.environnement.as_str(), t.statut.as_str()))
+        .collect();
+    if non_termines.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::Conflict {
+            code: "promotion_incomplete",
+            message: format!(
+                "Le travail ne peut démarrer qu'une fois ses prédécesseurs terminés : {}",
+                non_termines.join(", ")
+            ),
+        })
+    }
+}
+
+// Chaîne complète du travail, de l'environnement le plus en amont au plus en aval
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{travail_id}/promotions",
+    tag = "promotions",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    responses(
+        (status = 200, description = "Chaîne de promotion, de l'environnement le plus en amont au plus en aval", body = Vec<TravailResponse>),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_chaine_promotion(
+    _courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    travaux: web::Data<dyn TravailRepository>,
+) -> Result<HttpResponse> {
+    let Some(travail) = travaux.find(travail_id.into_inner()).await? else {
+        return Err(travail_non_trouve());
+    };
+
+    let mut chaine = predecesseurs(&travail, travaux.as_ref()).await?;
+    chaine.reverse();
+    let mut courant = travail;
+    loop {
+        let suivant = successeur(courant.id, travaux.as_ref()).await?;
+        chaine.push(courant);
+        match suivant {
+            Some(suivant) if !chaine.iter().any(|t| t.id == suivant.id) => courant = suivant,
+            _ => break,
+        }
+    }
+
+    let chaine: Vec<TravailResponse> = chaine.into_iter().map(TravailResponse::from).collect();
+    Ok(HttpResponse::Ok().json(chaine))
+}
+
+// Crée le travail de l'environnement suivant : même projet, type, application, description, responsable,
+// équipe et checklist (remise à NonDemarre, échéances décalées avec les dates)
+#[utoipa::path(
+    post,
+    path = "/api/travaux/{travail_id}/promotions",
+    tag = "promotions",
+    params(("travail_id" = Uuid, Path, description = "Identifiant du travail")),
+    request_body = PromotionRequest,
+    responses(
+        (status = 201, description = "Travail créé sur l'environnement suivant", body = TravailEnregistreResponse),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 409, description = "Promotion impossible, déjà promu, conflit de planning ou période de gel", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn promouvoir_travail(
+    courant: UtilisateurCourant,
+    travail_id: web::Path<Uuid>,
+    promotion_req: web::Json<PromotionRequest>,
+    depots: Repositories,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererTravaux)?;
+    let unite = depots.transaction().await?;
+    let Repositories { travaux, checklist, utilisateurs, gels, audit, .. } = &*unite;
+    let Some(source) = travaux.find(travail_id.into_inner()).await? else {
+        return Err(travail_non_trouve());
+    };
+    let Some(environnement) = source.environnement.suivant() else {
+        return Err(ApiError::Conflict {
+            code: "promotion_impossible",
+            message: format!("Un travail {} ne peut pas être promu", source.environnement.as_str()),
+        });
+    };
+    if let Some(existant) = successeur(source.id, travaux.as_ref()).await? {
+        return Err(ApiError::Conflict {
+            code: "deja_promu",
+            message: format!("Le travail est déjà promu par le travail {}", existant.id),
+        });
+    }
+
+    let promotion_req = promotion_req.into_inner();
+    let mut validation = Validation::new();
+    validation.periode(promotion_req.date_debut, promotion_req.date_fin_prevue);
+    verifier_utilisateur_actif(&mut validation, utilisateurs.as_ref(), source.responsable, "responsable").await?;
+    verifier_utilisateurs_actifs(&mut validation, utilisateurs.as_ref(), &source.equipe, "equipe").await?;
+    validation.verifier()?;
+
+    let travail = Travail {
+        id: Uuid::new_v4(),
+        projet_id: source.projet_id,
+        type_travail: source.type_travail.clone(),
+        application: source.application.clone(),
+        environnement,
+        description: source.description.clone(),
+        date_debut: promotion_req.date_debut,
+        date_fin_prevue: promotion_req.date_fin_prevue,
+        statut: StatutTravail::Planifie,
+        responsable: source.responsable,
+        equipe: source.equipe.clone(),
+        promotion_de: Some(source.id),
+        version: VERSION_INITIALE,
+        suppression: None,
+    };
+    let conflits = detecter_conflits(&travail, travaux.as_ref()).await?;
+    appliquer_politique(&config, &conflits, &[])?;
+    let derogations = verifier_gels(&travail, None, promotion_req.justification_gel.as_deref(), &courant, gels.as_ref()).await?;
+
+    travaux.insert(&travail).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Travail, travail.id, None, Some(&travail)).await?;
+    for derogation in &derogations {
+        gels.insert_derogation(derogation).await?;
+    }
+
+    let decalage = travail.date_debut - source.date_debut;
+    for item in checklist.list_by_travail(source.id).await? {
+        let item = ChecklistItem {
+            id: Uuid::new_v4(),
+            travail_id: travail.id,
+            statut: StatutChecklist::NonDemarre,
+            date_echeance: item.date_echeance.map(|date| date + decalage),
+            commentaires: None,
+            ..item
+        };
+        checklist.insert(&item).await?;
+        journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item.id, None, Some(&item)).await?;
+    }
+    unite.valider().await?;
+
+    Ok(HttpResponse::Created().json(TravailEnregistreResponse {
+        travail: TravailResponse::from(travail),
+        conflits,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Role};
+
+    // Travail de test (avec un item de checklist) d'un projet existant, sous la responsabilité d'un admin
+    struct Contexte {
+        depots: Repositories,
+        config: Config,
+        authorization: String,
+        source: Travail,
+    }
+
+    async fn contexte() -> Contexte {
+        let depots = Repositories::memoire();
+        let config = configuration();
+        let (admin, authorization) = connecter(&depots, &config, Role::Admin).await;
+        let projet = test_support::projet();
+        depots.projets.insert(&projet).await.unwrap();
+        let source = Travail {
+            projet_id: projet.id,
+            environnement: Environnement::Test,
+            description: "Rehaussement".to_string(),
+            responsable: admin.id,
+            equipe: Vec::new(),
+            ..test_support::travail()
+        };
+        depots.travaux.insert(&source).await.unwrap();
+        depots.checklist.insert(&test_support::checklist_item(&source)).await.unwrap();
+        Contexte { depots, config, authorization, source }
+    }
+
+    impl Contexte {
+        async fn appeler(&self, requete: TestRequest) -> ServiceResponse {
+            appeler(&self.depots, &self.config, requete.insert_header(("Authorization", self.authorization.clone()))).await
+        }
+
+        async fn promouvoir(&self, travail: &Travail) -> ServiceResponse {
+            let uri = format!("/api/travaux/{}/promotions", travail.id);
+            let corps = json!({"dateDebut": test_support::date(48), "dateFinPrevue": test_support::date(50)});
+            self.appeler(TestRequest::post().uri(&uri).set_json(corps)).await
+        }
+    }
+
+    async fn promotion(reponse: ServiceResponse) -> Travail {
+        assert_eq!(reponse.status(), StatusCode::CREATED);
+        let corps: Value = test::read_body_json(reponse).await;
+        serde_json::from_value(corps).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn promotion_reprise_apres_suppression() {
+        // Un travail n'a qu'une promotion non supprimée (index partiel) : une fois la première
+        // supprimée, une nouvelle promotion est possible, et la première ne peut plus revenir
+        let ctx = contexte().await;
+        let premiere = promotion(ctx.promouvoir(&ctx.source).await).await;
+        let uri = format!("/api/travaux/{}", premiere.id);
+        assert_eq!(ctx.appeler(TestRequest::delete().uri(&uri)).await.status(), StatusCode::NO_CONTENT);
+
+        let seconde = promotion(ctx.promouvoir(&ctx.source).await).await;
+        assert_eq!(seconde.promotion_de, Some(ctx.source.id));
+        let uri = format!("/api/travaux/{}/restauration", premiere.id);
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri)).await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["code"], "restauration_refusee");
+    }
+
+    #[actix_web::test]
+    async fn chaine_test_formation_production() {
+        let ctx = contexte().await;
+        let item = ChecklistItem {
+            statut: StatutChecklist::Termine,
+            date_echeance: Some(test_support::date(1)),
+            commentaires: Some("Fait".to_string()),
+            ordre: 1,
+            ..test_support::checklist_item(&ctx.source)
+        };
+        ctx.depots.checklist.insert(&item).await.unwrap();
+
+        let formation = promotion(ctx.promouvoir(&ctx.source).await).await;
+        assert_eq!((formation.environnement.clone(), formation.promotion_de), (Environnement::Formation, Some(ctx.source.id)));
+        assert_eq!((formation.statut.clone(), formation.description.as_str()), (StatutTravail::Planifie, "Rehaussement"));
+        // Checklist reprise, remise à zéro, échéances décalées comme les dates (+48 h)
+        let copie = ctx.depots.checklist.list_by_travail(formation.id).await.unwrap();
+        assert_eq!(copie.len(), 2);
+        assert!(copie.iter().all(|i| i.statut == StatutChecklist::NonDemarre && i.commentaires.is_none()));
+        assert_eq!(copie[1].date_echeance, Some(test_support::date(49)));
+
+        let production = promotion(ctx.promouvoir(&formation).await).await;
+        assert_eq!(production.environnement, Environnement::Production);
+
+        let uri = format!("/api/travaux/{}/promotions", formation.id);
+        let chaine: Vec<Value> = test::read_body_json(ctx.appeler(TestRequest::get().uri(&uri)).await).await;
+        let ids: Vec<&str> = chaine.iter().map(|t| t["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, [ctx.source.id.to_string(), formation.id.to_string(), production.id.to_string()]);
+    }
+
+    #[actix_web::test]
+    async fn promotions_refusees() {
+        let ctx = contexte().await;
+        let formation = promotion(ctx.promouvoir(&ctx.source).await).await;
+        let production = promotion(ctx.promouvoir(&formation).await).await;
+
+        for (travail, code) in [(&production, "promotion_impossible"), (&ctx.source, "deja_promu")] {
+            let reponse = ctx.promouvoir(travail).await;
+            assert_eq!(reponse.status(), StatusCode::CONFLICT);
+            let corps: Value = test::read_body_json(reponse).await;
+            assert_eq!(corps["code"], code);
+        }
+
+        let uri = format!("/api/travaux/{}/promotions", Uuid::new_v4());
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(json!({"dateDebut": test_support::date(0), "dateFinPrevue": test_support::date(1)}))).await;
+        assert_eq!(reponse.status(), StatusCode::NOT_FOUND);
+        let autre = Travail { id: Uuid::new_v4(), ..ctx.source.clone() };
+        ctx.depots.travaux.insert(&autre).await.unwrap();
+        let uri = format!("/api/travaux/{}/promotions", autre.id);
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(json!({"dateDebut": test_support::date(2), "dateFinPrevue": test_support::date(1)}))).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Déclaration directe : environnement précédent, même application, un seul successeur
+        let creation = |environnement: &str, application: &str, promotion_de: Uuid| {
+            TestRequest::post().uri("/api/travaux").set_json(json!({
+                "projetId": ctx.source.projet_id,
+                "type": "migration",
+                "application": application,
+                "environnement": environnement,
+                "description": "Promotion manuelle",
+                "dateDebut": test_support::date(100),
+                "dateFinPrevue": test_support::date(102),
+                "statut": "planifié",
+                "responsable": ctx.source.responsable,
+                "equipe": [],
+                "promotionDe": promotion_de,
+            }))
+        };
+        for (requete, code) in [
+            (creation("production", "espresso_gfr", autre.id), "environnement_invalide"),
+            (creation("test", "espresso_gfr", autre.id), "environnement_invalide"),
+            (creation("formation", "espresso_grm", autre.id), "application_differente"),
+            (creation("formation", "espresso_gfr", ctx.source.id), "deja_promu"),
+            (creation("formation", "espresso_gfr", Uuid::new_v4()), "inexistant"),
+        ] {
+            let reponse = ctx.appeler(requete).await;
+            assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let corps: Value = test::read_body_json(reponse).await;
+            assert_eq!((corps["erreurs"][0]["field"].as_str(), corps["erreurs"][0]["code"].as_str()), (Some("promotionDe"), Some(code)));
+        }
+        let reponse = ctx.appeler(creation("formation", "espresso_gfr", autre.id)).await;
+        assert_eq!(promotion(reponse).await.promotion_de, Some(autre.id));
+    }
+
+    #[actix_web::test]
+    async fn demarrage_apres_les_predecesseurs() {
+        let ctx = contexte().await;
+        let formation = promotion(ctx.promouvoir(&ctx.source).await).await;
+        let production = promotion(ctx.promouvoir(&formation).await).await;
+
+        // Seul le passage EnCours est conditionné ; la liste suit la chaîne depuis l'amont
+        let travaux = ctx.depots.travaux.as_ref();
+        assert!(verifier_predecesseurs_termines(&production, &StatutTravail::Annule, travaux).await.is_ok());
+        assert!(verifier_predecesseurs_termines(&ctx.source, &StatutTravail::EnCours, travaux).await.is_ok());
+        match verifier_predecesseurs_termines(&production, &StatutTravail::EnCours, travaux).await {
+            Err(ApiError::Conflict { code: "promotion_incomplete", message }) => {
+                assert!(message.ends_with("Test (Planifie), Formation (Planifie)"), "{message}");
+            }
+            autre => panic!("promotion_incomplete attendu : {:?}", autre),
+        }
+
+        let uri = format!("/api/travaux/{}/transitions", formation.id);
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(json!({"statut": "en_cours", "raison": "Démarrage"}))).await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["code"], "promotion_incomplete");
+        assert_eq!(ctx.depots.travaux.find(formation.id).await.unwrap().unwrap().statut, StatutTravail::Planifie);
+
+        let termine = Travail { statut: StatutTravail::Termine, ..ctx.source.clone() };
+        ctx.depots.travaux.update(&termine).await.unwrap();
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(json!({"statut": "en_cours", "raison": "Démarrage"}))).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+    }
+}
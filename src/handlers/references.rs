@@ -0,0 +1,72 @@
+// Vérification des références entre entités (projet, utilisateurs) avant écriture
+
+use serde::Deserialize;
+use uuid::Uuid;
+use utoipa::IntoParams;
+
+use crate::error::{ApiError, Result};
+use crate::repositories::{ProjetRepository, UtilisateurRepository};
+use crate::validation::Validation;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SuppressionQuery {
+    pub reassigner_a: Option<Uuid>,
+}
+
+pub async fn verifier_projet(
+    validation: &mut Validation,
+    projets: &dyn ProjetRepository,
+    projet_id: Uuid,
+    champ: &str,
+) -> Result<()> {
+    if projets.find(projet_id).await?.is_none() {
+        validation.ajouter(champ, "inexistant", format!("Projet {} inexistant", projet_id));
+    }
+    Ok(())
+}
+
+// L'utilisateur doit exister et être actif pour se voir attribuer du travail
+pub async fn verifier_utilisateur_actif(
+    validation: &mut Validation,
+    utilisateurs: &dyn UtilisateurRepository,
+    utilisateur_id: Uuid,
+    champ: &str,
+) -> Result<()> {
+    match utilisateurs.find(utilisateur_id).await? {
+        None => validation.ajouter(champ, "inexistant", format!("Utilisateur {} inexistant", utilisateur_id)),
+        Some(u) if !u.actif => validation.ajouter(champ, "inactif", format!("Utilisateur {} inactif", utilisateur_id)),
+        Some(_) => {}
+    }
+    Ok(())
+}
+
+pub async fn verifier_utilisateurs_actifs(
+    validation: &mut Validation,
+    utilisateurs: &dyn UtilisateurRepository,
+    utilisateur_ids: &[Uuid],
+    champ: &str,
+) -> Result<()> {
+    for utilisateur_id in utilisateur_ids {
+        verifier_utilisateur_actif(validation, utilisateurs, *utilisateur_id, champ).await?;
+    }
+    Ok(())
+}
+
+pub fn suppression_refusee(message: String) -> ApiError {
+    ApiError::Conflict {
+        code: "suppression_refusee",
+        message,
+    }
+}
+
+pub fn restauration_refusee(message: String) -> ApiError {
+    ApiError::Conflict {
+        code: "restauration_refusee",
+        message,
+    }
+}
+
+pub fn cible_reassignation_manquante() -> ApiError {
+    ApiError::BadRequest("Le paramètre reassigner_a est obligatoire avec la politique de réassignation".to_string())
+}
@@ -0,0 +1,40 @@
+// Outillage des tests de handlers : application complète sur les dépôts en mémoire,
+// utilisateurs connectés par jeton
+
+use actix_web::dev::ServiceResponse;
+use actix_web::test::{self, TestRequest};
+use actix_web::{web, App};
+use chrono::Utc;
+
+use crate::auth;
+use crate::config::{Config, PolitiqueConflits, PolitiqueSuppression};
+use crate::models::{test_support, Role, Utilisateur};
+use crate::repositories::Repositories;
+
+pub fn configuration() -> Config {
+    Config {
+        suppression_projets: PolitiqueSuppression::Rejeter,
+        suppression_utilisateurs: PolitiqueSuppression::Rejeter,
+        conflits_planning: PolitiqueConflits::Avertir,
+        cle_jetons: auth::cle_aleatoire().unwrap(),
+        duree_jetons: chrono::Duration::hours(1),
+        approbations_production: 1,
+        retention_suppressions: chrono::Duration::days(30),
+    }
+}
+
+// Crée un utilisateur actif du rôle donné ; renvoie aussi la valeur de son en-tête Authorization
+pub async fn connecter(depots: &Repositories, configuration: &Config, role: Role) -> (Utilisateur, String) {
+    let utilisateur = test_support::utilisateur(role);
+    depots.utilisateurs.insert(&utilisateur).await.unwrap();
+    let jeton = auth::emettre_jeton(&configuration.cle_jetons, utilisateur.id, Utc::now() + configuration.duree_jetons);
+    (utilisateur, format!("Bearer {}", jeton))
+}
+
+pub async fn appeler(depots: &Repositories, configuration: &Config, requete: TestRequest) -> ServiceResponse {
+    let app = test::init_service(
+        App::new().configure(|cfg| crate::configurer(cfg, web::Data::new(configuration.clone()), depots)),
+    )
+    .await;
+    test::call_service(&app, requete.to_request()).await
+}
@@ -0,0 +1,67 @@
+// Éléments communs aux endpoints de transition de statut (projets et travaux)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+use crate::error::ApiError;
+use crate::models::{StatutEntite, TransitionStatut, TypeEntite};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionRequest<S> {
+    pub statut: S,
+    pub raison: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionStatutResponse {
+    pub id: Uuid,
+    pub de: StatutEntite,
+    pub vers: StatutEntite,
+    pub raison: Option<String>,
+    pub date: DateTime<Utc>,
+}
+
+impl From<TransitionStatut> for TransitionStatutResponse {
+    fn from(t: TransitionStatut) -> Self {
+        TransitionStatutResponse {
+            id: t.id,
+            de: t.de,
+            vers: t.vers,
+            raison: t.raison,
+            date: t.date,
+        }
+    }
+}
+
+pub fn nouvelle_transition(
+    entite: TypeEntite,
+    entite_id: Uuid,
+    de: impl Into<StatutEntite>,
+    vers: impl Into<StatutEntite>,
+    raison: Option<String>,
+) -> TransitionStatut {
+    TransitionStatut {
+        id: Uuid::new_v4(),
+        entite,
+        entite_id,
+        de: de.into(),
+        vers: vers.into(),
+        raison,
+        date: Utc::now(),
+    }
+}
+
+pub fn transition_interdite(de: impl Into<StatutEntite>, vers: impl Into<StatutEntite>) -> ApiError {
+    ApiError::TransitionInterdite {
+        de: de.into(),
+        vers: vers.into(),
+    }
+}
+
+pub fn raison_manquante() -> ApiError {
+    ApiError::BadRequest("Une raison est obligatoire pour changer de statut".to_string())
+}
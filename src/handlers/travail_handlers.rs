@@ -1,30 +1,64 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use std::collections::HashMap;
-use std::sync::Mutex;
+use utoipa::{IntoParams, ToSchema};
 use chrono::{DateTime, Utc};
 
-use crate::models::{Travail, TypeTravail, Application, Environnement, StatutTravail};
+use crate::auth::UtilisateurCourant;
+use crate::permissions::Permission;
+use crate::config::Config;
+use crate::conflits::Conflit;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::models::{modele_applicable, ActionAudit, Suppression, Travail, TypeTravail, Application, Environnement, StatutTravail, VERSION_INITIALE};
+use crate::models::TypeEntite;
+use crate::repositories::{AuditRepository, ProjetRepository, TransitionRepository, TravailRepository, UtilisateurRepository};
+use crate::handlers::references::{restauration_refusee, verifier_projet, verifier_utilisateur_actif, verifier_utilisateurs_actifs};
+use crate::validation::Validation;
+use crate::handlers::pagination::{pagination, reponse_page};
+use crate::repositories::filtres::TRI_TRAVAUX;
+use crate::repositories::{FiltreTravaux, Pagination, Repositories};
+use crate::handlers::approbation_handlers::verifier_approbation;
+use crate::handlers::conflit_handlers::{appliquer_politique, detecter_conflits};
+use crate::handlers::corbeille_handlers::supprimes_listes;
+use crate::handlers::dependance_handlers::{verifier_dependances, verifier_dependances_terminees};
+use crate::handlers::gel_handlers::verifier_gels;
+use crate::handlers::promotion_handlers::{successeur, verifier_predecesseurs_termines, verifier_promotion_de};
+use crate::handlers::audit_handlers::{historique, journaliser, journaliser_action, reponse_historique, EvenementAuditResponse, HistoriqueQuery};
+use crate::handlers::transitions::{nouvelle_transition, raison_manquante, transition_interdite, TransitionRequest, TransitionStatutResponse};
+use crate::handlers::patch;
+use crate::handlers::versions::{ecriture_refusee, etag, VersionAttendue};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct CreateTravailRequest {
+    #[serde(alias = "projet_id")]
     pub projet_id: Uuid,
+    #[serde(rename = "type", alias = "type_travail")]
     pub type_travail: TypeTravail,
     pub application: Application,
     pub environnement: Environnement,
     pub description: String,
+    #[serde(alias = "date_debut")]
     pub date_debut: DateTime<Utc>,
+    #[serde(alias = "date_fin_prevue")]
     pub date_fin_prevue: DateTime<Utc>,
     pub statut: StatutTravail,
     pub responsable: Uuid,
     pub equipe: Vec<Uuid>,
+    // Travail de l'environnement précédent dont celui-ci est la promotion
+    #[serde(alias = "promotion_de", default)]
+    pub promotion_de: Option<Uuid>,
+    // Admin uniquement : dérogation aux périodes de gel chevauchées par le travail
+    #[serde(alias = "justification_gel", default)]
+    pub justification_gel: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct TravailResponse {
     pub id: Uuid,
     pub projet_id: Uuid,
+    #[serde(rename = "type")]
     pub type_travail: TypeTravail,
     pub application: Application,
     pub environnement: Environnement,
@@ -34,25 +68,38 @@ pub struct TravailResponse {
     pub statut: StatutTravail,
     pub responsable: Uuid,
     pub equipe: Vec<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promotion_de: Option<Uuid>,
+    pub version: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
 }
 
-pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api/travaux")
-            .route("", web::get().to(get_travaux))
-            .route("", web::post().to(create_travail))
-            .route("/{id}", web::get().to(get_travail))
-            .route("/{id}", web::put().to(update_travail))
-            .route("/{id}", web::delete().to(delete_travail))
-            .route("/projet/{projet_id}", web::get().to(get_travaux_by_projet))
-    );
+impl From<Travail> for TravailResponse {
+    fn from(t: Travail) -> Self {
+        TravailResponse {
+            id: t.id,
+            projet_id: t.projet_id,
+            type_travail: t.type_travail,
+            application: t.application,
+            environnement: t.environnement,
+            description: t.description,
+            date_debut: t.date_debut,
+            date_fin_prevue: t.date_fin_prevue,
+            statut: t.statut,
+            responsable: t.responsable,
+            equipe: t.equipe,
+            promotion_de: t.promotion_de,
+            version: t.version,
+            suppression: t.suppression,
+        }
+    }
 }
 
-pub async fn get_travaux(travaux: web::Data<Mutex<HashMap<Uuid, Travail>>>) -> Result<HttpResponse> {
-    let travaux_map = travaux.lock().unwrap();
-    let travaux_list: Vec<TravailResponse> = travaux_map.values()
-        .map(|t| TravailResponse {
-            id: t.id,
+// Requête de modification complète équivalente au travail, base d'un PATCH
+impl From<&Travail> for CreateTravailRequest {
+    fn from(t: &Travail) -> Self {
+        CreateTravailRequest {
             projet_id: t.projet_id,
             type_travail: t.type_travail.clone(),
             application: t.application.clone(),
@@ -63,156 +110,769 @@ pub async fn get_travaux(travaux: web::Data<Mutex<HashMap<Uuid, Travail>>>) -> R
             statut: t.statut.clone(),
             responsable: t.responsable,
             equipe: t.equipe.clone(),
+            promotion_de: t.promotion_de,
+            justification_gel: None,
+        }
+    }
+}
+
+// Réponse de création / modification : le travail et ses conflits de planning éventuels
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TravailEnregistreResponse {
+    #[serde(flatten)]
+    pub travail: TravailResponse,
+    pub conflits: Vec<Conflit>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListeTravauxQuery {
+    pub projet_id: Option<Uuid>,
+    pub statut: Option<StatutTravail>,
+    pub application: Option<Application>,
+    pub environnement: Option<Environnement>,
+    pub type_travail: Option<TypeTravail>,
+    pub responsable: Option<Uuid>,
+    pub promotion_de: Option<Uuid>,
+    pub du: Option<DateTime<Utc>>,
+    pub au: Option<DateTime<Utc>>,
+    // Admin : inclure les travaux supprimés (restaurables)
+    pub inclure_supprimes: Option<bool>,
+    pub sort: Option<String>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl ListeTravauxQuery {
+    fn filtre(&self, courant: &UtilisateurCourant) -> Result<FiltreTravaux> {
+        Ok(FiltreTravaux {
+            projet_id: self.projet_id,
+            statut: self.statut.clone(),
+            application: self.application.clone(),
+            environnement: self.environnement.clone(),
+            type_travail: self.type_travail.clone(),
+            responsable: self.responsable,
+            promotion_de: self.promotion_de,
+            du: self.du,
+            au: self.au,
+            supprimes: supprimes_listes(courant, self.inclure_supprimes)?,
         })
-        .collect();
+    }
+
+    fn pagination(&self) -> Result<Pagination> {
+        pagination(self.sort.as_deref(), self.offset, self.limit, TRI_TRAVAUX, "date_debut")
+    }
+}
 
-    Ok(HttpResponse::Ok().json(travaux_list))
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/travaux")
+            .route("", web::get().to(get_travaux))
+            .route("", web::post().to(create_travail))
+            .route("/{id}", web::get().to(get_travail))
+            .route("/{id}", web::put().to(update_travail))
+            .route("/{id}", web::patch().to(patch_travail))
+            .route("/{id}", web::delete().to(delete_travail))
+            .route("/{id}/transitions", web::get().to(get_transitions_travail))
+            .route("/{id}/transitions", web::post().to(transition_travail))
+            .route("/{id}/historique", web::get().to(get_historique_travail))
+            .route("/{id}/restauration", web::post().to(restaurer_travail))
+            .route("/projet/{projet_id}", web::get().to(get_travaux_by_projet))
+    );
 }
 
+impl CreateTravailRequest {
+    // Contrôles de forme, puis existence du projet, du responsable et de l'équipe (utilisateurs actifs)
+    // et cohérence du travail promu ; `travail_id` : travail modifié, None à la création
+    async fn valider(
+        &self,
+        travail_id: Option<Uuid>,
+        projets: &dyn ProjetRepository,
+        utilisateurs: &dyn UtilisateurRepository,
+        travaux: &dyn TravailRepository,
+    ) -> Result<Validation> {
+        let mut validation = Validation::new();
+        validation.texte("description", &self.description, usize::MAX);
+        validation.periode(self.date_debut, self.date_fin_prevue);
+        verifier_projet(&mut validation, projets, self.projet_id, "projetId").await?;
+        verifier_utilisateur_actif(&mut validation, utilisateurs, self.responsable, "responsable").await?;
+        verifier_utilisateurs_actifs(&mut validation, utilisateurs, &self.equipe, "equipe").await?;
+        verifier_promotion_de(&mut validation, travaux, self.promotion_de, travail_id, &self.application, &self.environnement).await?;
+        Ok(validation)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux",
+    tag = "travaux",
+    params(ListeTravauxQuery),
+    responses(
+        (status = 200, description = "Travaux de la page demandée", body = Vec<TravailResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 403, description = "inclure_supprimes : permission gerer_corbeille manquante", body = CorpsErreur),
+    ),
+)]
+pub async fn get_travaux(
+    courant: UtilisateurCourant,
+    query: web::Query<ListeTravauxQuery>,
+    repository: web::Data<dyn TravailRepository>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let pagination = query.pagination()?;
+    let page = repository.list(&query.filtre(&courant)?, &pagination).await?;
+
+    Ok(reponse_page(page, TravailResponse::from))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{id}",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail")),
+    responses(
+        (status = 200, description = "Travail", body = TravailResponse, headers(("ETag" = String, description = "Version du travail"))),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+    ),
+)]
 pub async fn get_travail(
-    web::Path(id): web::Path<Uuid>,
-    travaux: web::Data<Mutex<HashMap<Uuid, Travail>>>,
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn TravailRepository>,
 ) -> Result<HttpResponse> {
-    let travaux_map = travaux.lock().unwrap();
-    
-    match travaux_map.get(&id) {
-        Some(travail) => {
-            let response = TravailResponse {
-                id: travail.id,
-                projet_id: travail.projet_id,
-                type_travail: travail.type_travail.clone(),
-                application: travail.application.clone(),
-                environnement: travail.environnement.clone(),
-                description: travail.description.clone(),
-                date_debut: travail.date_debut,
-                date_fin_prevue: travail.date_fin_prevue,
-                statut: travail.statut.clone(),
-                responsable: travail.responsable,
-                equipe: travail.equipe.clone(),
-            };
-            Ok(HttpResponse::Ok().json(response))
-        }
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Travail non trouvé"
-        })))
+    match repository.find(id.into_inner()).await? {
+        Some(travail) => Ok(HttpResponse::Ok().insert_header(etag(travail.version)).json(TravailResponse::from(travail))),
+        None => Err(ApiError::NotFound("Travail non trouvé".to_string()))
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/travaux/projet/{projet_id}",
+    tag = "travaux",
+    params(("projet_id" = Uuid, Path, description = "Identifiant du projet"), ListeTravauxQuery),
+    responses(
+        (status = 200, description = "Travaux du projet", body = Vec<TravailResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 403, description = "inclure_supprimes : permission gerer_corbeille manquante", body = CorpsErreur),
+    ),
+)]
 pub async fn get_travaux_by_projet(
-    web::Path(projet_id): web::Path<Uuid>,
-    travaux: web::Data<Mutex<HashMap<Uuid, Travail>>>,
+    courant: UtilisateurCourant,
+    projet_id: web::Path<Uuid>,
+    query: web::Query<ListeTravauxQuery>,
+    repository: web::Data<dyn TravailRepository>,
 ) -> Result<HttpResponse> {
-    let travaux_map = travaux.lock().unwrap();
-    let travaux_list: Vec<TravailResponse> = travaux_map.values()
-        .filter(|t| t.projet_id == projet_id)
-        .map(|t| TravailResponse {
-            id: t.id,
-            projet_id: t.projet_id,
-            type_travail: t.type_travail.clone(),
-            application: t.application.clone(),
-            environnement: t.environnement.clone(),
-            description: t.description.clone(),
-            date_debut: t.date_debut,
-            date_fin_prevue: t.date_fin_prevue,
-            statut: t.statut.clone(),
-            responsable: t.responsable,
-            equipe: t.equipe.clone(),
-        })
-        .collect();
+    let query = query.into_inner();
+    let pagination = query.pagination()?;
+    let filtre = FiltreTravaux {
+        projet_id: Some(projet_id.into_inner()),
+        ..query.filtre(&courant)?
+    };
+    let page = repository.list(&filtre, &pagination).await?;
 
-    Ok(HttpResponse::Ok().json(travaux_list))
+    Ok(reponse_page(page, TravailResponse::from))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/travaux",
+    tag = "travaux",
+    request_body = CreateTravailRequest,
+    responses(
+        (status = 201, description = "Travail créé, avec ses conflits de planning éventuels", body = TravailEnregistreResponse),
+        (status = 409, description = "Conflit de planning, période de gel ou promotion incomplète", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
 pub async fn create_travail(
+    courant: UtilisateurCourant,
     travail_req: web::Json<CreateTravailRequest>,
-    travaux: web::Data<Mutex<HashMap<Uuid, Travail>>>,
+    depots: Repositories,
+    config: web::Data<Config>,
 ) -> Result<HttpResponse> {
-    let mut travaux_map = travaux.lock().unwrap();
-    
+    courant.exiger(Permission::GererTravaux)?;
+    let unite = depots.transaction().await?;
+    let Repositories { travaux: repository, checklist, modeles_checklist: modeles, projets, utilisateurs, transitions, approbations, gels, audit, .. } = &*unite;
+    let travail_req = travail_req.into_inner();
+    let mut validation = travail_req.valider(None, projets.as_ref(), utilisateurs.as_ref(), repository.as_ref()).await?;
+    // Un travail naît planifié ; les autres statuts s'atteignent par transitions
+    if travail_req.statut != StatutTravail::Planifie {
+        validation.ajouter("statut", "statut_initial", "Un travail est créé au statut planifié");
+    }
+    validation.verifier()?;
+
     let travail = Travail {
         id: Uuid::new_v4(),
         projet_id: travail_req.projet_id,
-        type_travail: travail_req.type_travail.clone(),
-        application: travail_req.application.clone(),
-        environnement: travail_req.environnement.clone(),
-        description: travail_req.description.clone(),
+        type_travail: travail_req.type_travail,
+        application: travail_req.application,
+        environnement: travail_req.environnement,
+        description: travail_req.description,
         date_debut: travail_req.date_debut,
         date_fin_prevue: travail_req.date_fin_prevue,
-        statut: travail_req.statut.clone(),
+        statut: travail_req.statut,
         responsable: travail_req.responsable,
-        equipe: travail_req.equipe.clone(),
+        equipe: travail_req.equipe,
+        promotion_de: travail_req.promotion_de,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
+    verifier_predecesseurs_termines(&travail, &travail.statut, repository.as_ref()).await?;
+    verifier_approbation(&travail, &travail.statut, false, approbations.as_ref(), transitions.as_ref(), &config).await?;
+    let conflits = detecter_conflits(&travail, repository.as_ref()).await?;
+    appliquer_politique(&config, &conflits, &[])?;
+    let derogations = verifier_gels(&travail, None, travail_req.justification_gel.as_deref(), &courant, gels.as_ref()).await?;
 
-    travaux_map.insert(travail.id, travail.clone());
-
-    let response = TravailResponse {
-        id: travail.id,
-        projet_id: travail.projet_id,
-        type_travail: travail.type_travail,
-        application: travail.application,
-        environnement: travail.environnement,
-        description: travail.description,
-        date_debut: travail.date_debut,
-        date_fin_prevue: travail.date_fin_prevue,
-        statut: travail.statut,
-        responsable: travail.responsable,
-        equipe: travail.equipe,
-    };
+    repository.insert(&travail).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Travail, travail.id, None, Some(&travail)).await?;
+    for derogation in &derogations {
+        gels.insert_derogation(derogation).await?;
+    }
 
-    Ok(HttpResponse::Created().json(response))
+    // Checklist pré-remplie à partir du modèle le plus spécifique pour ce type de travail
+    let modeles_type = modeles.list_by_type(&travail.type_travail).await?;
+    if let Some(modele) = modele_applicable(&modeles_type, &travail) {
+        for item in modele.instancier(&travail) {
+            checklist.insert(&item).await?;
+            journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item.id, None, Some(&item)).await?;
+        }
+    }
+    unite.valider().await?;
+
+    Ok(HttpResponse::Created().insert_header(etag(travail.version)).json(TravailEnregistreResponse {
+        travail: TravailResponse::from(travail),
+        conflits,
+    }))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/travaux/{id}",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la modification est refusée si le travail a changé depuis")),
+    request_body = CreateTravailRequest,
+    responses(
+        (status = 200, description = "Travail modifié, avec ses conflits de planning éventuels", body = TravailEnregistreResponse, headers(("ETag" = String, description = "Nouvelle version du travail"))),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 409, description = "Transition interdite, conflit de planning, période de gel, approbation requise ou dépendances non terminées", body = CorpsErreur),
+        (status = 412, description = "Travail modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
 pub async fn update_travail(
-    web::Path(id): web::Path<Uuid>,
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
     travail_req: web::Json<CreateTravailRequest>,
-    travaux: web::Data<Mutex<HashMap<Uuid, Travail>>>,
+    depots: Repositories,
+    config: web::Data<Config>,
 ) -> Result<HttpResponse> {
-    let mut travaux_map = travaux.lock().unwrap();
-    
-    if let Some(existing_travail) = travaux_map.get_mut(&id) {
-        existing_travail.projet_id = travail_req.projet_id;
-        existing_travail.type_travail = travail_req.type_travail.clone();
-        existing_travail.application = travail_req.application.clone();
-        existing_travail.environnement = travail_req.environnement.clone();
-        existing_travail.description = travail_req.description.clone();
-        existing_travail.date_debut = travail_req.date_debut;
-        existing_travail.date_fin_prevue = travail_req.date_fin_prevue;
-        existing_travail.statut = travail_req.statut.clone();
-        existing_travail.responsable = travail_req.responsable;
-        existing_travail.equipe = travail_req.equipe.clone();
-
-        let response = TravailResponse {
-            id: existing_travail.id,
-            projet_id: existing_travail.projet_id,
-            type_travail: existing_travail.type_travail.clone(),
-            application: existing_travail.application.clone(),
-            environnement: existing_travail.environnement.clone(),
-            description: existing_travail.description.clone(),
-            date_debut: existing_travail.date_debut,
-            date_fin_prevue: existing_travail.date_fin_prevue,
-            statut: existing_travail.statut.clone(),
-            responsable: existing_travail.responsable,
-            equipe: existing_travail.equipe.clone(),
-        };
+    let unite = depots.transaction().await?;
+    let Some(existing) = unite.travaux.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Travail non trouvé".to_string()));
+    };
+    courant.exiger_modification(&existing)?;
+    version_attendue.verifier(existing.version)?;
 
-        Ok(HttpResponse::Ok().json(response))
-    } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Travail non trouvé"
-        })))
+    let reponse = modifier_travail(&courant, existing, travail_req.into_inner(), &unite, &config).await?;
+    unite.valider().await?;
+    Ok(reponse)
+}
+
+// Modification partielle : seuls les champs présents dans le patch (JSON Merge Patch) changent
+#[utoipa::path(
+    patch,
+    path = "/api/travaux/{id}",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la modification est refusée si le travail a changé depuis")),
+    request_body(content = CreateTravailRequest, content_type = "application/merge-patch+json", description = "Champs à modifier, tous facultatifs ; promotionDe: null détache le travail de sa chaîne"),
+    responses(
+        (status = 200, description = "Travail modifié, avec ses conflits de planning éventuels", body = TravailEnregistreResponse, headers(("ETag" = String, description = "Nouvelle version du travail"))),
+        (status = 400, description = "Patch invalide", body = CorpsErreur),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 409, description = "Transition interdite, conflit de planning, période de gel, approbation requise ou dépendances non terminées", body = CorpsErreur),
+        (status = 412, description = "Travail modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn patch_travail(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    patch: web::Json<serde_json::Value>,
+    depots: Repositories,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    let unite = depots.transaction().await?;
+    let Some(existing) = unite.travaux.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Travail non trouvé".to_string()));
+    };
+    courant.exiger_modification(&existing)?;
+    version_attendue.verifier(existing.version)?;
+
+    let travail_req = patch::appliquer(&CreateTravailRequest::from(&existing), patch.into_inner())?;
+    let reponse = modifier_travail(&courant, existing, travail_req, &unite, &config).await?;
+    unite.valider().await?;
+    Ok(reponse)
+}
+
+// Validation et enregistrement communs à PUT et PATCH, dans l'unité de travail de l'appelant ;
+// l'écriture échoue si le travail a changé depuis la lecture de `existing`
+async fn modifier_travail(
+    courant: &UtilisateurCourant,
+    existing: Travail,
+    travail_req: CreateTravailRequest,
+    depots: &Repositories,
+    config: &Config,
+) -> Result<HttpResponse> {
+    let repository = depots.travaux.as_ref();
+    let transitions = depots.transitions.as_ref();
+    let projets = depots.projets.as_ref();
+    let utilisateurs = depots.utilisateurs.as_ref();
+    let approbations = depots.approbations.as_ref();
+    let gels = depots.gels.as_ref();
+    let dependances = depots.dependances.as_ref();
+    let audit = depots.audit.as_ref();
+    let id = existing.id;
+    let validation = travail_req.valider(Some(id), projets, utilisateurs, repository).await?;
+    validation.verifier()?;
+    if travail_req.statut != existing.statut && !existing.statut.peut_passer_a(&travail_req.statut) {
+        return Err(transition_interdite(existing.statut.clone(), travail_req.statut.clone()));
     }
+
+    let mut travail = Travail {
+        id,
+        projet_id: travail_req.projet_id,
+        type_travail: travail_req.type_travail,
+        application: travail_req.application,
+        environnement: travail_req.environnement,
+        description: travail_req.description,
+        date_debut: travail_req.date_debut,
+        date_fin_prevue: travail_req.date_fin_prevue,
+        statut: travail_req.statut,
+        responsable: travail_req.responsable,
+        equipe: travail_req.equipe,
+        promotion_de: travail_req.promotion_de,
+        version: existing.version,
+        suppression: existing.suppression.clone(),
+    };
+    let mut validation = Validation::new();
+    verifier_dependances(&mut validation, &travail, dependances, repository).await?;
+    validation.verifier()?;
+
+    let perimetre_modifie = travail.perimetre_modifie(&existing);
+    if travail.statut != existing.statut || travail.promotion_de != existing.promotion_de {
+        verifier_predecesseurs_termines(&travail, &travail.statut, repository).await?;
+    }
+    if travail.statut != existing.statut {
+        verifier_dependances_terminees(&travail, &travail.statut, dependances, repository).await?;
+    }
+    // Pas seulement au changement de statut : un travail déjà EnCours qui passe en production,
+    // ou dont le périmètre approuvé change, doit lui aussi être (de nouveau) approuvé
+    verifier_approbation(&travail, &existing.statut, perimetre_modifie, approbations, transitions, config).await?;
+    let conflits = detecter_conflits(&travail, repository).await?;
+    let conflits_existants = detecter_conflits(&existing, repository).await?;
+    appliquer_politique(config, &conflits, &conflits_existants)?;
+    let derogations = verifier_gels(&travail, Some(&existing), travail_req.justification_gel.as_deref(), courant, gels).await?;
+
+    if !repository.update(&travail).await? {
+        return Err(ecriture_refusee(repository.find(id).await?.map(|t| t.version), "Travail non trouvé"));
+    }
+    travail.version += 1;
+    journaliser(audit, Some(courant.0.id), TypeEntite::Travail, id, Some(&existing), Some(&travail)).await?;
+    for derogation in &derogations {
+        gels.insert_derogation(derogation).await?;
+    }
+    // Une approbation ne vaut que pour les dates et le périmètre qui ont été validés
+    if perimetre_modifie {
+        approbations.invalider(id).await?;
+    }
+    if travail.statut != existing.statut {
+        let transition = nouvelle_transition(TypeEntite::Travail, id, existing.statut.clone(), travail.statut.clone(), None);
+        transitions.insert(&transition).await?;
+    }
+    Ok(HttpResponse::Ok().insert_header(etag(travail.version)).json(TravailEnregistreResponse {
+        travail: TravailResponse::from(travail),
+        conflits,
+    }))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/travaux/{id}",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la suppression est refusée si le travail a changé depuis")),
+    responses(
+        (status = 204, description = "Travail supprimé (restaurable jusqu'à sa purge)"),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 412, description = "Travail modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
 pub async fn delete_travail(
-    web::Path(id): web::Path<Uuid>,
-    travaux: web::Data<Mutex<HashMap<Uuid, Travail>>>,
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererTravaux)?;
+    let unite = depots.transaction().await?;
+    let Repositories { travaux: repository, audit, .. } = &*unite;
+    let Some(travail) = repository.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Travail non trouvé".to_string()));
+    };
+    version_attendue.verifier(travail.version)?;
+
+    let suppression = Suppression { le: Utc::now(), par: Some(courant.0.id) };
+    if supprimer_travail(&travail, &suppression, repository.as_ref(), audit.as_ref()).await? {
+        unite.valider().await?;
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ecriture_refusee(repository.find(travail.id).await?.map(|t| t.version), "Travail non trouvé"))
+    }
+}
+
+// Suppression logique : la checklist, les approbations, les dérogations et les dépendances du
+// travail sont conservées pour une restauration ; le travail qui en était la promotion en est
+// détaché au préalable. False si le travail a été modifié depuis sa lecture, erreur si c'est sa
+// promotion qui l'a été (rien n'est alors supprimé)
+pub async fn supprimer_travail(
+    travail: &Travail,
+    suppression: &Suppression,
+    travaux: &dyn TravailRepository,
+    audit: &dyn AuditRepository,
+) -> Result<bool> {
+    if let Some(promotion) = successeur(travail.id, travaux).await? {
+        let detache = Travail { promotion_de: None, ..promotion.clone() };
+        if !travaux.update(&detache).await? {
+            return Err(ecriture_refusee(travaux.find(promotion.id).await?.map(|t| t.version), "Travail promu non trouvé"));
+        }
+        journaliser(audit, suppression.par, TypeEntite::Travail, detache.id, Some(&promotion), Some(&detache)).await?;
+    }
+    let supprime = Travail { suppression: Some(suppression.clone()), ..travail.clone() };
+    if !travaux.update(&supprime).await? {
+        return Ok(false);
+    }
+    journaliser_action(audit, suppression.par, TypeEntite::Travail, travail.id, ActionAudit::Suppression, Some(travail), Some(&supprime)).await?;
+    Ok(true)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/travaux/{id}/restauration",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail supprimé"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la restauration est refusée si le travail a changé depuis")),
+    responses(
+        (status = 200, description = "Travail restauré", body = TravailResponse, headers(("ETag" = String, description = "Nouvelle version du travail"))),
+        (status = 404, description = "Aucun travail supprimé avec cet identifiant", body = CorpsErreur),
+        (status = 409, description = "Restauration refusée : projet, responsable ou travail promu supprimé entre-temps", body = CorpsErreur),
+        (status = 412, description = "Travail modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
+pub async fn restaurer_travail(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererTravaux)?;
+    let unite = depots.transaction().await?;
+    let Repositories { travaux: repository, projets, utilisateurs, audit, .. } = &*unite;
+    let Some(travail) = repository.find_supprime(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Travail supprimé non trouvé".to_string()));
+    };
+    version_attendue.verifier(travail.version)?;
+
+    if let Some(raison) = restauration_impossible(&travail, projets.as_ref(), utilisateurs.as_ref(), repository.as_ref()).await? {
+        return Err(restauration_refusee(raison));
+    }
+    match restaurer(&travail, courant.0.id, repository.as_ref(), audit.as_ref()).await? {
+        Some(restaure) => {
+            unite.valider().await?;
+            Ok(HttpResponse::Ok().insert_header(etag(restaure.version)).json(TravailResponse::from(restaure)))
+        }
+        None => Err(ecriture_refusee(repository.find_supprime(travail.id).await?.map(|t| t.version), "Travail supprimé non trouvé")),
+    }
+}
+
+// Raison pour laquelle le travail supprimé ne peut pas revenir tel quel : il référencerait un
+// projet, un responsable ou un travail promu supprimés depuis, ou ce dernier a été promu à nouveau
+pub async fn restauration_impossible(
+    travail: &Travail,
+    projets: &dyn ProjetRepository,
+    utilisateurs: &dyn UtilisateurRepository,
+    travaux: &dyn TravailRepository,
+) -> Result<Option<String>> {
+    if projets.find(travail.projet_id).await?.is_none() {
+        return Ok(Some(format!("Le projet {} est supprimé : le restaurer d'abord", travail.projet_id)));
+    }
+    if utilisateurs.find(travail.responsable).await?.is_none() {
+        return Ok(Some(format!("Le responsable {} est supprimé", travail.responsable)));
+    }
+    if let Some(promotion_de) = travail.promotion_de {
+        if travaux.find(promotion_de).await?.is_none() {
+            return Ok(Some(format!("Le travail promu {} est supprimé", promotion_de)));
+        }
+        if let Some(autre) = successeur(promotion_de, travaux).await? {
+            return Ok(Some(format!("Le travail {} a depuis été promu par {}", promotion_de, autre.id)));
+        }
+    }
+    Ok(None)
+}
+
+// Annule la suppression logique ; None si le travail a été modifié depuis sa lecture
+pub async fn restaurer(
+    travail: &Travail,
+    acteur: Uuid,
+    travaux: &dyn TravailRepository,
+    audit: &dyn AuditRepository,
+) -> Result<Option<Travail>> {
+    let mut restaure = Travail { suppression: None, ..travail.clone() };
+    if !travaux.update(&restaure).await? {
+        return Ok(None);
+    }
+    restaure.version += 1;
+    journaliser_action(audit, Some(acteur), TypeEntite::Travail, travail.id, ActionAudit::Restauration, Some(travail), Some(&restaure)).await?;
+    Ok(Some(restaure))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{id}/historique",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail"), HistoriqueQuery),
+    responses(
+        (status = 200, description = "Historique des modifications", body = Vec<EvenementAuditResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_historique_travail(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    query: web::Query<HistoriqueQuery>,
+    repository: web::Data<dyn TravailRepository>,
+    audit: web::Data<dyn AuditRepository>,
+) -> Result<HttpResponse> {
+    let id = id.into_inner();
+    let page = historique(audit.as_ref(), TypeEntite::Travail, id, &query).await?;
+    if page.total == 0 && repository.find(id).await?.is_none() {
+        return Err(ApiError::NotFound("Travail non trouvé".to_string()));
+    }
+
+    Ok(reponse_historique(page))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/travaux/{id}/transitions",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail")),
+    responses(
+        (status = 200, description = "Changements de statut du travail", body = Vec<TransitionStatutResponse>),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_transitions_travail(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn TravailRepository>,
+    transitions: web::Data<dyn TransitionRepository>,
+) -> Result<HttpResponse> {
+    let id = id.into_inner();
+    if repository.find(id).await?.is_none() {
+        return Err(ApiError::NotFound("Travail non trouvé".to_string()));
+    }
+
+    let historique: Vec<TransitionStatutResponse> = transitions.list_by_entite(&TypeEntite::Travail, id).await?
+        .into_iter()
+        .map(TransitionStatutResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(historique))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/travaux/{id}/transitions",
+    tag = "travaux",
+    params(("id" = Uuid, Path, description = "Identifiant du travail"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la transition est refusée si le travail a changé depuis")),
+    request_body = TransitionRequest<StatutTravail>,
+    responses(
+        (status = 200, description = "Statut modifié", body = TravailResponse, headers(("ETag" = String, description = "Nouvelle version du travail"))),
+        (status = 400, description = "Raison manquante", body = CorpsErreur),
+        (status = 404, description = "Travail non trouvé", body = CorpsErreur),
+        (status = 409, description = "Transition interdite, approbation requise ou prédécesseurs non terminés", body = CorpsErreur),
+        (status = 412, description = "Travail modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
+pub async fn transition_travail(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    transition_req: web::Json<TransitionRequest<StatutTravail>>,
+    depots: Repositories,
+    config: web::Data<Config>,
 ) -> Result<HttpResponse> {
-    let mut travaux_map = travaux.lock().unwrap();
-    
-    match travaux_map.remove(&id) {
-        Some(_) => Ok(HttpResponse::NoContent().finish()),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Travail non trouvé"
-        })))
+    let unite = depots.transaction().await?;
+    let Repositories { travaux: repository, transitions, approbations, dependances, audit, .. } = &*unite;
+    let Some(mut travail) = repository.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Travail non trouvé".to_string()));
+    };
+    courant.exiger_modification(&travail)?;
+    version_attendue.verifier(travail.version)?;
+
+    let transition_req = transition_req.into_inner();
+    if transition_req.raison.trim().is_empty() {
+        return Err(raison_manquante());
+    }
+    if !travail.statut.peut_passer_a(&transition_req.statut) {
+        return Err(transition_interdite(travail.statut.clone(), transition_req.statut.clone()));
+    }
+    verifier_predecesseurs_termines(&travail, &transition_req.statut, repository.as_ref()).await?;
+    verifier_dependances_terminees(&travail, &transition_req.statut, dependances.as_ref(), repository.as_ref()).await?;
+    let vise = Travail { statut: transition_req.statut.clone(), ..travail.clone() };
+    verifier_approbation(&vise, &travail.statut, false, approbations.as_ref(), transitions.as_ref(), &config).await?;
+
+    let transition = nouvelle_transition(
+        TypeEntite::Travail,
+        travail.id,
+        travail.statut.clone(),
+        transition_req.statut.clone(),
+        Some(transition_req.raison),
+    );
+    let avant = travail.clone();
+    travail.statut = transition_req.statut;
+
+    if !repository.update(&travail).await? {
+        return Err(ecriture_refusee(repository.find(travail.id).await?.map(|t| t.version), "Travail non trouvé"));
+    }
+    travail.version += 1;
+    transitions.insert(&transition).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Travail, travail.id, Some(&avant), Some(&travail)).await?;
+    unite.valider().await?;
+
+    Ok(HttpResponse::Ok().insert_header(etag(travail.version)).json(TravailResponse::from(travail)))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Role, Utilisateur};
+
+    #[actix_web::test]
+    async fn transition_interdite_renvoie_409() {
+        let depots = Repositories::memoire();
+        let configuration = configuration();
+        let (admin, authorization) = connecter(&depots, &configuration, Role::Admin).await;
+        let travail = Travail { statut: StatutTravail::Termine, responsable: admin.id, ..test_support::travail() };
+        depots.travaux.insert(&travail).await.unwrap();
+
+        let requete = TestRequest::post()
+            .uri(&format!("/api/travaux/{}/transitions", travail.id))
+            .insert_header(("Authorization", authorization))
+            .set_json(json!({"statut": "planifié", "raison": "Reprise"}));
+        let reponse = appeler(&depots, &configuration, requete).await;
+
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["code"], "transition_interdite");
+        assert_eq!(corps["de"], "terminé");
+        assert_eq!(corps["vers"], "planifié");
+        assert_eq!(depots.travaux.find(travail.id).await.unwrap().unwrap().statut, StatutTravail::Termine);
+    }
+
+    #[actix_web::test]
+    async fn creation_au_statut_planifie_uniquement() {
+        let depots = Repositories::memoire();
+        let configuration = configuration();
+        let (admin, authorization) = connecter(&depots, &configuration, Role::Admin).await;
+        let projet = test_support::projet();
+        depots.projets.insert(&projet).await.unwrap();
+        let requete = |statut: &str| {
+            TestRequest::post()
+                .uri("/api/travaux")
+                .insert_header(("Authorization", authorization.clone()))
+                .set_json(json!({
+                    "projetId": projet.id,
+                    "type": "migration",
+                    "application": "espresso_gfr",
+                    "environnement": "test",
+                    "description": "Migration",
+                    "dateDebut": test_support::date(0),
+                    "dateFinPrevue": test_support::date(2),
+                    "statut": statut,
+                    "responsable": admin.id,
+                    "equipe": [],
+                }))
+        };
+
+        let reponse = appeler(&depots, &configuration, requete("en_cours")).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["erreurs"][0]["field"], "statut");
+        assert_eq!(corps["erreurs"][0]["code"], "statut_initial");
+
+        let reponse = appeler(&depots, &configuration, requete("planifié")).await;
+        assert_eq!(reponse.status(), StatusCode::CREATED);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["statut"], "planifié");
+    }
+
+    #[actix_web::test]
+    async fn toutes_les_erreurs_de_champ_en_une_reponse() {
+        let depots = Repositories::memoire();
+        let configuration = configuration();
+        let (_, authorization) = connecter(&depots, &configuration, Role::Admin).await;
+        let inactif = Utilisateur { actif: false, ..test_support::utilisateur(Role::Specialiste) };
+        depots.utilisateurs.insert(&inactif).await.unwrap();
+
+        let requete = TestRequest::post()
+            .uri("/api/travaux")
+            .insert_header(("Authorization", authorization))
+            .set_json(json!({
+                "projetId": Uuid::new_v4(),
+                "type": "migration",
+                "application": "espresso_gfr",
+                "environnement": "test",
+                "description": "",
+                "dateDebut": test_support::date(2),
+                "dateFinPrevue": test_support::date(0),
+                "statut": "en_cours",
+                "responsable": inactif.id,
+                "equipe": [Uuid::new_v4()],
+            }));
+        let reponse = appeler(&depots, &configuration, requete).await;
+
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["code"], "validation");
+        let erreurs: Vec<(&str, &str)> = corps["erreurs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| (e["field"].as_str().unwrap(), e["code"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            erreurs,
+            [
+                ("description", "requis"),
+                ("dateFinPrevue", "avant_date_debut"),
+                ("projetId", "inexistant"),
+                ("responsable", "inactif"),
+                ("equipe", "inexistant"),
+                ("statut", "statut_initial"),
+            ]
+        );
+        assert_eq!(depots.travaux.list(&FiltreTravaux::default(), &Pagination::tout("date_debut")).await.unwrap().total, 0);
     }
 }
@@ -1,21 +1,41 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use std::collections::HashMap;
-use std::sync::Mutex;
+use utoipa::{IntoParams, ToSchema};
+use chrono::Utc;
 
-use crate::models::{Utilisateur, Role};
+use crate::auth::{self, UtilisateurCourant};
+use crate::permissions::Permission;
+use crate::error::{ApiError, CorpsErreur, Result};
+use crate::models::{ActionAudit, ChecklistItem, Suppression, Utilisateur, Role, TypeEntite, VERSION_INITIALE};
+use crate::config::{Config, PolitiqueSuppression};
+use crate::repositories::{AuditRepository, Repositories, UtilisateurRepository};
+use crate::handlers::references::{cible_reassignation_manquante, restauration_refusee, suppression_refusee, verifier_utilisateur_actif, SuppressionQuery};
+use crate::validation::Validation;
+use crate::handlers::pagination::{pagination, reponse_page};
+use crate::repositories::filtres::TRI_UTILISATEURS;
+use crate::repositories::{FiltreAudit, FiltreTravaux, FiltreUtilisateurs, Pagination, Supprimes};
+use crate::handlers::audit_handlers::{historique, journaliser, journaliser_action, reponse_historique, EvenementAuditResponse, HistoriqueQuery};
+use crate::handlers::corbeille_handlers::supprimes_listes;
+use crate::handlers::travail_handlers::{restauration_impossible, restaurer, supprimer_travail};
+use crate::handlers::patch;
+use crate::handlers::versions::{ecriture_refusee, etag, VersionAttendue};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct CreateUtilisateurRequest {
     pub nom: String,
     pub email: String,
     pub role: Role,
     pub equipe: String,
     pub actif: bool,
+    // Facultatif : sans mot de passe, le compte ne peut pas se connecter
+    #[serde(alias = "mot_de_passe", default)]
+    pub mot_de_passe: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct UtilisateurResponse {
     pub id: Uuid,
     pub nom: String,
@@ -23,6 +43,80 @@ pub struct UtilisateurResponse {
     pub role: Role,
     pub equipe: String,
     pub actif: bool,
+    pub version: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
+}
+
+impl From<Utilisateur> for UtilisateurResponse {
+    fn from(u: Utilisateur) -> Self {
+        UtilisateurResponse {
+            id: u.id,
+            nom: u.nom,
+            email: u.email,
+            role: u.role,
+            equipe: u.equipe,
+            actif: u.actif,
+            version: u.version,
+            suppression: u.suppression,
+        }
+    }
+}
+
+// Requête de modification complète équivalente à l'utilisateur (sans mot de passe), base d'un PATCH
+impl From<&Utilisateur> for CreateUtilisateurRequest {
+    fn from(u: &Utilisateur) -> Self {
+        CreateUtilisateurRequest {
+            nom: u.nom.clone(),
+            email: u.email.clone(),
+            role: u.role.clone(),
+            equipe: u.equipe.clone(),
+            actif: u.actif,
+            mot_de_passe: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListeUtilisateursQuery {
+    pub role: Option<Role>,
+    pub equipe: Option<String>,
+    pub actif: Option<bool>,
+    // Admin : inclure les utilisateurs supprimés (restaurables)
+    pub inclure_supprimes: Option<bool>,
+    pub sort: Option<String>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl CreateUtilisateurRequest {
+    fn valider(&self) -> Validation {
+        let mut validation = Validation::new();
+        validation.texte("nom", &self.nom, 255);
+        validation.texte("email", &self.email, 255);
+        validation.email("email", &self.email);
+        validation.texte("equipe", &self.equipe, 100);
+        if let Some(mot_de_passe) = &self.mot_de_passe {
+            validation.mot_de_passe("motDePasse", mot_de_passe);
+        }
+        validation
+    }
+}
+
+// L'email est unique (contrainte UNIQUE de la table utilisateurs)
+async fn verifier_email_unique(
+    validation: &mut Validation,
+    utilisateurs: &dyn UtilisateurRepository,
+    email: &str,
+    id: Option<Uuid>,
+) -> Result<()> {
+    if let Some(existant) = utilisateurs.find_by_email(email).await? {
+        if Some(existant.id) != id {
+            validation.ajouter("email", "deja_utilise", format!("L'email {} est déjà utilisé", email));
+        }
+    }
+    Ok(())
 }
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -32,120 +126,647 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("", web::post().to(create_utilisateur))
             .route("/{id}", web::get().to(get_utilisateur))
             .route("/{id}", web::put().to(update_utilisateur))
+            .route("/{id}", web::patch().to(patch_utilisateur))
             .route("/{id}", web::delete().to(delete_utilisateur))
+            .route("/{id}/historique", web::get().to(get_historique_utilisateur))
+            .route("/{id}/restauration", web::post().to(restaurer_utilisateur))
     );
 }
 
-pub async fn get_utilisateurs(utilisateurs: web::Data<Mutex<HashMap<Uuid, Utilisateur>>>) -> Result<HttpResponse> {
-    let utilisateurs_map = utilisateurs.lock().unwrap();
-    let utilisateurs_list: Vec<UtilisateurResponse> = utilisateurs_map.values()
-        .map(|u| UtilisateurResponse {
-            id: u.id,
-            nom: u.nom.clone(),
-            email: u.email.clone(),
-            role: u.role.clone(),
-            equipe: u.equipe.clone(),
-            actif: u.actif,
-        })
-        .collect();
+#[utoipa::path(
+    get,
+    path = "/api/utilisateurs",
+    tag = "utilisateurs",
+    params(ListeUtilisateursQuery),
+    responses(
+        (status = 200, description = "Utilisateurs de la page demandée", body = Vec<UtilisateurResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 403, description = "inclure_supprimes : permission gerer_corbeille manquante", body = CorpsErreur),
+    ),
+)]
+pub async fn get_utilisateurs(
+    courant: UtilisateurCourant,
+    query: web::Query<ListeUtilisateursQuery>,
+    repository: web::Data<dyn UtilisateurRepository>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let pagination = pagination(query.sort.as_deref(), query.offset, query.limit, TRI_UTILISATEURS, "nom")?;
+    let filtre = FiltreUtilisateurs {
+        role: query.role,
+        equipe: query.equipe,
+        actif: query.actif,
+        supprimes: supprimes_listes(&courant, query.inclure_supprimes)?,
+    };
+    let page = repository.list(&filtre, &pagination).await?;
 
-    Ok(HttpResponse::Ok().json(utilisateurs_list))
+    Ok(reponse_page(page, UtilisateurResponse::from))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/utilisateurs/{id}",
+    tag = "utilisateurs",
+    params(("id" = Uuid, Path, description = "Identifiant de l'utilisateur")),
+    responses(
+        (status = 200, description = "Utilisateur", body = UtilisateurResponse, headers(("ETag" = String, description = "Version de l'utilisateur"))),
+        (status = 404, description = "Utilisateur non trouvé", body = CorpsErreur),
+    ),
+)]
 pub async fn get_utilisateur(
-    web::Path(id): web::Path<Uuid>,
-    utilisateurs: web::Data<Mutex<HashMap<Uuid, Utilisateur>>>,
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    repository: web::Data<dyn UtilisateurRepository>,
 ) -> Result<HttpResponse> {
-    let utilisateurs_map = utilisateurs.lock().unwrap();
-    
-    match utilisateurs_map.get(&id) {
-        Some(utilisateur) => {
-            let response = UtilisateurResponse {
-                id: utilisateur.id,
-                nom: utilisateur.nom.clone(),
-                email: utilisateur.email.clone(),
-                role: utilisateur.role.clone(),
-                equipe: utilisateur.equipe.clone(),
-                actif: utilisateur.actif,
-            };
-            Ok(HttpResponse::Ok().json(response))
-        }
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Utilisateur non trouvé"
-        })))
+    match repository.find(id.into_inner()).await? {
+        Some(utilisateur) => Ok(HttpResponse::Ok().insert_header(etag(utilisateur.version)).json(UtilisateurResponse::from(utilisateur))),
+        None => Err(ApiError::NotFound("Utilisateur non trouvé".to_string()))
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/utilisateurs",
+    tag = "utilisateurs",
+    request_body = CreateUtilisateurRequest,
+    responses(
+        (status = 201, description = "Utilisateur créé", body = UtilisateurResponse),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
 pub async fn create_utilisateur(
+    courant: UtilisateurCourant,
     utilisateur_req: web::Json<CreateUtilisateurRequest>,
-    utilisateurs: web::Data<Mutex<HashMap<Uuid, Utilisateur>>>,
+    depots: Repositories,
 ) -> Result<HttpResponse> {
-    let mut utilisateurs_map = utilisateurs.lock().unwrap();
-    
+    courant.exiger(Permission::GererUtilisateurs)?;
+    let unite = depots.transaction().await?;
+    let Repositories { utilisateurs: repository, audit, .. } = &*unite;
+    let utilisateur_req = utilisateur_req.into_inner();
+    let mut validation = utilisateur_req.valider();
+    verifier_email_unique(&mut validation, repository.as_ref(), &utilisateur_req.email, None).await?;
+    validation.verifier()?;
+
     let utilisateur = Utilisateur {
         id: Uuid::new_v4(),
-        nom: utilisateur_req.nom.clone(),
-        email: utilisateur_req.email.clone(),
-        role: utilisateur_req.role.clone(),
-        equipe: utilisateur_req.equipe.clone(),
+        nom: utilisateur_req.nom,
+        email: utilisateur_req.email,
+        role: utilisateur_req.role,
+        equipe: utilisateur_req.equipe,
         actif: utilisateur_req.actif,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
 
-    utilisateurs_map.insert(utilisateur.id, utilisateur.clone());
-
-    let response = UtilisateurResponse {
-        id: utilisateur.id,
-        nom: utilisateur.nom,
-        email: utilisateur.email,
-        role: utilisateur.role,
-        equipe: utilisateur.equipe,
-        actif: utilisateur.actif,
-    };
+    repository.insert(&utilisateur).await?;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Utilisateur, utilisateur.id, None, Some(&utilisateur)).await?;
+    if let Some(mot_de_passe) = utilisateur_req.mot_de_passe {
+        repository.set_mot_de_passe(utilisateur.id, &auth::hacher(mot_de_passe).await?).await?;
+    }
+    unite.valider().await?;
 
-    Ok(HttpResponse::Created().json(response))
+    Ok(HttpResponse::Created().insert_header(etag(utilisateur.version)).json(UtilisateurResponse::from(utilisateur)))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/utilisateurs/{id}",
+    tag = "utilisateurs",
+    params(("id" = Uuid, Path, description = "Identifiant de l'utilisateur"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la modification est refusée si l'utilisateur a changé depuis")),
+    request_body = CreateUtilisateurRequest,
+    responses(
+        (status = 200, description = "Utilisateur modifié", body = UtilisateurResponse, headers(("ETag" = String, description = "Nouvelle version de l'utilisateur"))),
+        (status = 404, description = "Utilisateur non trouvé", body = CorpsErreur),
+        (status = 412, description = "Utilisateur modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
 pub async fn update_utilisateur(
-    web::Path(id): web::Path<Uuid>,
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
     utilisateur_req: web::Json<CreateUtilisateurRequest>,
-    utilisateurs: web::Data<Mutex<HashMap<Uuid, Utilisateur>>>,
+    depots: Repositories,
 ) -> Result<HttpResponse> {
-    let mut utilisateurs_map = utilisateurs.lock().unwrap();
-    
-    if let Some(existing_utilisateur) = utilisateurs_map.get_mut(&id) {
-        existing_utilisateur.nom = utilisateur_req.nom.clone();
-        existing_utilisateur.email = utilisateur_req.email.clone();
-        existing_utilisateur.role = utilisateur_req.role.clone();
-        existing_utilisateur.equipe = utilisateur_req.equipe.clone();
-        existing_utilisateur.actif = utilisateur_req.actif;
-
-        let response = UtilisateurResponse {
-            id: existing_utilisateur.id,
-            nom: existing_utilisateur.nom.clone(),
-            email: existing_utilisateur.email.clone(),
-            role: existing_utilisateur.role.clone(),
-            equipe: existing_utilisateur.equipe.clone(),
-            actif: existing_utilisateur.actif,
-        };
+    courant.exiger(Permission::GererUtilisateurs)?;
+    let unite = depots.transaction().await?;
+    let Some(existing) = unite.utilisateurs.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Utilisateur non trouvé".to_string()));
+    };
+    version_attendue.verifier(existing.version)?;
+
+    let reponse = modifier_utilisateur(&courant, existing, utilisateur_req.into_inner(), &unite).await?;
+    unite.valider().await?;
+    Ok(reponse)
+}
+
+// Modification partielle : seuls les champs présents dans le patch (JSON Merge Patch) changent
+#[utoipa::path(
+    patch,
+    path = "/api/utilisateurs/{id}",
+    tag = "utilisateurs",
+    params(("id" = Uuid, Path, description = "Identifiant de l'utilisateur"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la modification est refusée si l'utilisateur a changé depuis")),
+    request_body(content = CreateUtilisateurRequest, content_type = "application/merge-patch+json", description = "Champs à modifier, tous facultatifs"),
+    responses(
+        (status = 200, description = "Utilisateur modifié", body = UtilisateurResponse, headers(("ETag" = String, description = "Nouvelle version de l'utilisateur"))),
+        (status = 400, description = "Patch invalide", body = CorpsErreur),
+        (status = 404, description = "Utilisateur non trouvé", body = CorpsErreur),
+        (status = 412, description = "Utilisateur modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+        (status = 422, description = "Requête invalide (erreurs par champ)", body = CorpsErreur),
+    ),
+)]
+pub async fn patch_utilisateur(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    patch: web::Json<serde_json::Value>,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererUtilisateurs)?;
+    let unite = depots.transaction().await?;
+    let Some(existing) = unite.utilisateurs.find(id.into_inner()).await? else {
+        return Err(ApiError::NotFound("Utilisateur non trouvé".to_string()));
+    };
+    version_attendue.verifier(existing.version)?;
+
+    let utilisateur_req = patch::appliquer(&CreateUtilisateurRequest::from(&existing), patch.into_inner())?;
+    let reponse = modifier_utilisateur(&courant, existing, utilisateur_req, &unite).await?;
+    unite.valider().await?;
+    Ok(reponse)
+}
 
-        Ok(HttpResponse::Ok().json(response))
-    } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Utilisateur non trouvé"
-        })))
+// Validation et enregistrement communs à PUT et PATCH, dans l'unité de travail de l'appelant ;
+// l'écriture échoue si l'utilisateur a changé depuis la lecture de `existing`
+async fn modifier_utilisateur(
+    courant: &UtilisateurCourant,
+    existing: Utilisateur,
+    utilisateur_req: CreateUtilisateurRequest,
+    depots: &Repositories,
+) -> Result<HttpResponse> {
+    let Repositories { utilisateurs: repository, audit, .. } = depots;
+    let id = existing.id;
+    let mut validation = utilisateur_req.valider();
+    verifier_email_unique(&mut validation, repository.as_ref(), &utilisateur_req.email, Some(id)).await?;
+    validation.verifier()?;
+
+    let mut utilisateur = Utilisateur {
+        id,
+        nom: utilisateur_req.nom,
+        email: utilisateur_req.email,
+        role: utilisateur_req.role,
+        equipe: utilisateur_req.equipe,
+        actif: utilisateur_req.actif,
+        version: existing.version,
+        suppression: existing.suppression.clone(),
+    };
+
+    if !repository.update(&utilisateur).await? {
+        return Err(ecriture_refusee(repository.find(id).await?.map(|u| u.version), "Utilisateur non trouvé"));
+    }
+    utilisateur.version += 1;
+    journaliser(audit.as_ref(), Some(courant.0.id), TypeEntite::Utilisateur, id, Some(&existing), Some(&utilisateur)).await?;
+    if let Some(mot_de_passe) = utilisateur_req.mot_de_passe {
+        repository.set_mot_de_passe(id, &auth::hacher(mot_de_passe).await?).await?;
     }
+    Ok(HttpResponse::Ok().insert_header(etag(utilisateur.version)).json(UtilisateurResponse::from(utilisateur)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/utilisateurs/{id}",
+    tag = "utilisateurs",
+    params(("id" = Uuid, Path, description = "Identifiant de l'utilisateur"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la suppression est refusée si l'utilisateur a changé depuis"), SuppressionQuery),
+    responses(
+        (status = 204, description = "Utilisateur supprimé (restaurable jusqu'à sa purge)"),
+        (status = 404, description = "Utilisateur non trouvé", body = CorpsErreur),
+        (status = 409, description = "Suppression refusée : l'utilisateur a un historique (le désactiver), ou des travaux ou items le référencent", body = CorpsErreur),
+        (status = 412, description = "Utilisateur modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
 pub async fn delete_utilisateur(
-    web::Path(id): web::Path<Uuid>,
-    utilisateurs: web::Data<Mutex<HashMap<Uuid, Utilisateur>>>,
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    query: web::Query<SuppressionQuery>,
+    depots: Repositories,
+    config: web::Data<Config>,
 ) -> Result<HttpResponse> {
-    let mut utilisateurs_map = utilisateurs.lock().unwrap();
-    
-    match utilisateurs_map.remove(&id) {
-        Some(_) => Ok(HttpResponse::NoContent().finish()),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Utilisateur non trouvé"
-        })))
+    courant.exiger(Permission::GererUtilisateurs)?;
+    // Utilisateur, travaux, items et journal : tout ou rien, quelle que soit la politique
+    let unite = depots.transaction().await?;
+    let Repositories { utilisateurs: repository, travaux, checklist, audit, .. } = &*unite;
+    let id = id.into_inner();
+    let Some(utilisateur) = repository.find(id).await? else {
+        return Err(ApiError::NotFound("Utilisateur non trouvé".to_string()));
+    };
+    version_attendue.verifier(utilisateur.version)?;
+    let acteur = Some(courant.0.id);
+
+    // Un utilisateur qui a agi dans l'application reste identifiable dans le journal : on le désactive
+    let auteur = FiltreAudit { acteur: Some(id), ..FiltreAudit::default() };
+    let operations = audit.list(&auteur, &Pagination { limit: Some(1), ..Pagination::tout("date") }).await?.total;
+    if operations > 0 {
+        return Err(ApiError::Conflict {
+            code: "utilisateur_avec_historique",
+            message: format!(
+                "L'utilisateur est l'auteur de {} opérations journalisées : le désactiver (actif = false) plutôt que le supprimer",
+                operations
+            ),
+        });
+    }
+    let suppression = Suppression { le: Utc::now(), par: acteur };
+
+    let travaux_utilisateur = travaux.list_by_utilisateur(id).await?;
+    let items_utilisateur = checklist.list_by_responsable(id).await?;
+    if !travaux_utilisateur.is_empty() || !items_utilisateur.is_empty() {
+        match config.suppression_utilisateurs {
+            PolitiqueSuppression::Rejeter => {
+                return Err(suppression_refusee(format!(
+                    "L'utilisateur est référencé par {} travaux et {} items de checklist",
+                    travaux_utilisateur.len(),
+                    items_utilisateur.len()
+                )));
+            }
+            // Supprime (logiquement, avec la même suppression que lui) les travaux et items dont il
+            // est responsable, le retire des équipes
+            PolitiqueSuppression::Cascade => {
+                for item in &items_utilisateur {
+                    let supprime = ChecklistItem { suppression: Some(suppression.clone()), ..item.clone() };
+                    checklist.update(&supprime).await?;
+                    journaliser_action(audit.as_ref(), acteur, TypeEntite::ChecklistItem, item.id, ActionAudit::Suppression, Some(item), Some(&supprime)).await?;
+                }
+                for travail in travaux_utilisateur {
+                    if travail.responsable == id {
+                        if !supprimer_travail(&travail, &suppression, travaux.as_ref(), audit.as_ref()).await? {
+                            return Err(ecriture_refusee(travaux.find(travail.id).await?.map(|t| t.version), "Travail non trouvé"));
+                        }
+                    } else {
+                        let mut modifie = travail.clone();
+                        modifie.equipe.retain(|membre| *membre != id);
+                        if !travaux.update(&modifie).await? {
+                            return Err(ecriture_refusee(travaux.find(travail.id).await?.map(|t| t.version), "Travail non trouvé"));
+                        }
+                        journaliser(audit.as_ref(), acteur, TypeEntite::Travail, travail.id, Some(&travail), Some(&modifie)).await?;
+                    }
+                }
+            }
+            PolitiqueSuppression::Reassigner => {
+                let Some(cible) = query.reassigner_a.filter(|cible| *cible != id) else {
+                    return Err(cible_reassignation_manquante());
+                };
+                let mut validation = Validation::new();
+                verifier_utilisateur_actif(&mut validation, repository.as_ref(), cible, "reassigner_a").await?;
+                validation.verifier()?;
+                for item in items_utilisateur {
+                    let modifie = ChecklistItem { responsable: cible, ..item.clone() };
+                    checklist.update(&modifie).await?;
+                    journaliser(audit.as_ref(), acteur, TypeEntite::ChecklistItem, item.id, Some(&item), Some(&modifie)).await?;
+                }
+                for travail in travaux_utilisateur {
+                    let mut modifie = travail.clone();
+                    if modifie.responsable == id {
+                        modifie.responsable = cible;
+                    }
+                    if modifie.equipe.contains(&id) {
+                        modifie.equipe.retain(|membre| *membre != id);
+                        if !modifie.equipe.contains(&cible) {
+                            modifie.equipe.push(cible);
+                        }
+                    }
+                    if !travaux.update(&modifie).await? {
+                        return Err(ecriture_refusee(travaux.find(travail.id).await?.map(|t| t.version), "Travail non trouvé"));
+                    }
+                    journaliser(audit.as_ref(), acteur, TypeEntite::Travail, travail.id, Some(&travail), Some(&modifie)).await?;
+                }
+            }
+        }
+    }
+
+    let supprime = Utilisateur { suppression: Some(suppression), ..utilisateur.clone() };
+    if !repository.update(&supprime).await? {
+        return Err(ecriture_refusee(repository.find(id).await?.map(|u| u.version), "Utilisateur non trouvé"));
+    }
+    journaliser_action(audit.as_ref(), acteur, TypeEntite::Utilisateur, id, ActionAudit::Suppression, Some(&utilisateur), Some(&supprime)).await?;
+    unite.valider().await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/utilisateurs/{id}/restauration",
+    tag = "utilisateurs",
+    params(("id" = Uuid, Path, description = "Identifiant de l'utilisateur supprimé"), ("If-Match" = Option<String>, Header, description = "ETag lu ; la restauration est refusée si l'utilisateur a changé depuis")),
+    responses(
+        (status = 200, description = "Utilisateur restauré, avec les travaux et items de checklist supprimés en même temps que lui", body = UtilisateurResponse, headers(("ETag" = String, description = "Nouvelle version de l'utilisateur"))),
+        (status = 404, description = "Aucun utilisateur supprimé avec cet identifiant", body = CorpsErreur),
+        (status = 409, description = "Restauration refusée : email réutilisé depuis", body = CorpsErreur),
+        (status = 412, description = "Utilisateur modifié depuis la version indiquée par If-Match", body = CorpsErreur),
+    ),
+)]
+pub async fn restaurer_utilisateur(
+    courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    version_attendue: VersionAttendue,
+    depots: Repositories,
+) -> Result<HttpResponse> {
+    courant.exiger(Permission::GererUtilisateurs)?;
+    let unite = depots.transaction().await?;
+    let Repositories { utilisateurs: repository, projets, travaux, checklist, audit, .. } = &*unite;
+    let id = id.into_inner();
+    let Some(utilisateur) = repository.find_supprime(id).await? else {
+        return Err(ApiError::NotFound("Utilisateur supprimé non trouvé".to_string()));
+    };
+    version_attendue.verifier(utilisateur.version)?;
+    if repository.find_by_email(&utilisateur.email).await?.is_some() {
+        return Err(restauration_refusee(format!("L'email {} a été réutilisé depuis la suppression", utilisateur.email)));
+    }
+
+    let mut restaure = Utilisateur { suppression: None, ..utilisateur.clone() };
+    if !repository.update(&restaure).await? {
+        return Err(ecriture_refusee(repository.find_supprime(id).await?.map(|u| u.version), "Utilisateur supprimé non trouvé"));
+    }
+    restaure.version += 1;
+    journaliser_action(audit.as_ref(), Some(courant.0.id), TypeEntite::Utilisateur, id, ActionAudit::Restauration, Some(&utilisateur), Some(&restaure)).await?;
+
+    // Travaux et items supprimés en cascade avec l'utilisateur (même suppression) ; les travaux qui
+    // ne peuvent plus revenir tels quels restent dans la corbeille
+    let filtre = FiltreTravaux { responsable: Some(id), supprimes: Supprimes::Inclus, ..FiltreTravaux::default() };
+    for travail in travaux.list(&filtre, &Pagination::tout("date_debut")).await?.elements {
+        if travail.suppression == utilisateur.suppression
+            && restauration_impossible(&travail, projets.as_ref(), repository.as_ref(), travaux.as_ref()).await?.is_none()
+        {
+            restaurer(&travail, courant.0.id, travaux.as_ref(), audit.as_ref()).await?;
+        }
+    }
+    for item in checklist.list_supprimes_by_responsable(id).await? {
+        if item.suppression == utilisateur.suppression {
+            let restaure = ChecklistItem { suppression: None, ..item.clone() };
+            checklist.update(&restaure).await?;
+            journaliser_action(audit.as_ref(), Some(courant.0.id), TypeEntite::ChecklistItem, item.id, ActionAudit::Restauration, Some(&item), Some(&restaure)).await?;
+        }
+    }
+    unite.valider().await?;
+    Ok(HttpResponse::Ok().insert_header(etag(restaure.version)).json(UtilisateurResponse::from(restaure)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/utilisateurs/{id}/historique",
+    tag = "utilisateurs",
+    params(("id" = Uuid, Path, description = "Identifiant de l'utilisateur"), HistoriqueQuery),
+    responses(
+        (status = 200, description = "Historique des modifications", body = Vec<EvenementAuditResponse>, headers(("X-Total-Count" = i64, description = "Nombre total d'éléments, toutes pages confondues"))),
+        (status = 400, description = "Paramètre de requête mal formé", body = CorpsErreur),
+        (status = 422, description = "Tri ou pagination invalide (erreurs par champ)", body = CorpsErreur),
+        (status = 404, description = "Utilisateur non trouvé", body = CorpsErreur),
+    ),
+)]
+pub async fn get_historique_utilisateur(
+    _courant: UtilisateurCourant,
+    id: web::Path<Uuid>,
+    query: web::Query<HistoriqueQuery>,
+    repository: web::Data<dyn UtilisateurRepository>,
+    audit: web::Data<dyn AuditRepository>,
+) -> Result<HttpResponse> {
+    let id = id.into_inner();
+    let page = historique(audit.as_ref(), TypeEntite::Utilisateur, id, &query).await?;
+    if page.total == 0 && repository.find(id).await?.is_none() {
+        return Err(ApiError::NotFound("Utilisateur non trouvé".to_string()));
+    }
+
+    Ok(reponse_historique(page))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Travail};
+
+    // Utilisateur responsable d'un travail (et de son item de checklist) et membre de l'équipe
+    // d'un autre, supprimé par un admin selon `politique`
+    struct Resultat {
+        depots: Repositories,
+        configuration: Config,
+        authorization: String,
+        utilisateur: Utilisateur,
+        responsable: Travail,
+        membre: Travail,
+        item: ChecklistItem,
+        reponse: ServiceResponse,
+    }
+
+    async fn supprimer(politique: PolitiqueSuppression, reassigner_a: Option<&Utilisateur>) -> Resultat {
+        let depots = Repositories::memoire();
+        let configuration = Config { suppression_utilisateurs: politique, ..configuration() };
+        let (_, authorization) = connecter(&depots, &configuration, Role::Admin).await;
+        let utilisateur = test_support::utilisateur(Role::Specialiste);
+        depots.utilisateurs.insert(&utilisateur).await.unwrap();
+        if let Some(cible) = reassigner_a {
+            depots.utilisateurs.insert(cible).await.unwrap();
+        }
+        let projet = test_support::projet();
+        depots.projets.insert(&projet).await.unwrap();
+        let responsable = Travail { projet_id: projet.id, responsable: utilisateur.id, ..test_support::travail() };
+        let membre = Travail { projet_id: projet.id, equipe: vec![utilisateur.id], ..test_support::travail() };
+        let item = test_support::checklist_item(&responsable);
+        for travail in [&responsable, &membre] {
+            depots.travaux.insert(travail).await.unwrap();
+        }
+        depots.checklist.insert(&item).await.unwrap();
+
+        let uri = match reassigner_a {
+            Some(cible) => format!("/api/utilisateurs/{}?reassigner_a={}", utilisateur.id, cible.id),
+            None => format!("/api/utilisateurs/{}", utilisateur.id),
+        };
+        let requete = TestRequest::delete().uri(&uri).insert_header(("Authorization", authorization.clone()));
+        let reponse = appeler(&depots, &configuration, requete).await;
+        Resultat { depots, configuration, authorization, utilisateur, responsable, membre, item, reponse }
+    }
+
+    impl Resultat {
+        async fn restaurer(&self) -> ServiceResponse {
+            let uri = format!("/api/utilisateurs/{}/restauration", self.utilisateur.id);
+            let requete = TestRequest::post().uri(&uri).insert_header(("Authorization", self.authorization.clone()));
+            appeler(&self.depots, &self.configuration, requete).await
+        }
+
+        async fn purger(&self, retention: chrono::Duration) -> Value {
+            let configuration = Config { retention_suppressions: retention, ..self.configuration.clone() };
+            let requete = TestRequest::post().uri("/api/corbeille/purge").insert_header(("Authorization", self.authorization.clone()));
+            let reponse = appeler(&self.depots, &configuration, requete).await;
+            assert_eq!(reponse.status(), StatusCode::OK);
+            test::read_body_json(reponse).await
+        }
+    }
+
+    async fn inchange(resultat: &Resultat) {
+        let depots = &resultat.depots;
+        assert!(depots.utilisateurs.find(resultat.utilisateur.id).await.unwrap().is_some());
+        for travail in [&resultat.responsable, &resultat.membre] {
+            assert_eq!(depots.travaux.find(travail.id).await.unwrap().unwrap().version, VERSION_INITIALE);
+        }
+        assert_eq!(depots.checklist.find(resultat.item.id).await.unwrap().unwrap().responsable, resultat.utilisateur.id);
+        assert_eq!(depots.audit.list(&FiltreAudit::default(), &Pagination::tout("date")).await.unwrap().total, 0);
+    }
+
+    #[actix_web::test]
+    async fn liste_filtree_et_paginee() {
+        let depots = Repositories::memoire();
+        let configuration = configuration();
+        let (_, authorization) = connecter(&depots, &configuration, Role::Admin).await;
+        let utilisateurs = [
+            Utilisateur { nom: "Claire".to_string(), equipe: "Réseau".to_string(), ..test_support::utilisateur(Role::Specialiste) },
+            Utilisateur { nom: "Bruno".to_string(), ..test_support::utilisateur(Role::Specialiste) },
+            Utilisateur { nom: "Alice".to_string(), ..test_support::utilisateur(Role::Specialiste) },
+            Utilisateur { nom: "Denis".to_string(), actif: false, ..test_support::utilisateur(Role::Specialiste) },
+            Utilisateur { nom: "Élise".to_string(), ..test_support::utilisateur(Role::Manager) },
+        ];
+        for utilisateur in &utilisateurs {
+            depots.utilisateurs.insert(utilisateur).await.unwrap();
+        }
+        let lister = |query: &str| {
+            let requete = TestRequest::get().uri(&format!("/api/utilisateurs?{query}")).insert_header(("Authorization", authorization.clone()));
+            appeler(&depots, &configuration, requete)
+        };
+
+        let reponse = lister("role=specialiste&actif=true&sort=-nom&limit=2").await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        assert_eq!(reponse.headers().get("X-Total-Count").unwrap(), "3");
+        let page: Vec<Value> = test::read_body_json(reponse).await;
+        assert_eq!(page.iter().map(|u| u["nom"].as_str().unwrap()).collect::<Vec<_>>(), ["Claire", "Bruno"]);
+
+        let reponse = lister("equipe=R%C3%A9seau").await;
+        let page: Vec<Value> = test::read_body_json(reponse).await;
+        assert_eq!(page.iter().map(|u| u["nom"].as_str().unwrap()).collect::<Vec<_>>(), ["Claire"]);
+
+        let reponse = lister("actif=false").await;
+        let page: Vec<Value> = test::read_body_json(reponse).await;
+        assert_eq!(page.iter().map(|u| u["nom"].as_str().unwrap()).collect::<Vec<_>>(), ["Denis"]);
+
+        // Tri hors liste blanche et pagination hors bornes : 422 par champ ; valeur mal formée : 400
+        let reponse = lister("sort=motDePasse&limit=1000").await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let corps: Value = test::read_body_json(reponse).await;
+        let champs: Vec<&str> = corps["erreurs"].as_array().unwrap().iter().map(|e| e["field"].as_str().unwrap()).collect();
+        assert_eq!(champs, ["sort", "limit"]);
+        assert_eq!(lister("actif=peut-etre").await.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn politique_rejeter() {
+        let resultat = supprimer(PolitiqueSuppression::Rejeter, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::CONFLICT);
+        inchange(&resultat).await;
+    }
+
+    #[actix_web::test]
+    async fn politique_cascade() {
+        let resultat = supprimer(PolitiqueSuppression::Cascade, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        let depots = &resultat.depots;
+        assert!(depots.utilisateurs.find_supprime(resultat.utilisateur.id).await.unwrap().is_some());
+        assert!(depots.travaux.find_supprime(resultat.responsable.id).await.unwrap().is_some());
+        assert!(depots.travaux.find(resultat.membre.id).await.unwrap().unwrap().equipe.is_empty());
+        // L'item est supprimé logiquement, avec la même suppression que son responsable
+        assert!(depots.checklist.find(resultat.item.id).await.unwrap().is_none());
+        assert!(depots.checklist.list_by_travail(resultat.responsable.id).await.unwrap().is_empty());
+        let supprime = depots.utilisateurs.find_supprime(resultat.utilisateur.id).await.unwrap().unwrap();
+        let items = depots.checklist.list_supprimes_by_responsable(resultat.utilisateur.id).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, resultat.item.id);
+        assert_eq!(items[0].suppression, supprime.suppression);
+    }
+
+    #[actix_web::test]
+    async fn restauration_apres_cascade() {
+        let resultat = supprimer(PolitiqueSuppression::Cascade, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        assert_eq!(resultat.restaurer().await.status(), StatusCode::OK);
+
+        let depots = &resultat.depots;
+        assert!(depots.utilisateurs.find(resultat.utilisateur.id).await.unwrap().is_some());
+        assert!(depots.travaux.find(resultat.responsable.id).await.unwrap().is_some());
+        let item = depots.checklist.find(resultat.item.id).await.unwrap().unwrap();
+        assert_eq!(item.suppression, None);
+        assert!(depots.checklist.list_supprimes_by_responsable(resultat.utilisateur.id).await.unwrap().is_empty());
+        let restauration = FiltreAudit { action: Some(ActionAudit::Restauration), ..FiltreAudit::default() };
+        assert_eq!(depots.audit.list(&restauration, &Pagination::tout("date")).await.unwrap().total, 3);
+        // Un travail retiré de l'équipe n'y revient pas : ce retrait est une modification, pas une suppression
+        assert!(depots.travaux.find(resultat.membre.id).await.unwrap().unwrap().equipe.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn purge_apres_la_retention() {
+        let resultat = supprimer(PolitiqueSuppression::Cascade, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        let depots = &resultat.depots;
+
+        // Période de rétention en cours : rien n'est purgé
+        let purge = resultat.purger(chrono::Duration::days(30)).await;
+        assert_eq!((purge["utilisateurs"].as_u64(), purge["travaux"].as_u64(), purge["itemsChecklist"].as_u64()), (Some(0), Some(0), Some(0)));
+        assert_eq!(depots.checklist.list_supprimes_by_responsable(resultat.utilisateur.id).await.unwrap().len(), 1);
+
+        // Période écoulée : l'item, le travail puis l'utilisateur disparaissent définitivement
+        let purge = resultat.purger(chrono::Duration::zero()).await;
+        assert_eq!((purge["utilisateurs"].as_u64(), purge["travaux"].as_u64(), purge["itemsChecklist"].as_u64()), (Some(1), Some(1), Some(1)));
+        assert!(depots.checklist.list_supprimes_by_responsable(resultat.utilisateur.id).await.unwrap().is_empty());
+        assert!(depots.travaux.find_supprime(resultat.responsable.id).await.unwrap().is_none());
+        assert!(depots.utilisateurs.find_supprime(resultat.utilisateur.id).await.unwrap().is_none());
+        assert_eq!(resultat.restaurer().await.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn email_reutilise_apres_suppression() {
+        // L'unicité de l'email ne porte que sur les utilisateurs non supprimés (index partiel)
+        let resultat = supprimer(PolitiqueSuppression::Cascade, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        let homonyme = json!({
+            "nom": "Homonyme",
+            "email": resultat.utilisateur.email,
+            "role": "conseiller",
+            "equipe": "Infrastructure",
+            "actif": true,
+        });
+        let requete = TestRequest::post().uri("/api/utilisateurs").insert_header(("Authorization", resultat.authorization.clone())).set_json(&homonyme);
+        assert_eq!(appeler(&resultat.depots, &resultat.configuration, requete).await.status(), StatusCode::CREATED);
+        let requete = TestRequest::post().uri("/api/utilisateurs").insert_header(("Authorization", resultat.authorization.clone())).set_json(&homonyme);
+        assert_eq!(appeler(&resultat.depots, &resultat.configuration, requete).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let reponse = resultat.restaurer().await;
+        assert_eq!(reponse.status(), StatusCode::CONFLICT);
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["code"], "restauration_refusee");
+        assert!(resultat.depots.checklist.find(resultat.item.id).await.unwrap().is_none());
+    }
+
+    #[actix_web::test]
+    async fn politique_reassigner() {
+        let cible = test_support::utilisateur(Role::Specialiste);
+        let resultat = supprimer(PolitiqueSuppression::Reassigner, Some(&cible)).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::NO_CONTENT);
+        let depots = &resultat.depots;
+        assert!(depots.utilisateurs.find_supprime(resultat.utilisateur.id).await.unwrap().is_some());
+        assert_eq!(depots.travaux.find(resultat.responsable.id).await.unwrap().unwrap().responsable, cible.id);
+        assert_eq!(depots.travaux.find(resultat.membre.id).await.unwrap().unwrap().equipe, vec![cible.id]);
+        assert_eq!(depots.checklist.find(resultat.item.id).await.unwrap().unwrap().responsable, cible.id);
+    }
+
+    #[actix_web::test]
+    async fn politique_reassigner_cible_invalide() {
+        let resultat = supprimer(PolitiqueSuppression::Reassigner, None).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::BAD_REQUEST);
+        inchange(&resultat).await;
+
+        // Utilisateur désactivé : il ne peut pas reprendre les travaux
+        let inactif = Utilisateur { actif: false, ..test_support::utilisateur(Role::Specialiste) };
+        let resultat = supprimer(PolitiqueSuppression::Reassigner, Some(&inactif)).await;
+        assert_eq!(resultat.reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        inchange(&resultat).await;
     }
 }
@@ -0,0 +1,163 @@
+// Verrouillage optimiste des projets, travaux et utilisateurs : la version de l'entité est
+// exposée dans l'en-tête ETag, et une écriture portant un If-Match périmé est refusée (412)
+
+use std::future::{ready, Ready};
+
+use actix_web::dev::Payload;
+use actix_web::http::header::{EntityTag, Header, IfMatch, ETag, IF_MATCH};
+use actix_web::{FromRequest, HttpRequest};
+
+use crate::error::{ApiError, Result};
+
+pub fn etag(version: i32) -> ETag {
+    ETag(EntityTag::new_strong(version.to_string()))
+}
+
+// En-tête If-Match de la requête ; absent, l'écriture n'est pas conditionnelle
+pub struct VersionAttendue(Option<IfMatch>);
+
+impl FromRequest for VersionAttendue {
+    type Error = ApiError;
+    type Future = Ready<Result<Self>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        if !req.headers().contains_key(IF_MATCH) {
+            return ready(Ok(VersionAttendue(None)));
+        }
+        // Le parseur d'actix ignore les éléments mal formés : une liste vide est donc invalide
+        ready(match IfMatch::parse(req) {
+            Ok(IfMatch::Items(etags)) if etags.is_empty() => Err(ApiError::BadRequest("En-tête If-Match invalide".to_string())),
+            Ok(if_match) => Ok(VersionAttendue(Some(if_match))),
+            Err(_) => Err(ApiError::BadRequest("En-tête If-Match invalide".to_string())),
+        })
+    }
+}
+
+impl VersionAttendue {
+    // Comparaison forte (RFC 9110) : un ETag faible W/"..." ne correspond jamais
+    pub fn verifier(&self, actuelle: i32) -> Result<()> {
+        match &self.0 {
+            None | Some(IfMatch::Any) => Ok(()),
+            Some(IfMatch::Items(etags)) if etags.iter().any(|e| e.strong_eq(&etag(actuelle))) => Ok(()),
+            Some(IfMatch::Items(_)) => Err(ApiError::VersionPerimee { actuelle }),
+        }
+    }
+}
+
+// Mise à jour refusée par le stockage après lecture : l'entité a été modifiée (`actuelle` :
+// sa nouvelle version) ou supprimée entre-temps
+pub fn ecriture_refusee(actuelle: Option<i32>, non_trouve: &str) -> ApiError {
+    match actuelle {
+        Some(actuelle) => ApiError::VersionPerimee { actuelle },
+        None => ApiError::NotFound(non_trouve.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::header::{ETAG, IF_MATCH};
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{json, Value};
+
+    use crate::config::Config;
+    use crate::handlers::test_support::{appeler, configuration, connecter};
+    use crate::models::{test_support, Role, Travail};
+    use crate::repositories::Repositories;
+
+    struct Contexte {
+        depots: Repositories,
+        config: Config,
+        authorization: String,
+    }
+
+    impl Contexte {
+        async fn nouveau() -> Self {
+            let depots = Repositories::memoire();
+            let config = configuration();
+            let (_, authorization) = connecter(&depots, &config, Role::Admin).await;
+            Contexte { depots, config, authorization }
+        }
+
+        async fn appeler(&self, requete: TestRequest, if_match: Option<&str>) -> ServiceResponse {
+            let requete = requete.insert_header(("Authorization", self.authorization.clone()));
+            let requete = match if_match {
+                Some(valeur) => requete.insert_header((IF_MATCH, valeur.to_string())),
+                None => requete,
+            };
+            appeler(&self.depots, &self.config, requete).await
+        }
+    }
+
+    fn etag(reponse: &ServiceResponse) -> Option<String> {
+        reponse.headers().get(ETAG).map(|valeur| valeur.to_str().unwrap().to_string())
+    }
+
+    #[actix_web::test]
+    async fn etag_puis_modification_conditionnelle() {
+        let ctx = Contexte::nouveau().await;
+        let projet = test_support::projet();
+        ctx.depots.projets.insert(&projet).await.unwrap();
+        let uri = format!("/api/projets/{}", projet.id);
+
+        let reponse = ctx.appeler(TestRequest::get().uri(&uri), None).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        assert_eq!(etag(&reponse).as_deref(), Some("\"1\""));
+
+        // If-Match à jour : la version passe à 2, annoncée par l'ETag et dans le corps
+        let reponse = ctx.appeler(TestRequest::patch().uri(&uri).set_json(json!({"nom": "Renommé"})), Some("\"1\"")).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        assert_eq!(etag(&reponse).as_deref(), Some("\"2\""));
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["version"], 2);
+
+        // If-Match périmé : 412 avec la version actuelle, rien n'est écrit
+        let reponse = ctx.appeler(TestRequest::patch().uri(&uri).set_json(json!({"nom": "Perdu"})), Some("\"1\"")).await;
+        assert_eq!(reponse.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(etag(&reponse).as_deref(), Some("\"2\""));
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["code"], "version_perimee");
+        assert_eq!(corps["version"], 2);
+        let courant = ctx.depots.projets.find(projet.id).await.unwrap().unwrap();
+        assert_eq!((courant.nom.as_str(), courant.version), ("Renommé", 2));
+
+        // Comparaison forte : un ETag faible ne correspond jamais
+        let reponse = ctx.appeler(TestRequest::patch().uri(&uri).set_json(json!({"nom": "Faible"})), Some("W/\"2\"")).await;
+        assert_eq!(reponse.status(), StatusCode::PRECONDITION_FAILED);
+        let reponse = ctx.appeler(TestRequest::patch().uri(&uri).set_json(json!({"nom": "Invalide"})), Some("2")).await;
+        assert_eq!(reponse.status(), StatusCode::BAD_REQUEST);
+
+        // If-Match est facultatif (pas de 428) : sans lui, ou avec *, l'écriture n'est pas conditionnelle
+        let reponse = ctx.appeler(TestRequest::patch().uri(&uri).set_json(json!({"nom": "Sans condition"})), None).await;
+        assert_eq!(etag(&reponse).as_deref(), Some("\"3\""));
+        let reponse = ctx.appeler(TestRequest::patch().uri(&uri).set_json(json!({"nom": "Toute version"})), Some("*")).await;
+        assert_eq!(etag(&reponse).as_deref(), Some("\"4\""));
+        assert_eq!(ctx.depots.projets.find(projet.id).await.unwrap().unwrap().version, 4);
+    }
+
+    #[actix_web::test]
+    async fn suppression_et_transition_conditionnelles() {
+        let ctx = Contexte::nouveau().await;
+        let projet = test_support::projet();
+        ctx.depots.projets.insert(&projet).await.unwrap();
+        let travail = Travail { projet_id: projet.id, ..test_support::travail() };
+        ctx.depots.travaux.insert(&travail).await.unwrap();
+
+        let uri = format!("/api/travaux/{}/transitions", travail.id);
+        let transition = json!({"statut": "annulé", "raison": "Reporté"});
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(&transition), Some("\"2\"")).await;
+        assert_eq!(reponse.status(), StatusCode::PRECONDITION_FAILED);
+        let reponse = ctx.appeler(TestRequest::post().uri(&uri).set_json(&transition), Some("\"1\"")).await;
+        assert_eq!(reponse.status(), StatusCode::OK);
+        assert_eq!(etag(&reponse).as_deref(), Some("\"2\""));
+
+        let uri = format!("/api/travaux/{}", travail.id);
+        let reponse = ctx.appeler(TestRequest::delete().uri(&uri), Some("\"1\"")).await;
+        assert_eq!(reponse.status(), StatusCode::PRECONDITION_FAILED);
+        assert!(ctx.depots.travaux.find(travail.id).await.unwrap().is_some());
+        let reponse = ctx.appeler(TestRequest::delete().uri(&uri), Some("\"2\"")).await;
+        assert_eq!(reponse.status(), StatusCode::NO_CONTENT);
+        assert!(ctx.depots.travaux.find_supprime(travail.id).await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,50 @@
+// Backend de planification des travaux : modules de l'application et assemblage des routes,
+// partagés par le serveur (main.rs) et les tests d'intégration (tests/)
+
+use actix_web::{web, HttpResponse, Result};
+use chrono::Utc;
+
+pub mod auth;
+pub mod config;
+pub mod calendrier;
+pub mod conflits;
+pub mod database;
+pub mod dependances;
+pub mod error;
+pub mod models;
+pub mod openapi;
+pub mod permissions;
+pub mod handlers;
+pub mod repositories;
+pub mod sante;
+pub mod validation;
+
+use config::Config;
+use repositories::Repositories;
+
+// Routes et données partagées de l'application ; les middlewares restent à la charge de l'appelant
+pub fn configurer(cfg: &mut web::ServiceConfig, config: web::Data<Config>, repositories: &Repositories) {
+    cfg.app_data(config)
+        .configure(error::config)
+        .configure(|cfg| repositories.register(cfg))
+        .configure(handlers::config)
+        .configure(openapi::config)
+        .route("/health", web::get().to(health_check));
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "supervision",
+    responses(
+        (status = 200, description = "Serveur disponible"),
+    ),
+    security(()),
+)]
+async fn health_check() -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "status": "ok",
+        "message": "Serveur Rust/Actix fonctionnel",
+        "timestamp": Utc::now().to_rfc3339()
+    })))
+}
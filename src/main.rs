@@ -1,58 +1,147 @@
-use actix_web::{web, App, HttpServer, HttpResponse, Result};
-use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use actix_web::{middleware, web, App, HttpServer};
+use chrono::Utc;
 use uuid::Uuid;
-use std::sync::Mutex;
-use std::collections::HashMap;
 
-mod models;
-mod handlers;
-
-use models::{Projet, Travail, Utilisateur, StatutProjet, Priorite, TypeTravail, Application, Environnement, StatutTravail, Role};
+use travaux_backend::config::Config;
+use travaux_backend::database::Database;
+use travaux_backend::models::{self, Projet, Travail, Utilisateur, StatutProjet, Priorite, TypeTravail, Application, Environnement, StatutTravail, Role, TypeEntite, VERSION_INITIALE};
+use travaux_backend::repositories::Repositories;
+use travaux_backend::{auth, handlers};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    println!("🚀 Démarrage du serveur Rust/Actix sur http://localhost:8080");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("migrate") => return run_migrate(&args[1..]).await.map_err(std::io::Error::other),
+        Some("mot-de-passe") => return run_mot_de_passe(&args[1..]).await.map_err(std::io::Error::other),
+        Some("creer-admin") => return run_creer_admin(&args[1..]).await.map_err(std::io::Error::other),
+        _ => {}
+    }
 
-    // Données mock en mémoire (remplacera par base de données)
-    let projets_data = web::Data::new(Mutex::new(HashMap::<Uuid, Projet>::new()));
-    let travaux_data = web::Data::new(Mutex::new(HashMap::<Uuid, Travail>::new()));
-    let utilisateurs_data = web::Data::new(Mutex::new(HashMap::<Uuid, Utilisateur>::new()));
+    println!("🚀 Démarrage du serveur Rust/Actix sur http://localhost:8080");
 
-    // Initialisation avec des données de test
-    init_mock_data(&projets_data, &travaux_data, &utilisateurs_data);
+    let config = web::Data::new(Config::from_env().map_err(std::io::Error::other)?);
+
+    // PostgreSQL si DATABASE_URL est défini, sinon données mock en mémoire (mode démo)
+    let repositories = match std::env::var("DATABASE_URL") {
+        Ok(database_url) => {
+            let database = Database::new(&database_url).await.map_err(std::io::Error::other)?;
+            // MIGRATIONS_AUTO=false laisse les DBA appliquer les migrations via `migrate`
+            if std::env::var("MIGRATIONS_AUTO").map(|v| v != "false").unwrap_or(true) {
+                database.migrate().await.map_err(std::io::Error::other)?;
+            }
+            println!("🗄️  Stockage PostgreSQL");
+            Repositories::postgres(&database.pool)
+        }
+        Err(_) => {
+            let repositories = Repositories::memoire();
+            init_mock_data(&repositories).await.map_err(std::io::Error::other)?;
+            println!("🧪 DATABASE_URL absent : stockage en mémoire (données de démo)");
+            repositories
+        }
+    };
 
     HttpServer::new(move || {
         App::new()
-            .app_data(projets_data.clone())
-            .app_data(travaux_data.clone())
-            .app_data(utilisateurs_data.clone())
-            .configure(handlers::projet_handlers::config)
-            .configure(handlers::travail_handlers::config)
-            .configure(handlers::utilisateur_handlers::config)
-            .route("/health", web::get().to(health_check))
+            .wrap(middleware::DefaultHeaders::new().add(("X-Contrat-Version", models::VERSION_CONTRAT)))
+            .configure(|cfg| travaux_backend::configurer(cfg, config.clone(), &repositories))
     })
     .bind("127.0.0.1:8080")?
     .run()
     .await
 }
 
-async fn health_check() -> Result<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "status": "ok",
-        "message": "Serveur Rust/Actix fonctionnel",
-        "timestamp": Utc::now().to_rfc3339()
-    })))
+// Sous-commande `migrate [up | down <version> | status]`
+async fn run_migrate(args: &[String]) -> anyhow::Result<()> {
+    let database_url = std::env::var("DATABASE_URL")
+        .map_err(|_| anyhow::anyhow!("DATABASE_URL doit être défini pour migrer"))?;
+    let database = Database::new(&database_url).await?;
+
+    match args.first().map(String::as_str).unwrap_or("up") {
+        "up" => {
+            database.migrate().await?;
+            println!("✅ Migrations appliquées");
+        }
+        "down" => {
+            let version: i64 = args.get(1)
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| anyhow::anyhow!("usage : migrate down <version cible>"))?;
+            database.revert(version).await?;
+            println!("↩️  Migrations annulées jusqu'à la version {}", version);
+        }
+        "status" => {
+            for etat in database.migration_status().await? {
+                let marque = if etat.appliquee { "appliquée" } else { "en attente" };
+                println!("{:04} {:<40} {}", etat.version, etat.description, marque);
+            }
+        }
+        autre => anyhow::bail!("commande migrate inconnue : {} (up, down <version>, status)", autre),
+    }
+
+    Ok(())
+}
+
+// Sous-commande `mot-de-passe <email>` : définit le mot de passe lu sur l'entrée standard
+async fn run_mot_de_passe(args: &[String]) -> anyhow::Result<()> {
+    let email = args.first().ok_or_else(|| anyhow::anyhow!("usage : mot-de-passe <email>"))?;
+    let repositories = repositories_cli().await?;
+
+    let utilisateur = repositories.utilisateurs.find_by_email(email).await?
+        .ok_or_else(|| anyhow::anyhow!("Aucun utilisateur avec l'email {}", email))?;
+    definir_mot_de_passe_stdin(&repositories, utilisateur.id).await?;
+    println!("🔑 Mot de passe défini pour {}", email);
+    Ok(())
+}
+
+// Sous-commande `creer-admin <email> <nom>` : premier compte Admin d'une base vide,
+// mot de passe lu sur l'entrée standard
+async fn run_creer_admin(args: &[String]) -> anyhow::Result<()> {
+    let (Some(email), Some(nom)) = (args.first(), args.get(1)) else {
+        anyhow::bail!("usage : creer-admin <email> <nom>");
+    };
+    let repositories = repositories_cli().await?;
+    if repositories.utilisateurs.find_by_email(email).await?.is_some() {
+        anyhow::bail!("L'email {} est déjà utilisé (utiliser mot-de-passe)", email);
+    }
+
+    let admin = Utilisateur {
+        id: Uuid::new_v4(),
+        nom: nom.clone(),
+        email: email.clone(),
+        role: Role::Admin,
+        equipe: "Administration".to_string(),
+        actif: true,
+        version: VERSION_INITIALE,
+        suppression: None,
+    };
+    repositories.utilisateurs.insert(&admin).await?;
+    handlers::audit_handlers::journaliser(
+        repositories.audit.as_ref(), None, TypeEntite::Utilisateur, admin.id, None, Some(&admin),
+    ).await?;
+    definir_mot_de_passe_stdin(&repositories, admin.id).await?;
+    println!("👤 Administrateur {} créé", email);
+    Ok(())
+}
+
+async fn repositories_cli() -> anyhow::Result<Repositories> {
+    let database_url = std::env::var("DATABASE_URL")
+        .map_err(|_| anyhow::anyhow!("DATABASE_URL doit être défini"))?;
+    let database = Database::new(&database_url).await?;
+    Ok(Repositories::postgres(&database.pool))
+}
+
+async fn definir_mot_de_passe_stdin(repositories: &Repositories, utilisateur_id: Uuid) -> anyhow::Result<()> {
+    let mut mot_de_passe = String::new();
+    std::io::stdin().read_line(&mut mot_de_passe)?;
+    let mot_de_passe = mot_de_passe.trim_end_matches(['\r', '\n']);
+    if mot_de_passe.chars().count() < 8 {
+        anyhow::bail!("Mot de passe trop court (8 caractères minimum)");
+    }
+    repositories.utilisateurs.set_mot_de_passe(utilisateur_id, &auth::hacher_mot_de_passe(mot_de_passe)?).await?;
+    Ok(())
 }
 
-fn init_mock_data(
-    projets: &web::Data<Mutex<HashMap<Uuid, Projet>>>,
-    travaux: &web::Data<Mutex<HashMap<Uuid, Travail>>>,
-    utilisateurs: &web::Data<Mutex<HashMap<Uuid, Utilisateur>>>,
-) {
-    let mut projets_map = projets.lock().unwrap();
-    let mut travaux_map = travaux.lock().unwrap();
-    let mut utilisateurs_map = utilisateurs.lock().unwrap();
+async fn init_mock_data(repositories: &Repositories) -> anyhow::Result<()> {
 
     // Utilisateurs mock
     let user1 = Utilisateur {
@@ -62,6 +151,8 @@ fn init_mock_data(
         role: Role::Manager,
         equipe: "Infrastructure".to_string(),
         actif: true,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
 
     let user2 = Utilisateur {
@@ -71,10 +162,43 @@ fn init_mock_data(
         role: Role::Specialiste,
         equipe: "Base de données".to_string(),
         actif: true,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
 
-    utilisateurs_map.insert(user1.id, user1.clone());
-    utilisateurs_map.insert(user2.id, user2.clone());
+    let admin = Utilisateur {
+        id: Uuid::new_v4(),
+        nom: "Admin Démo".to_string(),
+        email: "admin@entreprise.com".to_string(),
+        role: Role::Admin,
+        equipe: "Administration".to_string(),
+        actif: true,
+        version: VERSION_INITIALE,
+        suppression: None,
+    };
+
+    repositories.utilisateurs.insert(&user1).await?;
+    repositories.utilisateurs.insert(&user2).await?;
+    repositories.utilisateurs.insert(&admin).await?;
+
+    // Mot de passe commun aux comptes de démo : DEMO_MOT_DE_PASSE, sinon tiré au hasard à chaque
+    // démarrage et affiché une seule fois (aucun mot de passe connu d'avance)
+    let mot_de_passe = match std::env::var("DEMO_MOT_DE_PASSE") {
+        Ok(mot_de_passe) if mot_de_passe.chars().count() < 8 => {
+            anyhow::bail!("DEMO_MOT_DE_PASSE trop court (8 caractères minimum)")
+        }
+        Ok(mot_de_passe) => mot_de_passe,
+        Err(_) => {
+            let mot_de_passe = auth::mot_de_passe_aleatoire()?;
+            println!("🔑 Mot de passe des comptes de démo (affiché une seule fois) : {}", mot_de_passe);
+            mot_de_passe
+        }
+    };
+    let hash = auth::hacher_mot_de_passe(&mot_de_passe)?;
+    repositories.utilisateurs.set_mot_de_passe(user1.id, &hash).await?;
+    repositories.utilisateurs.set_mot_de_passe(user2.id, &hash).await?;
+    repositories.utilisateurs.set_mot_de_passe(admin.id, &hash).await?;
+    println!("🔑 Comptes de démo : {}, {}, {}", admin.email, user1.email, user2.email);
 
     // Projets mock
     let projet1 = Projet {
@@ -86,6 +210,8 @@ fn init_mock_data(
         date_fin_prevue: Utc::now() + chrono::Duration::days(60),
         statut: StatutProjet::EnCours,
         priorite: Priorite::Haute,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
 
     let projet2 = Projet {
@@ -97,10 +223,12 @@ fn init_mock_data(
         date_fin_prevue: Utc::now() + chrono::Duration::days(45),
         statut: StatutProjet::Planifie,
         priorite: Priorite::Moyenne,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
 
-    projets_map.insert(projet1.id, projet1.clone());
-    projets_map.insert(projet2.id, projet2.clone());
+    repositories.projets.insert(&projet1).await?;
+    repositories.projets.insert(&projet2).await?;
 
     // Travaux mock
     let travail1 = Travail {
@@ -115,7 +243,12 @@ fn init_mock_data(
         statut: StatutTravail::EnCours,
         responsable: user2.id,
         equipe: vec![user1.id, user2.id],
+        promotion_de: None,
+        version: VERSION_INITIALE,
+        suppression: None,
     };
 
-    travaux_map.insert(travail1.id, travail1);
+    repositories.travaux.insert(&travail1).await?;
+
+    Ok(())
 }
@@ -1,35 +1,80 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Version du contrat JSON partagé avec le client (src/types/index.ts) : champs en camelCase,
+// énumérations en minuscules accentuées. Les graphies précédentes (snake_case, variantes
+// PascalCase) restent acceptées en entrée le temps de la transition.
+pub const VERSION_CONTRAT: &str = "2";
+
+// Version d'une entité modifiable (projet, travail, utilisateur), incrémentée à chaque écriture :
+// sert d'ETag et de contrôle de verrouillage optimiste
+pub const VERSION_INITIALE: i32 = 1;
+
+fn version_initiale() -> i32 {
+    VERSION_INITIALE
+}
+
+// Suppression logique d'un projet, travail, utilisateur ou item de checklist : absent des
+// lectures courantes, restaurable jusqu'à sa purge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Suppression {
+    pub le: DateTime<Utc>,
+    // None pour les opérations hors API (ligne de commande)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub par: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct Projet {
     pub id: Uuid,
     pub code: String,
     pub nom: String,
     pub description: String,
+    #[serde(alias = "date_debut")]
     pub date_debut: DateTime<Utc>,
+    #[serde(alias = "date_fin_prevue")]
     pub date_fin_prevue: DateTime<Utc>,
     pub statut: StatutProjet,
     pub priorite: Priorite,
+    #[serde(default = "version_initiale")]
+    pub version: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct Travail {
     pub id: Uuid,
+    #[serde(alias = "projet_id")]
     pub projet_id: Uuid,
+    #[serde(rename = "type", alias = "type_travail")]
     pub type_travail: TypeTravail,
     pub application: Application,
     pub environnement: Environnement,
     pub description: String,
+    #[serde(alias = "date_debut")]
     pub date_debut: DateTime<Utc>,
+    #[serde(alias = "date_fin_prevue")]
     pub date_fin_prevue: DateTime<Utc>,
     pub statut: StatutTravail,
     pub responsable: Uuid,
     pub equipe: Vec<Uuid>,
+    // Travail dont celui-ci est la promotion sur l'environnement suivant (Test → Formation → Production)
+    #[serde(alias = "promotion_de", default, skip_serializing_if = "Option::is_none")]
+    pub promotion_de: Option<Uuid>,
+    #[serde(default = "version_initiale")]
+    pub version: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct Utilisateur {
     pub id: Uuid,
     pub nom: String,
@@ -37,81 +82,985 @@ pub struct Utilisateur {
     pub role: Role,
     pub equipe: String,
     pub actif: bool,
+    #[serde(default = "version_initiale")]
+    pub version: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct ChecklistItem {
     pub id: Uuid,
+    #[serde(alias = "travail_id")]
     pub travail_id: Uuid,
     pub description: String,
     pub statut: StatutChecklist,
     pub responsable: Uuid,
+    #[serde(alias = "date_echeance", default, skip_serializing_if = "Option::is_none")]
     pub date_echeance: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub commentaires: Option<String>,
+    pub ordre: i32,
+    // Renseignée quand l'item disparaît avec son responsable (suppression en cascade)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<Suppression>,
+}
+
+// Modèle de checklist instancié à la création d'un travail du type visé.
+// `application` et `environnement` à None signifient « toutes ».
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModeleChecklist {
+    pub id: Uuid,
+    pub nom: String,
+    #[serde(alias = "type_travail")]
+    pub type_travail: TypeTravail,
+    pub application: Option<Application>,
+    pub environnement: Option<Environnement>,
+    pub etapes: Vec<EtapeModele>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EtapeModele {
+    pub description: String,
+    // Échéance en jours relatifs à `date_debut` du travail (négatif = avant le début)
+    #[serde(alias = "decalage_jours")]
+    pub decalage_jours: Option<i64>,
+}
+
+impl ModeleChecklist {
+    // Nombre de critères précisés par le modèle, ou None s'il ne s'applique pas au travail
+    pub fn specificite(&self, travail: &Travail) -> Option<u8> {
+        if self.type_travail != travail.type_travail {
+            return None;
+        }
+        let mut score = 0;
+        match &self.application {
+            Some(application) if *application != travail.application => return None,
+            Some(_) => score += 1,
+            None => {}
+        }
+        match &self.environnement {
+            Some(environnement) if *environnement != travail.environnement => return None,
+            Some(_) => score += 1,
+            None => {}
+        }
+        Some(score)
+    }
+
+    pub fn instancier(&self, travail: &Travail) -> Vec<ChecklistItem> {
+        self.etapes.iter()
+            .enumerate()
+            .map(|(ordre, etape)| ChecklistItem {
+                id: Uuid::new_v4(),
+                travail_id: travail.id,
+                description: etape.description.clone(),
+                statut: StatutChecklist::NonDemarre,
+                responsable: travail.responsable,
+                date_echeance: etape.decalage_jours
+                    .map(|jours| travail.date_debut + chrono::Duration::days(jours)),
+                commentaires: None,
+                ordre: ordre as i32,
+                suppression: None,
+            })
+            .collect()
+    }
+}
+
+// Le modèle le plus spécifique applicable au travail
+pub fn modele_applicable<'a>(modeles: &'a [ModeleChecklist], travail: &Travail) -> Option<&'a ModeleChecklist> {
+    modeles.iter()
+        .filter_map(|m| m.specificite(travail).map(|score| (score, m)))
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, m)| m)
+}
+
+// Historique des changements de statut d'un projet ou d'un travail
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", try_from = "TransitionStatutBrute")]
+pub struct TransitionStatut {
+    pub id: Uuid,
+    pub entite: TypeEntite,
+    #[serde(alias = "entite_id")]
+    pub entite_id: Uuid,
+    pub de: StatutEntite,
+    pub vers: StatutEntite,
+    pub raison: Option<String>,
+    pub date: DateTime<Utc>,
+}
+
+// Les graphies des statuts de projet et de travail se recouvrent : `de` et `vers` se
+// désérialisent selon l'entité
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransitionStatutBrute {
+    id: Uuid,
+    entite: TypeEntite,
+    #[serde(alias = "entite_id")]
+    entite_id: Uuid,
+    de: serde_json::Value,
+    vers: serde_json::Value,
+    raison: Option<String>,
+    date: DateTime<Utc>,
+}
+
+impl TryFrom<TransitionStatutBrute> for TransitionStatut {
+    type Error = String;
+
+    fn try_from(brute: TransitionStatutBrute) -> Result<Self, Self::Error> {
+        let statut = |valeur: serde_json::Value| -> Result<StatutEntite, String> {
+            match brute.entite {
+                TypeEntite::Projet => serde_json::from_value(valeur).map(StatutEntite::Projet),
+                TypeEntite::Travail => serde_json::from_value(valeur).map(StatutEntite::Travail),
+                ref autre => return Err(format!("Pas de statut pour l'entité {}", autre.as_str())),
+            }
+            .map_err(|e| e.to_string())
+        };
+        Ok(TransitionStatut {
+            id: brute.id,
+            de: statut(brute.de)?,
+            vers: statut(brute.vers)?,
+            entite: brute.entite,
+            entite_id: brute.entite_id,
+            raison: brute.raison,
+            date: brute.date,
+        })
+    }
+}
+
+// Statut d'un projet ou d'un travail : sérialisé avec la graphie du contrat (« en_cours »),
+// stocké en base sous le nom de la variante (« EnCours »)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum StatutEntite {
+    Projet(StatutProjet),
+    Travail(StatutTravail),
+}
+
+impl StatutEntite {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatutEntite::Projet(statut) => statut.as_str(),
+            StatutEntite::Travail(statut) => statut.as_str(),
+        }
+    }
+
+    // Relecture d'une transition stockée : l'entité indique l'énumération du statut
+    pub fn depuis_texte(entite: &TypeEntite, valeur: &str) -> Result<Self, String> {
+        match entite {
+            TypeEntite::Projet => valeur.parse().map(StatutEntite::Projet),
+            TypeEntite::Travail => valeur.parse().map(StatutEntite::Travail),
+            autre => Err(format!("Pas de statut pour l'entité {}", autre.as_str())),
+        }
+    }
+}
+
+impl std::fmt::Display for StatutEntite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(valeur)) => f.write_str(&valeur),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+impl From<StatutProjet> for StatutEntite {
+    fn from(statut: StatutProjet) -> Self {
+        StatutEntite::Projet(statut)
+    }
+}
+
+impl From<StatutTravail> for StatutEntite {
+    fn from(statut: StatutTravail) -> Self {
+        StatutEntite::Travail(statut)
+    }
+}
+
+// Demande de validation managériale d'un travail de production
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DemandeApprobation {
+    pub id: Uuid,
+    #[serde(alias = "travail_id")]
+    pub travail_id: Uuid,
+    pub demandeur: Uuid,
+    pub date: DateTime<Utc>,
+    // Renseignée quand une nouvelle demande la remplace ou que les dates / le périmètre du travail changent
+    #[serde(alias = "invalidee_le")]
+    pub invalidee_le: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Approbation {
+    pub id: Uuid,
+    #[serde(alias = "demande_id")]
+    pub demande_id: Uuid,
+    pub approbateur: Uuid,
+    pub decision: DecisionApprobation,
+    pub commentaire: Option<String>,
+    pub date: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum DecisionApprobation {
+    #[serde(rename = "approuvé", alias = "Approuve")]
+    Approuve,
+    #[serde(rename = "rejeté", alias = "Rejete")]
+    Rejete,
+}
+
+// État calculé d'une demande à partir de ses décisions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum StatutApprobation {
+    #[serde(rename = "en_attente", alias = "EnAttente")]
+    EnAttente,
+    #[serde(rename = "approuvée", alias = "Approuvee")]
+    Approuvee,
+    #[serde(rename = "rejetée", alias = "Rejetee")]
+    Rejetee,
+    #[serde(rename = "invalidée", alias = "Invalidee")]
+    Invalidee,
+}
+
+impl DemandeApprobation {
+    // Un seul rejet suffit à bloquer ; il faut `requises` approbations sinon
+    pub fn statut(&self, decisions: &[Approbation], requises: usize) -> StatutApprobation {
+        if self.invalidee_le.is_some() {
+            StatutApprobation::Invalidee
+        } else if decisions.iter().any(|d| d.decision == DecisionApprobation::Rejete) {
+            StatutApprobation::Rejetee
+        } else if decisions.iter().filter(|d| d.decision == DecisionApprobation::Approuve).count() >= requises {
+            StatutApprobation::Approuvee
+        } else {
+            StatutApprobation::EnAttente
+        }
+    }
+}
+
+impl Travail {
+    pub fn exige_approbation(&self) -> bool {
+        self.environnement == Environnement::Production
+    }
+
+    // Vrai si la modification touche les dates ou le périmètre couverts par une approbation
+    pub fn perimetre_modifie(&self, autre: &Travail) -> bool {
+        self.projet_id != autre.projet_id
+            || self.type_travail != autre.type_travail
+            || self.application != autre.application
+            || self.environnement != autre.environnement
+            || self.description != autre.description
+            || self.date_debut != autre.date_debut
+            || self.date_fin_prevue != autre.date_fin_prevue
+    }
+}
+
+// Période de gel (clôture d'exercice, paie...) pendant laquelle aucun travail n'est permis sur
+// les applications / environnements visés. Liste vide = toutes les applications / tous les environnements.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodeGel {
+    pub id: Uuid,
+    #[serde(alias = "date_debut")]
+    pub date_debut: DateTime<Utc>,
+    #[serde(alias = "date_fin")]
+    pub date_fin: DateTime<Utc>,
+    pub applications: Vec<Application>,
+    pub environnements: Vec<Environnement>,
+    pub raison: String,
+    pub actif: bool,
+}
+
+// Autorisation exceptionnelle, accordée par un Admin, de planifier un travail pendant un gel
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DerogationGel {
+    pub id: Uuid,
+    #[serde(alias = "periode_gel_id")]
+    pub periode_gel_id: Uuid,
+    #[serde(alias = "travail_id")]
+    pub travail_id: Uuid,
+    pub auteur: Uuid,
+    pub justification: String,
+    pub date: DateTime<Utc>,
+}
+
+impl PeriodeGel {
+    // Le gel est actif, vise l'application et l'environnement du travail (actif) et chevauche ses dates
+    pub fn concerne(&self, travail: &Travail) -> bool {
+        self.actif
+            && travail.statut.est_actif()
+            && (self.applications.is_empty() || self.applications.contains(&travail.application))
+            && (self.environnements.is_empty() || self.environnements.contains(&travail.environnement))
+            && travail.date_debut < self.date_fin
+            && self.date_debut < travail.date_fin_prevue
+    }
+}
+
+// Dépendance fin → début entre deux travaux d'un même projet : `travail_id` ne peut commencer
+// qu'après la fin de `depend_de`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependance {
+    pub id: Uuid,
+    #[serde(alias = "travail_id")]
+    pub travail_id: Uuid,
+    #[serde(alias = "depend_de")]
+    pub depend_de: Uuid,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Entrée du journal d'audit, jamais modifiée ni supprimée
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EvenementAudit {
+    pub id: Uuid,
+    // None pour les opérations hors API (ligne de commande)
+    pub acteur: Option<Uuid>,
+    pub date: DateTime<Utc>,
+    pub entite: TypeEntite,
+    #[serde(alias = "entite_id")]
+    pub entite_id: Uuid,
+    pub action: ActionAudit,
+    pub changements: Vec<ChangementChamp>,
+}
+
+// Valeur d'un champ avant et après l'opération (null à la création / à la suppression)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangementChamp {
+    pub champ: String,
+    pub avant: serde_json::Value,
+    pub apres: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ActionAudit {
+    #[serde(rename = "création", alias = "Creation")]
+    Creation,
+    #[serde(rename = "modification", alias = "Modification")]
+    Modification,
+    #[serde(rename = "suppression", alias = "Suppression")]
+    Suppression,
+    #[serde(rename = "restauration", alias = "Restauration")]
+    Restauration,
+    // Suppression définitive, après la période de rétention
+    #[serde(rename = "purge", alias = "Purge")]
+    Purge,
+}
+
+// Différence champ par champ entre deux versions sérialisées d'une entité (l'id et le numéro de
+// version sont exclus)
+pub fn differences<T: Serialize>(avant: Option<&T>, apres: Option<&T>) -> Vec<ChangementChamp> {
+    let champs = |entite: Option<&T>| match entite.map(serde_json::to_value) {
+        Some(Ok(serde_json::Value::Object(champs))) => champs,
+        _ => serde_json::Map::new(),
+    };
+    let (avant, apres) = (champs(avant), champs(apres));
+
+    let mut noms: Vec<&String> = avant.keys().chain(apres.keys()).filter(|nom| *nom != "id" && *nom != "version").collect();
+    noms.sort();
+    noms.dedup();
+    noms.into_iter()
+        .filter_map(|nom| {
+            let valeur_avant = avant.get(nom).cloned().unwrap_or_default();
+            let valeur_apres = apres.get(nom).cloned().unwrap_or_default();
+            (valeur_avant != valeur_apres).then(|| ChangementChamp {
+                champ: nom.clone(),
+                avant: valeur_avant,
+                apres: valeur_apres,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum TypeEntite {
+    #[serde(rename = "projet", alias = "Projet")]
+    Projet,
+    #[serde(rename = "travail", alias = "Travail")]
+    Travail,
+    #[serde(rename = "utilisateur", alias = "Utilisateur")]
+    Utilisateur,
+    #[serde(rename = "checklist_item", alias = "ChecklistItem")]
+    ChecklistItem,
+    #[serde(rename = "période_gel", alias = "PeriodeGel")]
+    PeriodeGel,
+    #[serde(rename = "dépendance", alias = "Dependance")]
+    Dependance,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum StatutProjet {
+    #[serde(rename = "planifié", alias = "Planifie")]
     Planifie,
+    #[serde(rename = "en_cours", alias = "EnCours")]
     EnCours,
+    #[serde(rename = "terminé", alias = "Termine")]
     Termine,
+    #[serde(rename = "suspendu", alias = "Suspendu")]
     Suspendu,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum StatutTravail {
+    #[serde(rename = "planifié", alias = "Planifie")]
     Planifie,
+    #[serde(rename = "en_cours", alias = "EnCours")]
     EnCours,
+    #[serde(rename = "terminé", alias = "Termine")]
     Termine,
+    #[serde(rename = "suspendu", alias = "Suspendu")]
     Suspendu,
+    #[serde(rename = "annulé", alias = "Annule")]
     Annule,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl StatutProjet {
+    // Transitions autorisées : Planifie → EnCours → Termine, EnCours ↔ Suspendu
+    pub fn peut_passer_a(&self, cible: &StatutProjet) -> bool {
+        use StatutProjet::*;
+        matches!(
+            (self, cible),
+            (Planifie, EnCours) | (EnCours, Termine) | (EnCours, Suspendu) | (Suspendu, EnCours)
+        )
+    }
+}
+
+impl StatutTravail {
+    // Transitions autorisées : Planifie → EnCours → Termine, EnCours ↔ Suspendu,
+    // et annulation depuis tout statut sauf Termine
+    pub fn peut_passer_a(&self, cible: &StatutTravail) -> bool {
+        use StatutTravail::*;
+        matches!(
+            (self, cible),
+            (Planifie, EnCours) | (EnCours, Termine) | (EnCours, Suspendu) | (Suspendu, EnCours)
+                | (Planifie | EnCours | Suspendu, Annule)
+        )
+    }
+
+    // Un travail terminé ou annulé n'occupe plus de créneau
+    pub fn est_actif(&self) -> bool {
+        !matches!(self, StatutTravail::Termine | StatutTravail::Annule)
+    }
+}
+
+impl Environnement {
+    // Chaîne de promotion : Test → Formation → Production
+    pub fn suivant(&self) -> Option<Environnement> {
+        match self {
+            Environnement::Test => Some(Environnement::Formation),
+            Environnement::Formation => Some(Environnement::Production),
+            Environnement::Production => None,
+        }
+    }
+
+    pub fn precedent(&self) -> Option<Environnement> {
+        match self {
+            Environnement::Test => None,
+            Environnement::Formation => Some(Environnement::Test),
+            Environnement::Production => Some(Environnement::Formation),
+        }
+    }
+}
+
+impl Priorite {
+    // Ordre métier (et non alphabétique) utilisé pour trier par priorité
+    pub fn rang(&self) -> u8 {
+        match self {
+            Priorite::Faible => 0,
+            Priorite::Moyenne => 1,
+            Priorite::Haute => 2,
+            Priorite::Critique => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum StatutChecklist {
+    #[serde(rename = "non_démarré", alias = "NonDemarre")]
     NonDemarre,
+    #[serde(rename = "en_cours", alias = "EnCours")]
     EnCours,
+    #[serde(rename = "terminé", alias = "Termine")]
     Termine,
+    #[serde(rename = "bloqué", alias = "Bloque")]
     Bloque,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum TypeTravail {
+    #[serde(rename = "clonebd", alias = "CloneBd")]
     CloneBd,
+    #[serde(rename = "migration", alias = "Migration")]
     Migration,
+    #[serde(rename = "rehausement", alias = "Rehausement")]
     Rehausement,
+    #[serde(rename = "maj_application", alias = "MajApplication")]
     MajApplication,
+    #[serde(rename = "autre", alias = "Autre")]
     Autre,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum Application {
+    #[serde(rename = "espresso_gfr", alias = "EspressoGfr")]
     EspressoGfr,
+    #[serde(rename = "espresso_grm", alias = "EspressoGrm")]
     EspressoGrm,
+    #[serde(rename = "espresso_grh", alias = "EspressoGrh")]
     EspressoGrh,
+    #[serde(rename = "espresso_gpa", alias = "EspressoGpa")]
     EspressoGpa,
+    #[serde(rename = "autre", alias = "Autre")]
     Autre,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum Environnement {
+    #[serde(rename = "test", alias = "Test")]
     Test,
+    #[serde(rename = "formation", alias = "Formation")]
     Formation,
+    #[serde(rename = "production", alias = "Production")]
     Production,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum Priorite {
+    #[serde(rename = "faible", alias = "Faible")]
     Faible,
+    #[serde(rename = "moyenne", alias = "Moyenne")]
     Moyenne,
+    #[serde(rename = "élevée", alias = "Haute")]
     Haute,
+    #[serde(rename = "critique", alias = "Critique")]
     Critique,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum Role {
+    #[serde(rename = "conseiller", alias = "Conseiller")]
     Conseiller,
+    #[serde(rename = "manager", alias = "Manager")]
     Manager,
+    #[serde(rename = "specialiste", alias = "Specialiste")]
     Specialiste,
+    #[serde(rename = "admin", alias = "Admin")]
     Admin,
 }
+
+// Représentation texte des énumérations et correspondance avec le type ENUM PostgreSQL du même
+// nom (migration 0010_types_enum), dont les valeurs sont `VALEURS` dans l'ordre de déclaration
+macro_rules! enum_texte {
+    ($nom:ident = $type_pg:literal { $($variante:ident),+ $(,)? }) => {
+        impl $nom {
+            pub const TYPE_PG: &'static str = $type_pg;
+            pub const VALEURS: &'static [&'static str] = &[$(stringify!($variante)),+];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($nom::$variante => stringify!($variante),)+
+                }
+            }
+        }
+
+        impl std::str::FromStr for $nom {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($variante) => Ok($nom::$variante),)+
+                    autre => Err(format!("Valeur {} inconnue : {} ({})", stringify!($nom), autre, $nom::VALEURS.join(", "))),
+                }
+            }
+        }
+
+        impl sqlx::Type<sqlx::Postgres> for $nom {
+            fn type_info() -> sqlx::postgres::PgTypeInfo {
+                sqlx::postgres::PgTypeInfo::with_name($nom::TYPE_PG)
+            }
+        }
+
+        impl sqlx::postgres::PgHasArrayType for $nom {
+            fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+                sqlx::postgres::PgTypeInfo::with_name(concat!("_", $type_pg))
+            }
+        }
+
+        impl<'q> sqlx::Encode<'q, sqlx::Postgres> for $nom {
+            fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+                <&str as sqlx::Encode<sqlx::Postgres>>::encode(self.as_str(), buf)
+            }
+        }
+
+        impl<'r> sqlx::Decode<'r, sqlx::Postgres> for $nom {
+            fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+                Ok(<&str as sqlx::Decode<sqlx::Postgres>>::decode(value)?.parse()?)
+            }
+        }
+    };
+}
+
+enum_texte!(TypeEntite = "type_entite" { Projet, Travail, Utilisateur, ChecklistItem, PeriodeGel, Dependance });
+enum_texte!(ActionAudit = "action_audit" { Creation, Modification, Suppression, Restauration, Purge });
+enum_texte!(DecisionApprobation = "decision_approbation" { Approuve, Rejete });
+enum_texte!(StatutProjet = "statut_projet" { Planifie, EnCours, Termine, Suspendu });
+enum_texte!(StatutTravail = "statut_travail" { Planifie, EnCours, Termine, Suspendu, Annule });
+enum_texte!(StatutChecklist = "statut_checklist" { NonDemarre, EnCours, Termine, Bloque });
+enum_texte!(TypeTravail = "type_travail" { CloneBd, Migration, Rehausement, MajApplication, Autre });
+enum_texte!(Application = "application" { EspressoGfr, EspressoGrm, EspressoGrh, EspressoGpa, Autre });
+enum_texte!(Environnement = "environnement" { Test, Formation, Production });
+enum_texte!(Priorite = "priorite" { Faible, Moyenne, Haute, Critique });
+enum_texte!(Role = "role_utilisateur" { Conseiller, Manager, Specialiste, Admin });
+
+// Fixtures partagées par les tests des autres modules : valeurs neutres, à surcharger au cas
+// par cas avec la syntaxe `Travail { statut: ..., ..travail() }`
+#[cfg(test)]
+pub mod test_support {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+
+    // `heures` après la date de référence des tests (lundi 4 mars 2024, 8 h UTC)
+    pub fn date(heures: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 4, 8, 0, 0).unwrap() + Duration::hours(heures)
+    }
+
+    // Migration planifiée d'Espresso GFR en test, de 8 h à 10 h, sans équipe ni projet réel
+    pub fn travail() -> Travail {
+        Travail {
+            id: Uuid::new_v4(),
+            projet_id: Uuid::nil(),
+            type_travail: TypeTravail::Migration,
+            application: Application::EspressoGfr,
+            environnement: Environnement::Test,
+            description: String::new(),
+            date_debut: date(0),
+            date_fin_prevue: date(2),
+            statut: StatutTravail::Planifie,
+            responsable: Uuid::new_v4(),
+            equipe: Vec::new(),
+            promotion_de: None,
+            version: VERSION_INITIALE,
+            suppression: None,
+        }
+    }
+
+    // Item non démarré du travail, sous la responsabilité de son responsable
+    pub fn checklist_item(travail: &Travail) -> ChecklistItem {
+        ChecklistItem {
+            id: Uuid::new_v4(),
+            travail_id: travail.id,
+            description: "Vérifier les sauvegardes".to_string(),
+            statut: StatutChecklist::NonDemarre,
+            responsable: travail.responsable,
+            date_echeance: None,
+            commentaires: None,
+            ordre: 0,
+            suppression: None,
+        }
+    }
+
+    pub fn utilisateur(role: Role) -> Utilisateur {
+        let id = Uuid::new_v4();
+        Utilisateur {
+            id,
+            nom: format!("{:?}", role),
+            email: format!("{}@entreprise.com", id),
+            role,
+            equipe: "Infrastructure".to_string(),
+            actif: true,
+            version: VERSION_INITIALE,
+            suppression: None,
+        }
+    }
+
+    pub fn projet() -> Projet {
+        let id = Uuid::new_v4();
+        Projet {
+            id,
+            code: format!("PRJ-{}", &id.simple().to_string()[..8]),
+            nom: "Projet".to_string(),
+            description: String::new(),
+            date_debut: date(0),
+            date_fin_prevue: date(24 * 30),
+            statut: StatutProjet::EnCours,
+            priorite: Priorite::Moyenne,
+            version: VERSION_INITIALE,
+            suppression: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::str::FromStr;
+
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::handlers::checklist_handlers::ChecklistItemResponse;
+    use crate::handlers::dependance_handlers::DependanceRequest;
+    use crate::handlers::transitions::TransitionStatutResponse;
+    use crate::handlers::travail_handlers::TravailResponse;
+    use crate::handlers::utilisateur_handlers::UtilisateurResponse;
+
+    // Fixtures conformes aux types de src/types/index.ts
+    const PROJET: &str = include_str!("../tests/fixtures/contrat/projet.json");
+    const PROJET_ANCIEN: &str = include_str!("../tests/fixtures/contrat/projet_ancien.json");
+    const TRAVAIL: &str = include_str!("../tests/fixtures/contrat/travail.json");
+    const TRAVAIL_ANCIEN: &str = include_str!("../tests/fixtures/contrat/travail_ancien.json");
+    const UTILISATEUR: &str = include_str!("../tests/fixtures/contrat/utilisateur.json");
+    const UTILISATEUR_ANCIEN: &str = include_str!("../tests/fixtures/contrat/utilisateur_ancien.json");
+    const CHECKLIST_ITEM: &str = include_str!("../tests/fixtures/contrat/checklist_item.json");
+    const CHECKLIST_ITEM_MINIMAL: &str = include_str!("../tests/fixtures/contrat/checklist_item_minimal.json");
+    const CHECKLIST_ITEM_ANCIEN: &str = include_str!("../tests/fixtures/contrat/checklist_item_ancien.json");
+    const DEPENDANCE: &str = include_str!("../tests/fixtures/contrat/dependance.json");
+    const DEPENDANCE_ANCIEN: &str = include_str!("../tests/fixtures/contrat/dependance_ancien.json");
+    const ENUMS: &str = include_str!("../tests/fixtures/contrat/enums.json");
+    const TRANSITION: &str = include_str!("../tests/fixtures/contrat/transition.json");
+
+    fn json(fixture: &str) -> Value {
+        serde_json::from_str(fixture).expect("fixture JSON invalide")
+    }
+
+    // Fixture → modèle → JSON identique à la fixture
+    fn aller_retour<T: Serialize + DeserializeOwned>(fixture: &str) -> T {
+        let entite: T = serde_json::from_str(fixture).expect("désérialisation");
+        assert_eq!(serde_json::to_value(&entite).unwrap(), json(fixture));
+        entite
+    }
+
+    // Graphies précédentes acceptées en entrée, réémises au format du contrat
+    fn migration<T: Serialize + DeserializeOwned>(ancien: &str, contrat: &str) {
+        let entite: T = serde_json::from_str(ancien).expect("désérialisation");
+        assert_eq!(serde_json::to_value(&entite).unwrap(), json(contrat));
+    }
+
+    #[test]
+    fn projet_aller_retour() {
+        let projet: Projet = aller_retour(PROJET);
+        assert_eq!(projet.statut, StatutProjet::EnCours);
+        assert_eq!(projet.priorite, Priorite::Haute);
+        migration::<Projet>(PROJET_ANCIEN, PROJET);
+    }
+
+    #[test]
+    fn travail_aller_retour() {
+        let travail: Travail = aller_retour(TRAVAIL);
+        assert_eq!(travail.type_travail, TypeTravail::CloneBd);
+        assert!(travail.promotion_de.is_some());
+        migration::<Travail>(TRAVAIL_ANCIEN, TRAVAIL);
+        assert_eq!(serde_json::to_value(TravailResponse::from(travail)).unwrap(), json(TRAVAIL));
+    }
+
+    #[test]
+    fn travail_sans_promotion_omet_le_champ() {
+        let mut travail: Travail = serde_json::from_str(TRAVAIL).unwrap();
+        travail.promotion_de = None;
+        let valeur = serde_json::to_value(&travail).unwrap();
+        assert!(valeur.get("promotionDe").is_none());
+        let relu: Travail = serde_json::from_value(valeur).unwrap();
+        assert!(relu.promotion_de.is_none());
+    }
+
+    #[test]
+    fn utilisateur_aller_retour() {
+        let utilisateur: Utilisateur = aller_retour(UTILISATEUR);
+        migration::<Utilisateur>(UTILISATEUR_ANCIEN, UTILISATEUR);
+        assert_eq!(serde_json::to_value(UtilisateurResponse::from(utilisateur)).unwrap(), json(UTILISATEUR));
+    }
+
+    #[test]
+    fn checklist_item_aller_retour() {
+        let item: ChecklistItem = aller_retour(CHECKLIST_ITEM);
+        migration::<ChecklistItem>(CHECKLIST_ITEM_ANCIEN, CHECKLIST_ITEM);
+        assert_eq!(serde_json::to_value(ChecklistItemResponse::from(item)).unwrap(), json(CHECKLIST_ITEM));
+
+        // Champs optionnels absents plutôt que null, comme dans le type TS
+        let item: ChecklistItem = aller_retour(CHECKLIST_ITEM_MINIMAL);
+        assert!(item.date_echeance.is_none() && item.commentaires.is_none());
+        assert_eq!(serde_json::to_value(ChecklistItemResponse::from(item)).unwrap(), json(CHECKLIST_ITEM_MINIMAL));
+    }
+
+    // Requête en entrée seule : les deux graphies désignent le même prédécesseur
+    #[test]
+    fn dependance_requete_graphies() {
+        let requete: DependanceRequest = serde_json::from_str(DEPENDANCE).expect("désérialisation");
+        let ancienne: DependanceRequest = serde_json::from_str(DEPENDANCE_ANCIEN).expect("désérialisation");
+        assert_eq!(requete.depend_de, ancienne.depend_de);
+        assert_eq!(json(DEPENDANCE)["dependDe"], serde_json::to_value(requete.depend_de).unwrap());
+    }
+
+    fn verifier_enum<T>(nom: &str, valeurs: &Value)
+    where
+        T: FromStr<Err = String> + Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        let valeurs = valeurs[nom].as_object().unwrap_or_else(|| panic!("{} absent de enums.json", nom));
+        for (variante, contrat) in valeurs {
+            let attendu = T::from_str(variante).unwrap();
+            assert_eq!(serde_json::to_value(&attendu).unwrap(), *contrat, "{}::{}", nom, variante);
+            assert_eq!(serde_json::from_value::<T>(contrat.clone()).unwrap(), attendu);
+            assert_eq!(serde_json::from_value::<T>(Value::String(variante.clone())).unwrap(), attendu);
+        }
+    }
+
+    #[test]
+    fn enums_conformes_au_contrat() {
+        let valeurs = json(ENUMS);
+        verifier_enum::<StatutProjet>("StatutProjet", &valeurs);
+        verifier_enum::<StatutTravail>("StatutTravail", &valeurs);
+        verifier_enum::<StatutChecklist>("StatutChecklist", &valeurs);
+        verifier_enum::<TypeTravail>("TypeTravail", &valeurs);
+        verifier_enum::<Application>("Application", &valeurs);
+        verifier_enum::<Environnement>("Environnement", &valeurs);
+        verifier_enum::<Priorite>("Priorite", &valeurs);
+        verifier_enum::<Role>("Role", &valeurs);
+        verifier_enum::<TypeEntite>("TypeEntite", &valeurs);
+        verifier_enum::<ActionAudit>("ActionAudit", &valeurs);
+        verifier_enum::<DecisionApprobation>("DecisionApprobation", &valeurs);
+    }
+
+    // Valeurs de chaque type ENUM PostgreSQL après application de toutes les migrations, dans l'ordre
+    fn types_enum_migrations() -> HashMap<String, Vec<String>> {
+        let repertoire = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
+        let mut fichiers: Vec<_> = std::fs::read_dir(repertoire).unwrap()
+            .map(|f| f.unwrap().path())
+            .filter(|f| f.to_string_lossy().ends_with(".up.sql"))
+            .collect();
+        fichiers.sort();
+
+        let litteraux = |texte: &str| -> Vec<String> {
+            texte.split('\'').skip(1).step_by(2).map(str::to_string).collect()
+        };
+        let mut types: HashMap<String, Vec<String>> = HashMap::new();
+        for fichier in fichiers {
+            let sql = std::fs::read_to_string(&fichier).unwrap();
+            let sql: String = sql.lines().filter(|l| !l.trim_start().starts_with("--")).collect::<Vec<_>>().join(" ");
+            for instruction in sql.split(';').map(|i| i.split_whitespace().collect::<Vec<_>>().join(" ")) {
+                if let Some(reste) = instruction.strip_prefix("CREATE TYPE ") {
+                    if let Some((nom, valeurs)) = reste.split_once(" AS ENUM ") {
+                        types.insert(nom.to_string(), litteraux(valeurs));
+                    }
+                } else if let Some(reste) = instruction.strip_prefix("ALTER TYPE ") {
+                    if let Some((nom, ajout)) = reste.split_once(" ADD VALUE ") {
+                        let valeurs = types.get_mut(nom).unwrap_or_else(|| panic!("type {} inconnu", nom));
+                        let ajout = litteraux(ajout);
+                        let position = match ajout.get(1) {
+                            Some(repere) if instruction.contains(" BEFORE ") => valeurs.iter().position(|v| v == repere).unwrap(),
+                            Some(repere) => valeurs.iter().position(|v| v == repere).unwrap() + 1,
+                            None => valeurs.len(),
+                        };
+                        if !valeurs.contains(&ajout[0]) {
+                            valeurs.insert(position, ajout[0].clone());
+                        }
+                    }
+                } else if let Some(nom) = instruction.strip_prefix("DROP TYPE ") {
+                    types.remove(nom.trim_start_matches("IF EXISTS "));
+                }
+            }
+        }
+        types
+    }
+
+    // Échoue si une variante Rust est ajoutée sans la migration `ALTER TYPE ... ADD VALUE` correspondante
+    #[test]
+    fn types_enum_postgres_conformes() {
+        let types = types_enum_migrations();
+        let attendus = [
+            (TypeEntite::TYPE_PG, TypeEntite::VALEURS),
+            (ActionAudit::TYPE_PG, ActionAudit::VALEURS),
+            (DecisionApprobation::TYPE_PG, DecisionApprobation::VALEURS),
+            (StatutProjet::TYPE_PG, StatutProjet::VALEURS),
+            (StatutTravail::TYPE_PG, StatutTravail::VALEURS),
+            (StatutChecklist::TYPE_PG, StatutChecklist::VALEURS),
+            (TypeTravail::TYPE_PG, TypeTravail::VALEURS),
+            (Application::TYPE_PG, Application::VALEURS),
+            (Environnement::TYPE_PG, Environnement::VALEURS),
+            (Priorite::TYPE_PG, Priorite::VALEURS),
+            (Role::TYPE_PG, Role::VALEURS),
+        ];
+        for (type_pg, valeurs) in attendus {
+            let en_base = types.get(type_pg).unwrap_or_else(|| panic!("type {} absent des migrations", type_pg));
+            assert_eq!(en_base, valeurs, "valeurs du type {}", type_pg);
+        }
+        // L'ordre de déclaration sert au tri par priorité (ORDER BY priorite)
+        assert!(Priorite::VALEURS.iter().map(|v| v.parse::<Priorite>().unwrap().rang()).is_sorted());
+    }
+
+    // Toutes les paires (de, vers) : autorisées si et seulement si elles figurent dans `autorisees`
+    fn verifier_transitions<S>(autorisees: &[(S, S)], peut_passer_a: impl Fn(&S, &S) -> bool, valeurs: &[&str])
+    where
+        S: FromStr<Err = String> + PartialEq + Debug,
+    {
+        for de in valeurs.iter().map(|v| v.parse::<S>().unwrap()) {
+            for vers in valeurs.iter().map(|v| v.parse::<S>().unwrap()) {
+                let attendu = autorisees.iter().any(|(a, b)| *a == de && *b == vers);
+                assert_eq!(peut_passer_a(&de, &vers), attendu, "{:?} → {:?}", de, vers);
+            }
+        }
+    }
+
+    #[test]
+    fn transitions_travail() {
+        use StatutTravail::*;
+        let autorisees = [
+            (Planifie, EnCours),
+            (EnCours, Termine),
+            (EnCours, Suspendu),
+            (Suspendu, EnCours),
+            (Planifie, Annule),
+            (EnCours, Annule),
+            (Suspendu, Annule),
+        ];
+        verifier_transitions(&autorisees, StatutTravail::peut_passer_a, StatutTravail::VALEURS);
+        assert!(!Termine.peut_passer_a(&Planifie));
+        assert!(!Annule.peut_passer_a(&EnCours));
+        assert!(!Termine.peut_passer_a(&Annule));
+        assert!(!Planifie.peut_passer_a(&Termine));
+    }
+
+    #[test]
+    fn transition_aller_retour() {
+        let transition: TransitionStatut = aller_retour(TRANSITION);
+        assert_eq!(transition.de, StatutEntite::Travail(StatutTravail::EnCours));
+        assert_eq!(transition.vers, StatutEntite::Travail(StatutTravail::Annule));
+        assert_eq!(serde_json::to_value(TransitionStatutResponse::from(transition.clone())).unwrap()["vers"], "annulé");
+
+        // En base : nom de la variante, relu selon l'entité
+        assert_eq!(transition.vers.as_str(), "Annule");
+        assert_eq!(StatutEntite::depuis_texte(&TypeEntite::Travail, "Annule"), Ok(transition.vers));
+        assert_eq!(
+            StatutEntite::depuis_texte(&TypeEntite::Projet, "EnCours"),
+            Ok(StatutEntite::Projet(StatutProjet::EnCours))
+        );
+        assert!(StatutEntite::depuis_texte(&TypeEntite::Projet, "Annule").is_err());
+        assert!(StatutEntite::depuis_texte(&TypeEntite::Dependance, "EnCours").is_err());
+
+        // Message d'erreur avec la graphie du contrat
+        let erreur = crate::error::ApiError::TransitionInterdite { de: StatutTravail::Termine.into(), vers: StatutTravail::Planifie.into() };
+        assert_eq!(erreur.to_string(), "Transition de statut interdite : terminé → planifié");
+    }
+
+    #[test]
+    fn transitions_projet() {
+        use StatutProjet::*;
+        let autorisees = [(Planifie, EnCours), (EnCours, Termine), (EnCours, Suspendu), (Suspendu, EnCours)];
+        verifier_transitions(&autorisees, StatutProjet::peut_passer_a, StatutProjet::VALEURS);
+        assert!(!Termine.peut_passer_a(&EnCours));
+        assert!(!Suspendu.peut_passer_a(&Termine));
+        assert!(!Planifie.peut_passer_a(&Planifie));
+    }
+
+    #[test]
+    fn valeur_inconnue_refusee() {
+        assert!(serde_json::from_str::<StatutTravail>("\"en cours\"").is_err());
+        assert!(serde_json::from_str::<Priorite>("\"elevee\"").is_err());
+    }
+}
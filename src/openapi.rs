@@ -0,0 +1,214 @@
+// Spécification OpenAPI 3 dérivée des types des handlers et des modèles (annotations
+// #[utoipa::path] des handlers), servie en JSON et consultable dans une interface interactive
+
+use actix_web::{web, HttpResponse};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_scalar::{Scalar, Servable};
+
+use crate::handlers::{
+    approbation_handlers, audit_handlers, auth_handlers, calendrier_handlers, checklist_handlers, conflit_handlers,
+    corbeille_handlers, dependance_handlers, gel_handlers, modele_checklist_handlers, projet_handlers, promotion_handlers,
+    travail_handlers, utilisateur_handlers,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "API Travaux", description = "Planification des travaux (clones, migrations, rehaussements) des applications Espresso"),
+    paths(
+        crate::health_check,
+        approbation_handlers::get_approbations,
+        approbation_handlers::demander_approbation,
+        approbation_handlers::decider_approbation,
+        audit_handlers::get_audit,
+        auth_handlers::login,
+        auth_handlers::get_moi,
+        auth_handlers::update_mot_de_passe,
+        auth_handlers::emettre_jeton_calendrier,
+        auth_handlers::revoquer_jeton_calendrier,
+        calendrier_handlers::get_calendrier,
+        checklist_handlers::get_checklist,
+        checklist_handlers::create_checklist_item,
+        checklist_handlers::update_checklist_item,
+        checklist_handlers::update_statut_checklist_item,
+        checklist_handlers::delete_checklist_item,
+        checklist_handlers::reorder_checklist,
+        checklist_handlers::get_historique_checklist_item,
+        conflit_handlers::get_conflits,
+        corbeille_handlers::purger,
+        dependance_handlers::get_dependances,
+        dependance_handlers::create_dependance,
+        dependance_handlers::delete_dependance,
+        dependance_handlers::get_chemin_critique,
+        gel_handlers::get_gels,
+        gel_handlers::get_gel,
+        gel_handlers::create_gel,
+        gel_handlers::update_gel,
+        gel_handlers::delete_gel,
+        gel_handlers::get_travaux_gel,
+        gel_handlers::get_historique_gel,
+        modele_checklist_handlers::get_modeles,
+        modele_checklist_handlers::get_modele,
+        modele_checklist_handlers::create_modele,
+        modele_checklist_handlers::update_modele,
+        modele_checklist_handlers::delete_modele,
+        projet_handlers::get_projets,
+        projet_handlers::get_projet,
+        projet_handlers::get_sante_projet,
+        projet_handlers::create_projet,
+        projet_handlers::update_projet,
+        projet_handlers::patch_projet,
+        projet_handlers::delete_projet,
+        projet_handlers::restaurer_projet,
+        projet_handlers::get_historique_projet,
+        projet_handlers::get_transitions_projet,
+        projet_handlers::transition_projet,
+        promotion_handlers::get_chaine_promotion,
+        promotion_handlers::promouvoir_travail,
+        travail_handlers::get_travaux,
+        travail_handlers::get_travail,
+        travail_handlers::get_travaux_by_projet,
+        travail_handlers::create_travail,
+        travail_handlers::update_travail,
+        travail_handlers::patch_travail,
+        travail_handlers::delete_travail,
+        travail_handlers::restaurer_travail,
+        travail_handlers::get_historique_travail,
+        travail_handlers::get_transitions_travail,
+        travail_handlers::transition_travail,
+        utilisateur_handlers::get_utilisateurs,
+        utilisateur_handlers::get_utilisateur,
+        utilisateur_handlers::create_utilisateur,
+        utilisateur_handlers::update_utilisateur,
+        utilisateur_handlers::patch_utilisateur,
+        utilisateur_handlers::delete_utilisateur,
+        utilisateur_handlers::restaurer_utilisateur,
+        utilisateur_handlers::get_historique_utilisateur,
+    ),
+    modifiers(&AuthentificationBearer),
+    security(("bearer" = [])),
+    tags(
+        (name = "authentification", description = "Connexion et compte de l'utilisateur courant"),
+        (name = "projets"),
+        (name = "travaux"),
+        (name = "checklist", description = "Items de checklist d'un travail"),
+        (name = "modeles-checklist", description = "Modèles instanciés à la création d'un travail"),
+        (name = "approbations", description = "Validation managériale des travaux de production"),
+        (name = "promotions", description = "Chaîne Test → Formation → Production"),
+        (name = "dependances", description = "Dépendances fin → début et chemin critique"),
+        (name = "gels", description = "Périodes de gel"),
+        (name = "conflits", description = "Conflits de planning"),
+        (name = "calendrier"),
+        (name = "utilisateurs"),
+        (name = "audit", description = "Journal d'audit"),
+        (name = "corbeille", description = "Purge des éléments supprimés après la période de rétention"),
+        (name = "supervision"),
+    )
+)]
+pub struct ApiDoc;
+
+// Jeton obtenu via POST /api/auth/login, à passer dans l'en-tête Authorization: Bearer <jeton>
+struct AuthentificationBearer;
+
+impl Modify for AuthentificationBearer {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme("bearer", SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()));
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/openapi.json", web::get().to(get_openapi))
+        .service(Scalar::with_url("/api/docs", ApiDoc::openapi()));
+}
+
+pub async fn get_openapi() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    // Routes de ce module, qui ne se documentent pas elles-mêmes
+    const NON_DOCUMENTEES: &[(&str, &str)] = &[("get", "/api/openapi.json")];
+
+    // Couples (méthode, chemin) déclarés dans les tables de routes : `web::scope("...")` puis
+    // `.route("...", web::<méthode>()...)`, les chemins commençant par `/` hors scope étant absolus
+    fn routes_declarees() -> BTreeSet<(String, String)> {
+        let racine = env!("CARGO_MANIFEST_DIR");
+        let mut fichiers = vec![format!("{racine}/src/lib.rs")];
+        for fichier in std::fs::read_dir(format!("{racine}/src/handlers")).unwrap() {
+            fichiers.push(fichier.unwrap().path().to_string_lossy().into_owned());
+        }
+
+        // Texte entre les deux premiers guillemets
+        let chaine = |texte: &str| texte.split('"').nth(1).unwrap_or_default().to_string();
+        let mut routes = BTreeSet::new();
+        for fichier in fichiers {
+            let source = std::fs::read_to_string(&fichier).unwrap();
+            let mut reste = source.as_str();
+            let mut scope = String::new();
+            loop {
+                let position_scope = reste.find("web::scope(");
+                let position_route = reste.find(".route(");
+                match (position_scope, position_route) {
+                    (Some(s), r) if r.is_none_or(|r| s < r) => {
+                        scope = chaine(&reste[s..]);
+                        reste = &reste[s + 1..];
+                    }
+                    (_, Some(r)) => {
+                        let route = &reste[r..];
+                        let chemin = chaine(route);
+                        let methode = route.split("web::").nth(1).and_then(|m| m.split('(').next()).unwrap().to_string();
+                        let absolu = chemin.starts_with("/api/") || chemin == "/health";
+                        routes.insert((methode, if absolu { chemin } else { format!("{scope}{chemin}") }));
+                        reste = &reste[r + 1..];
+                    }
+                    _ => break,
+                }
+            }
+        }
+        routes
+    }
+
+    fn routes_documentees() -> BTreeSet<(String, String)> {
+        let mut routes = BTreeSet::new();
+        for (chemin, item) in ApiDoc::openapi().paths.paths {
+            let operations = [("get", &item.get), ("post", &item.post), ("put", &item.put), ("patch", &item.patch), ("delete", &item.delete)];
+            for (methode, operation) in operations {
+                if operation.is_some() {
+                    routes.insert((methode.to_string(), chemin.clone()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn toutes_les_routes_sont_documentees() {
+        let mut declarees = routes_declarees();
+        for (methode, chemin) in NON_DOCUMENTEES {
+            declarees.remove(&(methode.to_string(), chemin.to_string()));
+        }
+        assert!(declarees.len() > 50, "extraction des routes suspecte : {:?}", declarees);
+
+        let documentees = routes_documentees();
+        let manquantes: Vec<_> = declarees.difference(&documentees).collect();
+        assert!(manquantes.is_empty(), "routes sans #[utoipa::path] : {:?}", manquantes);
+        let fantomes: Vec<_> = documentees.difference(&declarees).collect();
+        assert!(fantomes.is_empty(), "routes documentées mais absentes des tables : {:?}", fantomes);
+    }
+
+    #[test]
+    fn enums_documentes_avec_les_valeurs_du_contrat() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = &spec["components"]["schemas"];
+        assert_eq!(schemas["StatutTravail"]["enum"], serde_json::json!(["planifié", "en_cours", "terminé", "suspendu", "annulé"]));
+        assert_eq!(schemas["Priorite"]["enum"], serde_json::json!(["faible", "moyenne", "élevée", "critique"]));
+        assert!(schemas["CreateProjetRequest"]["properties"]["dateFinPrevue"].is_object());
+        assert!(schemas["TravailResponse"]["properties"]["type"].is_object());
+    }
+}
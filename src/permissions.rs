@@ -0,0 +1,168 @@
+// Matrice des permissions par rôle ; la lecture est ouverte à tout utilisateur authentifié
+
+use crate::auth::UtilisateurCourant;
+use crate::error::ApiError;
+use crate::models::{Role, Travail};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    // Créer, modifier, supprimer des utilisateurs
+    GererUtilisateurs,
+    // Créer, modifier, supprimer des projets et changer leur statut
+    GererProjets,
+    // Créer, supprimer et modifier n'importe quel travail (et sa checklist)
+    GererTravaux,
+    // Modifier les travaux (et leur checklist) dont on est responsable ou membre de l'équipe
+    ModifierTravauxAssignes,
+    GererModelesChecklist,
+    // Approuver ou rejeter la mise en production d'un travail
+    ApprouverProduction,
+    // Créer, modifier, supprimer des périodes de gel
+    GererGels,
+    // Autoriser un travail pendant une période de gel
+    DerogerGel,
+    // Lister les projets, travaux et utilisateurs supprimés, et les purger définitivement
+    GererCorbeille,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::GererUtilisateurs => "gerer_utilisateurs",
+            Permission::GererProjets => "gerer_projets",
+            Permission::GererTravaux => "gerer_travaux",
+            Permission::ModifierTravauxAssignes => "modifier_travaux_assignes",
+            Permission::GererModelesChecklist => "gerer_modeles_checklist",
+            Permission::ApprouverProduction => "approuver_production",
+            Permission::GererGels => "gerer_gels",
+            Permission::DerogerGel => "deroger_gel",
+            Permission::GererCorbeille => "gerer_corbeille",
+        }
+    }
+}
+
+impl Role {
+    pub fn a_permission(&self, permission: Permission) -> bool {
+        use Permission::*;
+        match self {
+            Role::Admin => true,
+            Role::Manager => matches!(
+                permission,
+                GererProjets | GererTravaux | ModifierTravauxAssignes | GererModelesChecklist | ApprouverProduction
+            ),
+            Role::Specialiste => matches!(permission, ModifierTravauxAssignes),
+            Role::Conseiller => false,
+        }
+    }
+}
+
+impl UtilisateurCourant {
+    pub fn exiger(&self, permission: Permission) -> Result<(), ApiError> {
+        if self.0.role.a_permission(permission) {
+            Ok(())
+        } else {
+            Err(ApiError::PermissionManquante(permission))
+        }
+    }
+
+    // GererTravaux, ou ModifierTravauxAssignes si l'utilisateur est affecté au travail
+    pub fn exiger_modification(&self, travail: &Travail) -> Result<(), ApiError> {
+        if self.0.role.a_permission(Permission::GererTravaux) {
+            return Ok(());
+        }
+        self.exiger(Permission::ModifierTravauxAssignes)?;
+        if travail.responsable == self.0.id || travail.equipe.contains(&self.0.id) {
+            Ok(())
+        } else {
+            Err(ApiError::PermissionManquante(Permission::GererTravaux))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::test_support;
+
+    const TOUTES: [Permission; 9] = [
+        Permission::GererUtilisateurs,
+        Permission::GererProjets,
+        Permission::GererTravaux,
+        Permission::ModifierTravauxAssignes,
+        Permission::GererModelesChecklist,
+        Permission::ApprouverProduction,
+        Permission::GererGels,
+        Permission::DerogerGel,
+        Permission::GererCorbeille,
+    ];
+
+    fn courant(role: Role) -> UtilisateurCourant {
+        UtilisateurCourant(test_support::utilisateur(role))
+    }
+
+    fn travail(responsable: Uuid, equipe: Vec<Uuid>) -> Travail {
+        Travail { responsable, equipe, ..test_support::travail() }
+    }
+
+    // Permissions accordées au rôle, dans l'ordre de TOUTES
+    fn accordees(role: Role) -> Vec<Permission> {
+        TOUTES.into_iter().filter(|p| role.a_permission(*p)).collect()
+    }
+
+    fn refus(resultat: Result<(), ApiError>) -> Option<Permission> {
+        match resultat {
+            Err(ApiError::PermissionManquante(permission)) => Some(permission),
+            Err(autre) => panic!("erreur inattendue : {:?}", autre),
+            Ok(()) => None,
+        }
+    }
+
+    #[test]
+    fn admin() {
+        assert_eq!(accordees(Role::Admin), TOUTES);
+        let admin = courant(Role::Admin);
+        assert!(admin.exiger_modification(&travail(Uuid::new_v4(), Vec::new())).is_ok());
+    }
+
+    #[test]
+    fn manager() {
+        use Permission::*;
+        assert_eq!(
+            accordees(Role::Manager),
+            [GererProjets, GererTravaux, ModifierTravauxAssignes, GererModelesChecklist, ApprouverProduction]
+        );
+        let manager = courant(Role::Manager);
+        assert_eq!(refus(manager.exiger(GererUtilisateurs)), Some(GererUtilisateurs));
+        assert!(manager.exiger_modification(&travail(Uuid::new_v4(), Vec::new())).is_ok());
+    }
+
+    #[test]
+    fn specialiste() {
+        assert_eq!(accordees(Role::Specialiste), [Permission::ModifierTravauxAssignes]);
+        let specialiste = courant(Role::Specialiste);
+        let id = specialiste.0.id;
+        assert!(specialiste.exiger_modification(&travail(id, Vec::new())).is_ok());
+        assert!(specialiste.exiger_modification(&travail(Uuid::new_v4(), vec![Uuid::new_v4(), id])).is_ok());
+        assert_eq!(
+            refus(specialiste.exiger_modification(&travail(Uuid::new_v4(), vec![Uuid::new_v4()]))),
+            Some(Permission::GererTravaux)
+        );
+    }
+
+    #[test]
+    fn conseiller() {
+        assert!(accordees(Role::Conseiller).is_empty());
+        let conseiller = courant(Role::Conseiller);
+        for permission in TOUTES {
+            assert_eq!(refus(conseiller.exiger(permission)), Some(permission));
+        }
+        // Même affecté au travail, le conseiller ne peut pas le modifier
+        let id = conseiller.0.id;
+        assert_eq!(
+            refus(conseiller.exiger_modification(&travail(id, vec![id]))),
+            Some(Permission::ModifierTravauxAssignes)
+        );
+    }
+}
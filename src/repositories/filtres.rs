@@ -0,0 +1,282 @@
+// Critères de recherche, tri et pagination des listes, communs aux deux stockages
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{ActionAudit, Application, Environnement, EvenementAudit, TypeEntite, Priorite, Projet, Role, StatutProjet, StatutTravail, Suppression, Travail, TypeTravail, Utilisateur};
+
+// Prise en compte des éléments supprimés logiquement
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Supprimes {
+    #[default]
+    Exclus,
+    Inclus,
+    // Uniquement ceux supprimés avant cette date : candidats à la purge
+    Avant(DateTime<Utc>),
+}
+
+impl Supprimes {
+    pub fn accepte(&self, suppression: Option<&Suppression>) -> bool {
+        match self {
+            Supprimes::Exclus => suppression.is_none(),
+            Supprimes::Inclus => true,
+            Supprimes::Avant(date) => suppression.is_some_and(|s| s.le < *date),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FiltreProjets {
+    pub statut: Option<StatutProjet>,
+    pub priorite: Option<Priorite>,
+    pub supprimes: Supprimes,
+}
+
+#[derive(Debug, Default)]
+pub struct FiltreTravaux {
+    pub projet_id: Option<Uuid>,
+    pub statut: Option<StatutTravail>,
+    pub application: Option<Application>,
+    pub environnement: Option<Environnement>,
+    pub type_travail: Option<TypeTravail>,
+    pub responsable: Option<Uuid>,
+    pub promotion_de: Option<Uuid>,
+    // Travaux dont la période chevauche [du, au]
+    pub du: Option<DateTime<Utc>>,
+    pub au: Option<DateTime<Utc>>,
+    pub supprimes: Supprimes,
+}
+
+#[derive(Debug, Default)]
+pub struct FiltreUtilisateurs {
+    pub role: Option<Role>,
+    pub equipe: Option<String>,
+    pub actif: Option<bool>,
+    pub supprimes: Supprimes,
+}
+
+#[derive(Debug, Default)]
+pub struct FiltreAudit {
+    pub entite: Option<TypeEntite>,
+    pub entite_id: Option<Uuid>,
+    pub acteur: Option<Uuid>,
+    pub action: Option<ActionAudit>,
+    // Événements survenus dans [du, au]
+    pub du: Option<DateTime<Utc>>,
+    pub au: Option<DateTime<Utc>>,
+}
+
+// `champ` est toujours issu d'une liste blanche : il peut être injecté tel quel dans l'ORDER BY
+#[derive(Debug, Clone, Copy)]
+pub struct Tri {
+    pub champ: &'static str,
+    pub descendant: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub tri: Tri,
+    pub offset: i64,
+    // None : tous les éléments à partir de `offset`
+    pub limit: Option<i64>,
+}
+
+impl Pagination {
+    // Tous les éléments, pour les traitements internes qui ne paginent pas
+    pub fn tout(champ: &'static str) -> Self {
+        Pagination {
+            tri: Tri { champ, descendant: false },
+            offset: 0,
+            limit: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Page<T> {
+    pub elements: Vec<T>,
+    // Nombre total d'éléments correspondant au filtre, toutes pages confondues
+    pub total: i64,
+}
+
+pub const TRI_PROJETS: &[&str] = &["code", "nom", "date_debut", "date_fin_prevue", "statut", "priorite"];
+pub const TRI_TRAVAUX: &[&str] = &[
+    "date_debut", "date_fin_prevue", "statut", "type_travail", "application", "environnement", "description",
+];
+pub const TRI_UTILISATEURS: &[&str] = &["nom", "email", "role", "equipe"];
+pub const TRI_AUDIT: &[&str] = &["date"];
+
+impl FiltreProjets {
+    pub fn accepte(&self, projet: &Projet) -> bool {
+        self.statut.as_ref().is_none_or(|s| *s == projet.statut)
+            && self.priorite.as_ref().is_none_or(|p| *p == projet.priorite)
+            && self.supprimes.accepte(projet.suppression.as_ref())
+    }
+}
+
+impl FiltreTravaux {
+    pub fn accepte(&self, travail: &Travail) -> bool {
+        self.projet_id.is_none_or(|id| id == travail.projet_id)
+            && self.statut.as_ref().is_none_or(|s| *s == travail.statut)
+            && self.application.as_ref().is_none_or(|a| *a == travail.application)
+            && self.environnement.as_ref().is_none_or(|e| *e == travail.environnement)
+            && self.type_travail.as_ref().is_none_or(|t| *t == travail.type_travail)
+            && self.responsable.is_none_or(|r| r == travail.responsable)
+            && self.promotion_de.is_none_or(|id| travail.promotion_de == Some(id))
+            && self.du.is_none_or(|du| travail.date_fin_prevue >= du)
+            && self.au.is_none_or(|au| travail.date_debut <= au)
+            && self.supprimes.accepte(travail.suppression.as_ref())
+    }
+}
+
+impl FiltreUtilisateurs {
+    pub fn accepte(&self, utilisateur: &Utilisateur) -> bool {
+        self.role.as_ref().is_none_or(|r| *r == utilisateur.role)
+            && self.equipe.as_ref().is_none_or(|e| *e == utilisateur.equipe)
+            && self.actif.is_none_or(|a| a == utilisateur.actif)
+            && self.supprimes.accepte(utilisateur.suppression.as_ref())
+    }
+}
+
+impl FiltreAudit {
+    pub fn accepte(&self, evenement: &EvenementAudit) -> bool {
+        self.entite.as_ref().is_none_or(|e| *e == evenement.entite)
+            && self.entite_id.is_none_or(|id| id == evenement.entite_id)
+            && self.acteur.is_none_or(|a| Some(a) == evenement.acteur)
+            && self.action.as_ref().is_none_or(|a| *a == evenement.action)
+            && self.du.is_none_or(|du| evenement.date >= du)
+            && self.au.is_none_or(|au| evenement.date <= au)
+    }
+}
+
+// Valeur de tri d'un élément en mémoire ; l'ordre doit reproduire celui de l'ORDER BY SQL
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CleTri {
+    Texte(String),
+    Date(DateTime<Utc>),
+    Rang(u8),
+}
+
+pub fn cle_projet(projet: &Projet, champ: &str) -> CleTri {
+    match champ {
+        "nom" => CleTri::Texte(projet.nom.clone()),
+        "date_debut" => CleTri::Date(projet.date_debut),
+        "date_fin_prevue" => CleTri::Date(projet.date_fin_prevue),
+        "statut" => CleTri::Texte(projet.statut.as_str().to_string()),
+        "priorite" => CleTri::Rang(projet.priorite.rang()),
+        _ => CleTri::Texte(projet.code.clone()),
+    }
+}
+
+pub fn cle_travail(travail: &Travail, champ: &str) -> CleTri {
+    match champ {
+        "date_fin_prevue" => CleTri::Date(travail.date_fin_prevue),
+        "statut" => CleTri::Texte(travail.statut.as_str().to_string()),
+        "type_travail" => CleTri::Texte(travail.type_travail.as_str().to_string()),
+        "application" => CleTri::Texte(travail.application.as_str().to_string()),
+        "environnement" => CleTri::Texte(travail.environnement.as_str().to_string()),
+        "description" => CleTri::Texte(travail.description.clone()),
+        _ => CleTri::Date(travail.date_debut),
+    }
+}
+
+pub fn cle_utilisateur(utilisateur: &Utilisateur, champ: &str) -> CleTri {
+    match champ {
+        "email" => CleTri::Texte(utilisateur.email.clone()),
+        "role" => CleTri::Texte(utilisateur.role.as_str().to_string()),
+        "equipe" => CleTri::Texte(utilisateur.equipe.clone()),
+        _ => CleTri::Texte(utilisateur.nom.clone()),
+    }
+}
+
+pub fn cle_audit(evenement: &EvenementAudit, _champ: &str) -> CleTri {
+    CleTri::Date(evenement.date)
+}
+
+// Expression ORDER BY correspondant à `cle_*` ; l'id départage les égalités pour un ordre stable
+pub fn order_by(tri: &Tri) -> String {
+    // Un ENUM PostgreSQL se trie dans l'ordre de déclaration de ses valeurs, soit l'ordre métier pour
+    // la priorité ; les autres énumérations sont triées sur leur texte, comme en mémoire
+    let expression = match tri.champ {
+        "statut" | "type_travail" | "application" | "environnement" | "role" => format!("{}::text", tri.champ),
+        champ => champ.to_string(),
+    };
+    let sens = if tri.descendant { "DESC" } else { "ASC" };
+    format!(" ORDER BY {expression} {sens}, id {sens}")
+}
+
+// Tri, puis découpage de la page, pour le stockage en mémoire
+pub fn paginer<T>(mut elements: Vec<T>, pagination: &Pagination, cle: impl Fn(&T, &str) -> CleTri, id: impl Fn(&T) -> Uuid) -> Page<T> {
+    elements.sort_by_cached_key(|e| (cle(e, pagination.tri.champ), id(e)));
+    if pagination.tri.descendant {
+        elements.reverse();
+    }
+    let total = elements.len() as i64;
+    let elements = elements
+        .into_iter()
+        .skip(pagination.offset.max(0) as usize)
+        .take(pagination.limit.map_or(usize::MAX, |l| l.max(0) as usize))
+        .collect();
+    Page { elements, total }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support;
+
+    fn pagination(champ: &'static str, descendant: bool, offset: i64, limit: Option<i64>) -> Pagination {
+        Pagination { tri: Tri { champ, descendant }, offset, limit }
+    }
+
+    #[test]
+    fn tri_stable_puis_decoupage() {
+        let projets: Vec<Projet> = [(Priorite::Critique, "C"), (Priorite::Faible, "A"), (Priorite::Haute, "B"), (Priorite::Faible, "D")]
+            .into_iter()
+            .map(|(priorite, code)| Projet { priorite, code: code.to_string(), ..test_support::projet() })
+            .collect();
+        let codes = |page: Page<Projet>| (page.elements.into_iter().map(|p| p.code).collect::<Vec<_>>(), page.total);
+
+        // Ordre métier des priorités ; les ex aequo sont départagés par l'id, dans les deux sens
+        let croissant = codes(paginer(projets.clone(), &pagination("priorite", false, 0, None), cle_projet, |p| p.id));
+        let decroissant = codes(paginer(projets.clone(), &pagination("priorite", true, 0, None), cle_projet, |p| p.id));
+        assert_eq!(&croissant.0[2..], ["B", "C"]);
+        assert_eq!(croissant.0.iter().rev().collect::<Vec<_>>(), decroissant.0.iter().collect::<Vec<_>>());
+
+        assert_eq!(codes(paginer(projets.clone(), &pagination("code", false, 1, Some(2)), cle_projet, |p| p.id)), (vec!["B".into(), "C".into()], 4));
+        assert_eq!(codes(paginer(projets.clone(), &pagination("code", true, 3, Some(10)), cle_projet, |p| p.id)), (vec!["A".into()], 4));
+        assert_eq!(codes(paginer(projets, &pagination("code", false, 10, None), cle_projet, |p| p.id)), (Vec::new(), 4));
+    }
+
+    #[test]
+    fn filtre_des_travaux() {
+        let travail = Travail { date_debut: test_support::date(10), date_fin_prevue: test_support::date(20), ..test_support::travail() };
+        let periode = |du: i64, au: i64| FiltreTravaux { du: Some(test_support::date(du)), au: Some(test_support::date(au)), ..Default::default() };
+
+        // Chevauchement de périodes, bornes incluses
+        assert!(periode(0, 10).accepte(&travail));
+        assert!(periode(20, 30).accepte(&travail));
+        assert!(periode(12, 14).accepte(&travail));
+        assert!(!periode(0, 9).accepte(&travail));
+        assert!(!periode(21, 30).accepte(&travail));
+
+        assert!(FiltreTravaux { responsable: Some(travail.responsable), statut: Some(travail.statut.clone()), ..Default::default() }.accepte(&travail));
+        assert!(!FiltreTravaux { responsable: Some(Uuid::new_v4()), ..Default::default() }.accepte(&travail));
+        assert!(!FiltreTravaux { environnement: Some(Environnement::Production), ..Default::default() }.accepte(&Travail { environnement: Environnement::Test, ..travail.clone() }));
+
+        let supprime = Travail { suppression: Some(Suppression { le: test_support::date(5), par: Some(Uuid::new_v4()) }), ..travail.clone() };
+        assert!(!FiltreTravaux::default().accepte(&supprime));
+        assert!(FiltreTravaux { supprimes: Supprimes::Inclus, ..Default::default() }.accepte(&supprime));
+        assert!(FiltreTravaux { supprimes: Supprimes::Avant(test_support::date(6)), ..Default::default() }.accepte(&supprime));
+        assert!(!FiltreTravaux { supprimes: Supprimes::Avant(test_support::date(5)), ..Default::default() }.accepte(&supprime));
+        assert!(!FiltreTravaux { supprimes: Supprimes::Avant(test_support::date(6)), ..Default::default() }.accepte(&travail));
+    }
+
+    #[test]
+    fn order_by_sql() {
+        assert_eq!(order_by(&Tri { champ: "date_debut", descendant: false }), " ORDER BY date_debut ASC, id ASC");
+        assert_eq!(order_by(&Tri { champ: "statut", descendant: true }), " ORDER BY statut::text DESC, id DESC");
+        // ENUM PostgreSQL déclaré dans l'ordre métier : pas de conversion en texte
+        assert_eq!(order_by(&Tri { champ: "priorite", descendant: false }), " ORDER BY priorite ASC, id ASC");
+    }
+}
@@ -0,0 +1,580 @@
+// Implémentation en mémoire (mode démo et tests)
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::models::{Approbation, ChecklistItem, DemandeApprobation, Dependance, DerogationGel, EvenementAudit, ModeleChecklist, PeriodeGel, Projet, Travail, TransitionStatut, TypeEntite, TypeTravail, Utilisateur};
+use super::filtres::{cle_audit, cle_projet, cle_travail, cle_utilisateur, paginer};
+use super::{FiltreAudit, FiltreProjets, FiltreTravaux, FiltreUtilisateurs, Page, Pagination};
+use super::{ApprobationRepository, AuditRepository, ChecklistRepository, DependanceRepository, GelRepository, ModeleChecklistRepository, ProjetRepository, TransitionRepository, TravailRepository, UtilisateurRepository};
+
+// Un mutex empoisonné (panique d'un autre worker pendant l'écriture) devient une erreur de stockage
+fn verrou<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>> {
+    mutex.lock().map_err(|_| anyhow!("Stockage mémoire inutilisable : verrou empoisonné"))
+}
+
+#[derive(Default)]
+pub struct MemoireProjetRepository {
+    projets: Mutex<HashMap<Uuid, Projet>>,
+}
+
+#[async_trait]
+impl ProjetRepository for MemoireProjetRepository {
+    async fn list(&self, filtre: &FiltreProjets, pagination: &Pagination) -> Result<Page<Projet>> {
+        let projets = verrou(&self.projets)?.values().filter(|p| filtre.accepte(p)).cloned().collect();
+        Ok(paginer(projets, pagination, cle_projet, |p| p.id))
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<Projet>> {
+        Ok(verrou(&self.projets)?.get(&id).filter(|p| p.suppression.is_none()).cloned())
+    }
+
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Projet>> {
+        Ok(verrou(&self.projets)?.get(&id).filter(|p| p.suppression.is_some()).cloned())
+    }
+
+    async fn find_by_code(&self, code: &str) -> Result<Option<Projet>> {
+        Ok(verrou(&self.projets)?.values().find(|p| p.code == code && p.suppression.is_none()).cloned())
+    }
+
+    async fn insert(&self, projet: &Projet) -> Result<()> {
+        verrou(&self.projets)?.insert(projet.id, projet.clone());
+        Ok(())
+    }
+
+    async fn update(&self, projet: &Projet) -> Result<bool> {
+        match verrou(&self.projets)?.get_mut(&projet.id) {
+            Some(existing) if existing.version == projet.version => {
+                *existing = projet.clone();
+                existing.version += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        Ok(verrou(&self.projets)?.remove(&id).is_some())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireTravailRepository {
+    travaux: Mutex<HashMap<Uuid, Travail>>,
+}
+
+#[async_trait]
+impl TravailRepository for MemoireTravailRepository {
+    async fn list(&self, filtre: &FiltreTravaux, pagination: &Pagination) -> Result<Page<Travail>> {
+        let travaux = verrou(&self.travaux)?.values().filter(|t| filtre.accepte(t)).cloned().collect();
+        Ok(paginer(travaux, pagination, cle_travail, |t| t.id))
+    }
+
+    async fn list_by_projet(&self, projet_id: Uuid) -> Result<Vec<Travail>> {
+        Ok(verrou(&self.travaux)?.values()
+            .filter(|t| t.projet_id == projet_id && t.suppression.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_projets(&self, projet_ids: &[Uuid]) -> Result<Vec<Travail>> {
+        Ok(verrou(&self.travaux)?.values()
+            .filter(|t| projet_ids.contains(&t.projet_id) && t.suppression.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_utilisateur(&self, utilisateur_id: Uuid) -> Result<Vec<Travail>> {
+        Ok(verrou(&self.travaux)?.values()
+            .filter(|t| t.suppression.is_none() && (t.responsable == utilisateur_id || t.equipe.contains(&utilisateur_id)))
+            .cloned()
+            .collect())
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<Travail>> {
+        Ok(verrou(&self.travaux)?.get(&id).filter(|t| t.suppression.is_none()).cloned())
+    }
+
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Travail>> {
+        Ok(verrou(&self.travaux)?.get(&id).filter(|t| t.suppression.is_some()).cloned())
+    }
+
+    async fn insert(&self, travail: &Travail) -> Result<()> {
+        verrou(&self.travaux)?.insert(travail.id, travail.clone());
+        Ok(())
+    }
+
+    async fn update(&self, travail: &Travail) -> Result<bool> {
+        match verrou(&self.travaux)?.get_mut(&travail.id) {
+            Some(existing) if existing.version == travail.version => {
+                *existing = travail.clone();
+                existing.version += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        Ok(verrou(&self.travaux)?.remove(&id).is_some())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireUtilisateurRepository {
+    utilisateurs: Mutex<HashMap<Uuid, Utilisateur>>,
+    mots_de_passe: Mutex<HashMap<Uuid, String>>,
+    jetons_calendrier: Mutex<HashMap<Uuid, String>>,
+}
+
+#[async_trait]
+impl UtilisateurRepository for MemoireUtilisateurRepository {
+    async fn list(&self, filtre: &FiltreUtilisateurs, pagination: &Pagination) -> Result<Page<Utilisateur>> {
+        let utilisateurs = verrou(&self.utilisateurs)?.values().filter(|u| filtre.accepte(u)).cloned().collect();
+        Ok(paginer(utilisateurs, pagination, cle_utilisateur, |u| u.id))
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<Utilisateur>> {
+        Ok(verrou(&self.utilisateurs)?.get(&id).filter(|u| u.suppression.is_none()).cloned())
+    }
+
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Utilisateur>> {
+        Ok(verrou(&self.utilisateurs)?.get(&id).filter(|u| u.suppression.is_some()).cloned())
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<Utilisateur>> {
+        Ok(verrou(&self.utilisateurs)?.values().find(|u| u.email == email && u.suppression.is_none()).cloned())
+    }
+
+    async fn insert(&self, utilisateur: &Utilisateur) -> Result<()> {
+        verrou(&self.utilisateurs)?.insert(utilisateur.id, utilisateur.clone());
+        Ok(())
+    }
+
+    async fn update(&self, utilisateur: &Utilisateur) -> Result<bool> {
+        match verrou(&self.utilisateurs)?.get_mut(&utilisateur.id) {
+            Some(existing) if existing.version == utilisateur.version => {
+                *existing = utilisateur.clone();
+                existing.version += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        verrou(&self.mots_de_passe)?.remove(&id);
+        verrou(&self.jetons_calendrier)?.remove(&id);
+        Ok(verrou(&self.utilisateurs)?.remove(&id).is_some())
+    }
+
+    async fn mot_de_passe(&self, id: Uuid) -> Result<Option<String>> {
+        Ok(verrou(&self.mots_de_passe)?.get(&id).cloned())
+    }
+
+    async fn set_mot_de_passe(&self, id: Uuid, hash: &str) -> Result<bool> {
+        if !verrou(&self.utilisateurs)?.contains_key(&id) {
+            return Ok(false);
+        }
+        verrou(&self.mots_de_passe)?.insert(id, hash.to_string());
+        Ok(true)
+    }
+
+    async fn set_jeton_calendrier(&self, id: Uuid, empreinte: Option<&str>) -> Result<bool> {
+        if !verrou(&self.utilisateurs)?.contains_key(&id) {
+            return Ok(false);
+        }
+        match empreinte {
+            Some(empreinte) => verrou(&self.jetons_calendrier)?.insert(id, empreinte.to_string()),
+            None => verrou(&self.jetons_calendrier)?.remove(&id),
+        };
+        Ok(true)
+    }
+
+    async fn find_by_jeton_calendrier(&self, empreinte: &str) -> Result<Option<Utilisateur>> {
+        let Some(id) = verrou(&self.jetons_calendrier)?.iter().find(|(_, e)| *e == empreinte).map(|(id, _)| *id) else {
+            return Ok(None);
+        };
+        self.find(id).await
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireChecklistRepository {
+    items: Mutex<HashMap<Uuid, ChecklistItem>>,
+}
+
+#[async_trait]
+impl ChecklistRepository for MemoireChecklistRepository {
+    async fn list_by_travail(&self, travail_id: Uuid) -> Result<Vec<ChecklistItem>> {
+        let mut items: Vec<ChecklistItem> = verrou(&self.items)?.values()
+            .filter(|i| i.travail_id == travail_id && i.suppression.is_none())
+            .cloned()
+            .collect();
+        items.sort_by_key(|i| i.ordre);
+        Ok(items)
+    }
+
+    async fn list_by_responsable(&self, utilisateur_id: Uuid) -> Result<Vec<ChecklistItem>> {
+        Ok(verrou(&self.items)?.values()
+            .filter(|i| i.responsable == utilisateur_id && i.suppression.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_travaux(&self, travail_ids: &[Uuid]) -> Result<Vec<ChecklistItem>> {
+        Ok(verrou(&self.items)?.values()
+            .filter(|i| travail_ids.contains(&i.travail_id) && i.suppression.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn list_supprimes_by_responsable(&self, utilisateur_id: Uuid) -> Result<Vec<ChecklistItem>> {
+        Ok(verrou(&self.items)?.values()
+            .filter(|i| i.responsable == utilisateur_id && i.suppression.is_some())
+            .cloned()
+            .collect())
+    }
+
+    async fn list_supprimes_avant(&self, date: DateTime<Utc>) -> Result<Vec<ChecklistItem>> {
+        Ok(verrou(&self.items)?.values()
+            .filter(|i| i.suppression.as_ref().is_some_and(|s| s.le < date))
+            .cloned()
+            .collect())
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<ChecklistItem>> {
+        Ok(verrou(&self.items)?.get(&id).filter(|i| i.suppression.is_none()).cloned())
+    }
+
+    async fn insert(&self, item: &ChecklistItem) -> Result<()> {
+        verrou(&self.items)?.insert(item.id, item.clone());
+        Ok(())
+    }
+
+    async fn update(&self, item: &ChecklistItem) -> Result<bool> {
+        match verrou(&self.items)?.get_mut(&item.id) {
+            Some(existing) => {
+                *existing = item.clone();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        Ok(verrou(&self.items)?.remove(&id).is_some())
+    }
+
+    async fn delete_by_travail(&self, travail_id: Uuid) -> Result<()> {
+        verrou(&self.items)?.retain(|_, i| i.travail_id != travail_id);
+        Ok(())
+    }
+
+    async fn reorder(&self, travail_id: Uuid, ids: &[Uuid]) -> Result<()> {
+        let mut items = verrou(&self.items)?;
+        for (position, id) in ids.iter().enumerate() {
+            if let Some(item) = items.get_mut(id).filter(|i| i.travail_id == travail_id) {
+                item.ordre = position as i32;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireModeleChecklistRepository {
+    modeles: Mutex<HashMap<Uuid, ModeleChecklist>>,
+}
+
+#[async_trait]
+impl ModeleChecklistRepository for MemoireModeleChecklistRepository {
+    async fn list(&self) -> Result<Vec<ModeleChecklist>> {
+        Ok(verrou(&self.modeles)?.values().cloned().collect())
+    }
+
+    async fn list_by_type(&self, type_travail: &TypeTravail) -> Result<Vec<ModeleChecklist>> {
+        Ok(verrou(&self.modeles)?.values()
+            .filter(|m| m.type_travail == *type_travail)
+            .cloned()
+            .collect())
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<ModeleChecklist>> {
+        Ok(verrou(&self.modeles)?.get(&id).cloned())
+    }
+
+    async fn insert(&self, modele: &ModeleChecklist) -> Result<()> {
+        verrou(&self.modeles)?.insert(modele.id, modele.clone());
+        Ok(())
+    }
+
+    async fn update(&self, modele: &ModeleChecklist) -> Result<bool> {
+        match verrou(&self.modeles)?.get_mut(&modele.id) {
+            Some(existing) => {
+                *existing = modele.clone();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        Ok(verrou(&self.modeles)?.remove(&id).is_some())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireTransitionRepository {
+    transitions: Mutex<Vec<TransitionStatut>>,
+}
+
+#[async_trait]
+impl TransitionRepository for MemoireTransitionRepository {
+    async fn list_by_entite(&self, entite: &TypeEntite, entite_id: Uuid) -> Result<Vec<TransitionStatut>> {
+        Ok(verrou(&self.transitions)?.iter()
+            .filter(|t| t.entite == *entite && t.entite_id == entite_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn insert(&self, transition: &TransitionStatut) -> Result<()> {
+        verrou(&self.transitions)?.push(transition.clone());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireApprobationRepository {
+    demandes: Mutex<Vec<DemandeApprobation>>,
+    decisions: Mutex<Vec<Approbation>>,
+}
+
+#[async_trait]
+impl ApprobationRepository for MemoireApprobationRepository {
+    async fn list_demandes(&self, travail_id: Uuid) -> Result<Vec<DemandeApprobation>> {
+        Ok(verrou(&self.demandes)?.iter()
+            .filter(|d| d.travail_id == travail_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_decisions(&self, demande_id: Uuid) -> Result<Vec<Approbation>> {
+        Ok(verrou(&self.decisions)?.iter()
+            .filter(|d| d.demande_id == demande_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn insert_demande(&self, demande: &DemandeApprobation) -> Result<()> {
+        verrou(&self.demandes)?.push(demande.clone());
+        Ok(())
+    }
+
+    async fn insert_decision(&self, decision: &Approbation) -> Result<()> {
+        verrou(&self.decisions)?.push(decision.clone());
+        Ok(())
+    }
+
+    async fn invalider(&self, travail_id: Uuid) -> Result<u64> {
+        let maintenant = Utc::now();
+        let mut invalidees = 0;
+        for demande in verrou(&self.demandes)?.iter_mut() {
+            if demande.travail_id == travail_id && demande.invalidee_le.is_none() {
+                demande.invalidee_le = Some(maintenant);
+                invalidees += 1;
+            }
+        }
+        Ok(invalidees)
+    }
+
+    async fn delete_by_travail(&self, travail_id: Uuid) -> Result<()> {
+        let mut demandes = verrou(&self.demandes)?;
+        let ids: Vec<Uuid> = demandes.iter().filter(|d| d.travail_id == travail_id).map(|d| d.id).collect();
+        verrou(&self.decisions)?.retain(|d| !ids.contains(&d.demande_id));
+        demandes.retain(|d| d.travail_id != travail_id);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireAuditRepository {
+    evenements: Mutex<Vec<EvenementAudit>>,
+}
+
+#[async_trait]
+impl AuditRepository for MemoireAuditRepository {
+    async fn list(&self, filtre: &FiltreAudit, pagination: &Pagination) -> Result<Page<EvenementAudit>> {
+        let evenements = verrou(&self.evenements)?.iter().filter(|e| filtre.accepte(e)).cloned().collect();
+        Ok(paginer(evenements, pagination, cle_audit, |e| e.id))
+    }
+
+    async fn insert(&self, evenement: &EvenementAudit) -> Result<()> {
+        verrou(&self.evenements)?.push(evenement.clone());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireGelRepository {
+    gels: Mutex<HashMap<Uuid, PeriodeGel>>,
+    derogations: Mutex<Vec<DerogationGel>>,
+}
+
+#[async_trait]
+impl GelRepository for MemoireGelRepository {
+    async fn list(&self) -> Result<Vec<PeriodeGel>> {
+        let mut gels: Vec<PeriodeGel> = verrou(&self.gels)?.values().cloned().collect();
+        gels.sort_by_key(|g| (g.date_debut, g.id));
+        Ok(gels)
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<PeriodeGel>> {
+        Ok(verrou(&self.gels)?.get(&id).cloned())
+    }
+
+    async fn insert(&self, gel: &PeriodeGel) -> Result<()> {
+        verrou(&self.gels)?.insert(gel.id, gel.clone());
+        Ok(())
+    }
+
+    async fn update(&self, gel: &PeriodeGel) -> Result<bool> {
+        match verrou(&self.gels)?.get_mut(&gel.id) {
+            Some(existing) => {
+                *existing = gel.clone();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        verrou(&self.derogations)?.retain(|d| d.periode_gel_id != id);
+        Ok(verrou(&self.gels)?.remove(&id).is_some())
+    }
+
+    async fn list_derogations_by_gel(&self, periode_gel_id: Uuid) -> Result<Vec<DerogationGel>> {
+        Ok(verrou(&self.derogations)?.iter()
+            .filter(|d| d.periode_gel_id == periode_gel_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_derogations_by_travail(&self, travail_id: Uuid) -> Result<Vec<DerogationGel>> {
+        Ok(verrou(&self.derogations)?.iter()
+            .filter(|d| d.travail_id == travail_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn insert_derogation(&self, derogation: &DerogationGel) -> Result<()> {
+        verrou(&self.derogations)?.push(derogation.clone());
+        Ok(())
+    }
+
+    async fn delete_derogations_by_travail(&self, travail_id: Uuid) -> Result<()> {
+        verrou(&self.derogations)?.retain(|d| d.travail_id != travail_id);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoireDependanceRepository {
+    dependances: Mutex<Vec<Dependance>>,
+}
+
+#[async_trait]
+impl DependanceRepository for MemoireDependanceRepository {
+    async fn list_by_travaux(&self, travail_ids: &[Uuid]) -> Result<Vec<Dependance>> {
+        Ok(verrou(&self.dependances)?.iter()
+            .filter(|d| travail_ids.contains(&d.travail_id) || travail_ids.contains(&d.depend_de))
+            .cloned()
+            .collect())
+    }
+
+    async fn insert(&self, dependance: &Dependance) -> Result<()> {
+        verrou(&self.dependances)?.push(dependance.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut dependances = verrou(&self.dependances)?;
+        let avant = dependances.len();
+        dependances.retain(|d| d.id != id);
+        Ok(dependances.len() < avant)
+    }
+}
+
+// Données de tous les repositories en mémoire. Une unité de travail en exclut les autres et
+// restaure l'instantané pris à son ouverture si elle n'est pas validée.
+macro_rules! etat_memoire {
+    ($($depot:ident: $type:ident { $($champ:ident),+ }),+ $(,)?) => {
+        #[derive(Default)]
+        pub struct EtatMemoire {
+            $(pub $depot: Arc<$type>,)+
+            unite: Arc<tokio::sync::Mutex<()>>,
+        }
+
+        struct Instantane {
+            $($depot: $type,)+
+        }
+
+        impl EtatMemoire {
+            fn capturer(&self) -> Result<Instantane> {
+                Ok(Instantane {
+                    $($depot: $type { $($champ: Mutex::new(verrou(&self.$depot.$champ)?.clone()),)+ },)+
+                })
+            }
+
+            fn restaurer(&self, instantane: Instantane) -> Result<()> {
+                $($(*verrou(&self.$depot.$champ)? = instantane.$depot.$champ.into_inner().unwrap_or_else(PoisonError::into_inner);)+)+
+                Ok(())
+            }
+        }
+    };
+}
+
+etat_memoire! {
+    projets: MemoireProjetRepository { projets },
+    travaux: MemoireTravailRepository { travaux },
+    utilisateurs: MemoireUtilisateurRepository { utilisateurs, mots_de_passe, jetons_calendrier },
+    checklist: MemoireChecklistRepository { items },
+    modeles_checklist: MemoireModeleChecklistRepository { modeles },
+    transitions: MemoireTransitionRepository { transitions },
+    approbations: MemoireApprobationRepository { demandes, decisions },
+    audit: MemoireAuditRepository { evenements },
+    gels: MemoireGelRepository { gels, derogations },
+    dependances: MemoireDependanceRepository { dependances },
+}
+
+pub struct UniteMemoire {
+    etat: Arc<EtatMemoire>,
+    instantane: Option<Box<Instantane>>,
+    _exclusion: tokio::sync::OwnedMutexGuard<()>,
+}
+
+impl UniteMemoire {
+    pub async fn ouvrir(etat: Arc<EtatMemoire>) -> Result<Self> {
+        let exclusion = etat.unite.clone().lock_owned().await;
+        let instantane = Box::new(etat.capturer()?);
+        Ok(UniteMemoire { etat, instantane: Some(instantane), _exclusion: exclusion })
+    }
+
+    pub fn valider(mut self) {
+        self.instantane = None;
+    }
+}
+
+impl Drop for UniteMemoire {
+    fn drop(&mut self) {
+        if let Some(instantane) = self.instantane.take() {
+            if let Err(err) = self.etat.restaurer(*instantane) {
+                eprintln!("❌ Annulation de l'unité de travail impossible : {:#}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,321 @@
+// Abstraction du stockage utilisée par les handlers
+
+use std::future::{ready, Ready};
+use std::ops::Deref;
+use std::sync::Arc;
+use actix_web::dev::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::{Approbation, ChecklistItem, DemandeApprobation, Dependance, DerogationGel, EvenementAudit, ModeleChecklist, PeriodeGel, Projet, Travail, TransitionStatut, TypeEntite, TypeTravail, Utilisateur};
+
+pub mod filtres;
+pub mod memoire;
+pub mod postgres;
+
+pub use filtres::{FiltreAudit, FiltreProjets, FiltreTravaux, FiltreUtilisateurs, Page, Pagination, Supprimes, Tri};
+
+// Ensemble des repositories choisis au démarrage, partagés par tous les workers
+#[derive(Clone)]
+pub struct Repositories {
+    pub projets: Arc<dyn ProjetRepository>,
+    pub travaux: Arc<dyn TravailRepository>,
+    pub utilisateurs: Arc<dyn UtilisateurRepository>,
+    pub checklist: Arc<dyn ChecklistRepository>,
+    pub modeles_checklist: Arc<dyn ModeleChecklistRepository>,
+    pub transitions: Arc<dyn TransitionRepository>,
+    pub approbations: Arc<dyn ApprobationRepository>,
+    pub audit: Arc<dyn AuditRepository>,
+    pub gels: Arc<dyn GelRepository>,
+    pub dependances: Arc<dyn DependanceRepository>,
+    stockage: Stockage,
+}
+
+#[derive(Clone)]
+enum Stockage {
+    Memoire(Arc<memoire::EtatMemoire>),
+    Postgres(Pool<Postgres>),
+    // Repositories d'une unité de travail ouverte : les unités imbriquées s'y joignent
+    Unite,
+}
+
+impl Repositories {
+    pub fn memoire() -> Self {
+        let etat = Arc::new(memoire::EtatMemoire::default());
+        Repositories {
+            projets: etat.projets.clone(),
+            travaux: etat.travaux.clone(),
+            utilisateurs: etat.utilisateurs.clone(),
+            checklist: etat.checklist.clone(),
+            modeles_checklist: etat.modeles_checklist.clone(),
+            transitions: etat.transitions.clone(),
+            approbations: etat.approbations.clone(),
+            audit: etat.audit.clone(),
+            gels: etat.gels.clone(),
+            dependances: etat.dependances.clone(),
+            stockage: Stockage::Memoire(etat),
+        }
+    }
+
+    pub fn postgres(pool: &Pool<Postgres>) -> Self {
+        Repositories::sur_connexion(postgres::Connexion::Pool(pool.clone()), Stockage::Postgres(pool.clone()))
+    }
+
+    fn sur_connexion(connexion: postgres::Connexion, stockage: Stockage) -> Self {
+        Repositories {
+            projets: Arc::new(postgres::PgProjetRepository::new(connexion.clone())),
+            travaux: Arc::new(postgres::PgTravailRepository::new(connexion.clone())),
+            utilisateurs: Arc::new(postgres::PgUtilisateurRepository::new(connexion.clone())),
+            checklist: Arc::new(postgres::PgChecklistRepository::new(connexion.clone())),
+            modeles_checklist: Arc::new(postgres::PgModeleChecklistRepository::new(connexion.clone())),
+            transitions: Arc::new(postgres::PgTransitionRepository::new(connexion.clone())),
+            approbations: Arc::new(postgres::PgApprobationRepository::new(connexion.clone())),
+            audit: Arc::new(postgres::PgAuditRepository::new(connexion.clone())),
+            gels: Arc::new(postgres::PgGelRepository::new(connexion.clone())),
+            dependances: Arc::new(postgres::PgDependanceRepository::new(connexion)),
+            stockage,
+        }
+    }
+
+    // Ouvre une unité de travail : les écritures faites via ses repositories sont validées
+    // ensemble par `valider`, et annulées si l'unité est abandonnée (erreur propagée par `?`)
+    pub async fn transaction(&self) -> Result<UniteDeTravail> {
+        match &self.stockage {
+            Stockage::Memoire(etat) => Ok(UniteDeTravail {
+                depots: Repositories { stockage: Stockage::Unite, ..self.clone() },
+                fin: FinUnite::Memoire(memoire::UniteMemoire::ouvrir(etat.clone()).await?),
+            }),
+            Stockage::Postgres(pool) => {
+                let connexion = postgres::Connexion::transaction(pool).await?;
+                Ok(UniteDeTravail {
+                    depots: Repositories::sur_connexion(connexion.clone(), Stockage::Unite),
+                    fin: FinUnite::Postgres(connexion),
+                })
+            }
+            Stockage::Unite => Ok(UniteDeTravail { depots: self.clone(), fin: FinUnite::Englobee }),
+        }
+    }
+
+    // Expose chaque repository aux handlers sous la forme `web::Data<dyn XRepository>`, et
+    // l'ensemble sous la forme de l'extracteur `Repositories`
+    pub fn register(&self, cfg: &mut web::ServiceConfig) {
+        cfg.app_data(web::Data::new(self.clone()))
+            .app_data(web::Data::from(self.projets.clone()))
+            .app_data(web::Data::from(self.travaux.clone()))
+            .app_data(web::Data::from(self.utilisateurs.clone()))
+            .app_data(web::Data::from(self.checklist.clone()))
+            .app_data(web::Data::from(self.modeles_checklist.clone()))
+            .app_data(web::Data::from(self.transitions.clone()))
+            .app_data(web::Data::from(self.approbations.clone()))
+            .app_data(web::Data::from(self.audit.clone()))
+            .app_data(web::Data::from(self.gels.clone()))
+            .app_data(web::Data::from(self.dependances.clone()));
+    }
+}
+
+pub struct UniteDeTravail {
+    depots: Repositories,
+    fin: FinUnite,
+}
+
+enum FinUnite {
+    Memoire(memoire::UniteMemoire),
+    Postgres(postgres::Connexion),
+    // Unité imbriquée : validée avec l'unité qui l'englobe
+    Englobee,
+}
+
+impl UniteDeTravail {
+    pub async fn valider(self) -> Result<()> {
+        match self.fin {
+            FinUnite::Memoire(unite) => unite.valider(),
+            FinUnite::Postgres(connexion) => connexion.valider().await?,
+            FinUnite::Englobee => {}
+        }
+        Ok(())
+    }
+}
+
+impl Deref for UniteDeTravail {
+    type Target = Repositories;
+
+    fn deref(&self) -> &Repositories {
+        &self.depots
+    }
+}
+
+// Extracteur des handlers qui écrivent (l'unité de travail s'ouvre depuis `Repositories`) ou
+// qui s'appuient sur quatre repositories ou plus, plutôt qu'un `web::Data<dyn XRepository>` par repository
+impl FromRequest for Repositories {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, ApiError>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            req.app_data::<web::Data<Repositories>>()
+                .map(|repositories| repositories.get_ref().clone())
+                .ok_or_else(|| ApiError::Storage(anyhow!("Repositories non enregistrés"))),
+        )
+    }
+}
+
+// Les projets, travaux et utilisateurs supprimés logiquement (champ `suppression`) sont ignorés
+// par toutes les lectures, sauf `list` selon `filtre.supprimes` et `find_supprime`
+#[async_trait]
+pub trait ProjetRepository: Send + Sync {
+    async fn list(&self, filtre: &FiltreProjets, pagination: &Pagination) -> Result<Page<Projet>>;
+    async fn find(&self, id: Uuid) -> Result<Option<Projet>>;
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Projet>>;
+    async fn find_by_code(&self, code: &str) -> Result<Option<Projet>>;
+    async fn insert(&self, projet: &Projet) -> Result<()>;
+    // Écrit (suppression logique comprise) si la version stockée est encore celle de l'entité, et
+    // l'incrémente ; false si l'entité est absente ou a été modifiée entre-temps
+    async fn update(&self, projet: &Projet) -> Result<bool>;
+    // Suppression définitive (purge)
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait TravailRepository: Send + Sync {
+    async fn list(&self, filtre: &FiltreTravaux, pagination: &Pagination) -> Result<Page<Travail>>;
+    async fn list_by_projet(&self, projet_id: Uuid) -> Result<Vec<Travail>>;
+    // Travaux de plusieurs projets en une requête (santé d'une page de projets)
+    async fn list_by_projets(&self, projet_ids: &[Uuid]) -> Result<Vec<Travail>>;
+    // Travaux dont l'utilisateur est responsable ou membre de l'équipe
+    async fn list_by_utilisateur(&self, utilisateur_id: Uuid) -> Result<Vec<Travail>>;
+    async fn find(&self, id: Uuid) -> Result<Option<Travail>>;
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Travail>>;
+    async fn insert(&self, travail: &Travail) -> Result<()>;
+    // Même contrôle de version que ProjetRepository::update
+    async fn update(&self, travail: &Travail) -> Result<bool>;
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait UtilisateurRepository: Send + Sync {
+    async fn list(&self, filtre: &FiltreUtilisateurs, pagination: &Pagination) -> Result<Page<Utilisateur>>;
+    async fn find(&self, id: Uuid) -> Result<Option<Utilisateur>>;
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Utilisateur>>;
+    async fn find_by_email(&self, email: &str) -> Result<Option<Utilisateur>>;
+    async fn insert(&self, utilisateur: &Utilisateur) -> Result<()>;
+    // Même contrôle de version que ProjetRepository::update
+    async fn update(&self, utilisateur: &Utilisateur) -> Result<bool>;
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+    // Hash du mot de passe, stocké à part du modèle pour ne jamais être sérialisé
+    async fn mot_de_passe(&self, id: Uuid) -> Result<Option<String>>;
+    async fn set_mot_de_passe(&self, id: Uuid, hash: &str) -> Result<bool>;
+    // Empreinte du jeton d'abonnement au calendrier ; None révoque l'abonnement
+    async fn set_jeton_calendrier(&self, id: Uuid, empreinte: Option<&str>) -> Result<bool>;
+    async fn find_by_jeton_calendrier(&self, empreinte: &str) -> Result<Option<Utilisateur>>;
+}
+
+#[async_trait]
+pub trait ChecklistRepository: Send + Sync {
+    // Items d'un travail triés par `ordre`
+    async fn list_by_travail(&self, travail_id: Uuid) -> Result<Vec<ChecklistItem>>;
+    async fn list_by_responsable(&self, utilisateur_id: Uuid) -> Result<Vec<ChecklistItem>>;
+    async fn list_by_travaux(&self, travail_ids: &[Uuid]) -> Result<Vec<ChecklistItem>>;
+    // Items supprimés logiquement : ceux d'un responsable, ceux supprimés avant une date
+    async fn list_supprimes_by_responsable(&self, utilisateur_id: Uuid) -> Result<Vec<ChecklistItem>>;
+    async fn list_supprimes_avant(&self, date: DateTime<Utc>) -> Result<Vec<ChecklistItem>>;
+    async fn find(&self, id: Uuid) -> Result<Option<ChecklistItem>>;
+    async fn insert(&self, item: &ChecklistItem) -> Result<()>;
+    async fn update(&self, item: &ChecklistItem) -> Result<bool>;
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+    async fn delete_by_travail(&self, travail_id: Uuid) -> Result<()>;
+    // Réattribue `ordre` selon la position de chaque id dans `ids`
+    async fn reorder(&self, travail_id: Uuid, ids: &[Uuid]) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ModeleChecklistRepository: Send + Sync {
+    async fn list(&self) -> Result<Vec<ModeleChecklist>>;
+    async fn list_by_type(&self, type_travail: &TypeTravail) -> Result<Vec<ModeleChecklist>>;
+    async fn find(&self, id: Uuid) -> Result<Option<ModeleChecklist>>;
+    async fn insert(&self, modele: &ModeleChecklist) -> Result<()>;
+    async fn update(&self, modele: &ModeleChecklist) -> Result<bool>;
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait TransitionRepository: Send + Sync {
+    // Historique chronologique des transitions d'une entité
+    async fn list_by_entite(&self, entite: &TypeEntite, entite_id: Uuid) -> Result<Vec<TransitionStatut>>;
+    async fn insert(&self, transition: &TransitionStatut) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ApprobationRepository: Send + Sync {
+    // Demandes d'un travail, de la plus ancienne à la plus récente
+    async fn list_demandes(&self, travail_id: Uuid) -> Result<Vec<DemandeApprobation>>;
+    async fn list_decisions(&self, demande_id: Uuid) -> Result<Vec<Approbation>>;
+    async fn insert_demande(&self, demande: &DemandeApprobation) -> Result<()>;
+    async fn insert_decision(&self, decision: &Approbation) -> Result<()>;
+    // Invalide les demandes encore actives du travail ; renvoie le nombre de demandes touchées
+    async fn invalider(&self, travail_id: Uuid) -> Result<u64>;
+    async fn delete_by_travail(&self, travail_id: Uuid) -> Result<()>;
+}
+
+// Journal en ajout seul : pas de mise à jour ni de suppression
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn list(&self, filtre: &FiltreAudit, pagination: &Pagination) -> Result<Page<EvenementAudit>>;
+    async fn insert(&self, evenement: &EvenementAudit) -> Result<()>;
+}
+
+// Périodes de gel et dérogations accordées
+#[async_trait]
+pub trait GelRepository: Send + Sync {
+    // Triées par date de début
+    async fn list(&self) -> Result<Vec<PeriodeGel>>;
+    async fn find(&self, id: Uuid) -> Result<Option<PeriodeGel>>;
+    async fn insert(&self, gel: &PeriodeGel) -> Result<()>;
+    async fn update(&self, gel: &PeriodeGel) -> Result<bool>;
+    // Supprime aussi les dérogations de la période
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+    async fn list_derogations_by_gel(&self, periode_gel_id: Uuid) -> Result<Vec<DerogationGel>>;
+    async fn list_derogations_by_travail(&self, travail_id: Uuid) -> Result<Vec<DerogationGel>>;
+    async fn insert_derogation(&self, derogation: &DerogationGel) -> Result<()>;
+    async fn delete_derogations_by_travail(&self, travail_id: Uuid) -> Result<()>;
+}
+
+#[async_trait]
+pub trait DependanceRepository: Send + Sync {
+    // Dépendances dont le travail dépendant ou le prédécesseur fait partie de `travail_ids`
+    async fn list_by_travaux(&self, travail_ids: &[Uuid]) -> Result<Vec<Dependance>>;
+    async fn insert(&self, dependance: &Dependance) -> Result<()>;
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support;
+
+    #[actix_web::test]
+    async fn unite_de_travail_en_memoire() {
+        let depots = Repositories::memoire();
+        let (annule, valide) = (test_support::projet(), test_support::projet());
+
+        // Abandonnée (erreur propagée avant `valider`) : écritures annulées
+        {
+            let unite = depots.transaction().await.unwrap();
+            unite.projets.insert(&annule).await.unwrap();
+            assert!(depots.projets.find(annule.id).await.unwrap().is_some());
+        }
+        assert!(depots.projets.find(annule.id).await.unwrap().is_none());
+
+        // Une unité imbriquée se joint à l'unité englobante
+        let unite = depots.transaction().await.unwrap();
+        let imbriquee = unite.transaction().await.unwrap();
+        imbriquee.projets.insert(&valide).await.unwrap();
+        imbriquee.valider().await.unwrap();
+        unite.valider().await.unwrap();
+        assert!(depots.projets.find(valide.id).await.unwrap().is_some());
+    }
+}
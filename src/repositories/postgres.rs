@@ -0,0 +1,1261 @@
+// Implémentation PostgreSQL via le pool SQLx de `Database`
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgConnection, PgRow};
+use sqlx::types::Json;
+use sqlx::{Connection, Pool, Postgres, QueryBuilder, Row};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use uuid::Uuid;
+
+use crate::models::{Approbation, ChangementChamp, ChecklistItem, DemandeApprobation, Dependance, DerogationGel, EvenementAudit, EtapeModele, ModeleChecklist, PeriodeGel, Projet, StatutEntite, Suppression, Travail, TransitionStatut, TypeEntite, TypeTravail, Utilisateur};
+use super::filtres::order_by;
+use super::{FiltreAudit, FiltreProjets, FiltreTravaux, FiltreUtilisateurs, Page, Pagination, Supprimes};
+use super::{ApprobationRepository, AuditRepository, ChecklistRepository, DependanceRepository, GelRepository, ModeleChecklistRepository, ProjetRepository, TransitionRepository, TravailRepository, UtilisateurRepository};
+
+// Connexion des repositories : le pool, ou la transaction d'une unité de travail partagée par
+// tous les repositories de l'unité (voir Repositories::transaction)
+#[derive(Clone)]
+pub enum Connexion {
+    Pool(Pool<Postgres>),
+    Transaction(Arc<Mutex<Option<sqlx::Transaction<'static, Postgres>>>>),
+}
+
+impl Connexion {
+    pub async fn transaction(pool: &Pool<Postgres>) -> Result<Self> {
+        Ok(Connexion::Transaction(Arc::new(Mutex::new(Some(pool.begin().await?)))))
+    }
+
+    // Connexion pour une requête ; dans une unité de travail, les requêtes s'exécutent l'une après l'autre
+    async fn acquerir(&self) -> Result<Acquise<'_>> {
+        match self {
+            Connexion::Pool(pool) => Ok(Acquise::Pool(Box::new(pool.acquire().await?))),
+            Connexion::Transaction(transaction) => MutexGuard::try_map(transaction.lock().await, Option::as_mut)
+                .map(Acquise::Transaction)
+                .map_err(|_| anyhow!("Unité de travail déjà validée")),
+        }
+    }
+
+    // COMMIT de l'unité de travail ; sans appel, la transaction est annulée à sa libération
+    pub async fn valider(&self) -> Result<()> {
+        if let Connexion::Transaction(transaction) = self {
+            if let Some(transaction) = transaction.lock().await.take() {
+                transaction.commit().await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+enum Acquise<'a> {
+    Pool(Box<PoolConnection<Postgres>>),
+    Transaction(MappedMutexGuard<'a, sqlx::Transaction<'static, Postgres>>),
+}
+
+impl Deref for Acquise<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Acquise::Pool(connexion) => connexion,
+            Acquise::Transaction(transaction) => transaction,
+        }
+    }
+}
+
+impl DerefMut for Acquise<'_> {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            Acquise::Pool(connexion) => connexion,
+            Acquise::Transaction(transaction) => transaction,
+        }
+    }
+}
+
+pub struct PgProjetRepository {
+    connexion: Connexion,
+}
+
+impl PgProjetRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgProjetRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl ProjetRepository for PgProjetRepository {
+    async fn list(&self, filtre: &FiltreProjets, pagination: &Pagination) -> Result<Page<Projet>> {
+        let (rows, total) = lister(&self.connexion, "projets", pagination, |requete| {
+            if let Some(statut) = &filtre.statut {
+                requete.push(" AND statut = ").push_bind(statut.clone());
+            }
+            if let Some(priorite) = &filtre.priorite {
+                requete.push(" AND priorite = ").push_bind(priorite.clone());
+            }
+            filtrer_supprimes(requete, filtre.supprimes);
+        }).await?;
+        Ok(Page { elements: rows.iter().map(projet_from_row).collect::<Result<_>>()?, total })
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<Projet>> {
+        let row = sqlx::query("SELECT * FROM projets WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(projet_from_row).transpose()
+    }
+
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Projet>> {
+        let row = sqlx::query("SELECT * FROM projets WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(projet_from_row).transpose()
+    }
+
+    async fn find_by_code(&self, code: &str) -> Result<Option<Projet>> {
+        let row = sqlx::query("SELECT * FROM projets WHERE code = $1 AND deleted_at IS NULL")
+            .bind(code)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(projet_from_row).transpose()
+    }
+
+    async fn insert(&self, projet: &Projet) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO projets (id, code, nom, description, date_debut, date_fin_prevue, statut, priorite)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(projet.id)
+        .bind(&projet.code)
+        .bind(&projet.nom)
+        .bind(&projet.description)
+        .bind(projet.date_debut)
+        .bind(projet.date_fin_prevue)
+        .bind(&projet.statut)
+        .bind(&projet.priorite)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update(&self, projet: &Projet) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE projets
+            SET code = $2, nom = $3, description = $4, date_debut = $5,
+                date_fin_prevue = $6, statut = $7, priorite = $8,
+                deleted_at = $10, deleted_by = $11,
+                version = version + 1, updated_at = NOW()
+            WHERE id = $1 AND version = $9
+            "#
+        )
+        .bind(projet.id)
+        .bind(&projet.code)
+        .bind(&projet.nom)
+        .bind(&projet.description)
+        .bind(projet.date_debut)
+        .bind(projet.date_fin_prevue)
+        .bind(&projet.statut)
+        .bind(&projet.priorite)
+        .bind(projet.version)
+        .bind(projet.suppression.as_ref().map(|s| s.le))
+        .bind(projet.suppression.as_ref().and_then(|s| s.par))
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM projets WHERE id = $1")
+            .bind(id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+pub struct PgTravailRepository {
+    connexion: Connexion,
+}
+
+impl PgTravailRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgTravailRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl TravailRepository for PgTravailRepository {
+    async fn list(&self, filtre: &FiltreTravaux, pagination: &Pagination) -> Result<Page<Travail>> {
+        let (rows, total) = lister(&self.connexion, "travaux", pagination, |requete| {
+            if let Some(projet_id) = filtre.projet_id {
+                requete.push(" AND projet_id = ").push_bind(projet_id);
+            }
+            if let Some(statut) = &filtre.statut {
+                requete.push(" AND statut = ").push_bind(statut.clone());
+            }
+            if let Some(application) = &filtre.application {
+                requete.push(" AND application = ").push_bind(application.clone());
+            }
+            if let Some(environnement) = &filtre.environnement {
+                requete.push(" AND environnement = ").push_bind(environnement.clone());
+            }
+            if let Some(type_travail) = &filtre.type_travail {
+                requete.push(" AND type_travail = ").push_bind(type_travail.clone());
+            }
+            if let Some(responsable) = filtre.responsable {
+                requete.push(" AND responsable = ").push_bind(responsable);
+            }
+            if let Some(promotion_de) = filtre.promotion_de {
+                requete.push(" AND promotion_de = ").push_bind(promotion_de);
+            }
+            if let Some(du) = filtre.du {
+                requete.push(" AND date_fin_prevue >= ").push_bind(du);
+            }
+            if let Some(au) = filtre.au {
+                requete.push(" AND date_debut <= ").push_bind(au);
+            }
+            filtrer_supprimes(requete, filtre.supprimes);
+        }).await?;
+        Ok(Page { elements: rows.iter().map(travail_from_row).collect::<Result<_>>()?, total })
+    }
+
+    async fn list_by_projet(&self, projet_id: Uuid) -> Result<Vec<Travail>> {
+        let rows = sqlx::query("SELECT * FROM travaux WHERE projet_id = $1 AND deleted_at IS NULL ORDER BY date_debut, id")
+            .bind(projet_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(travail_from_row).collect()
+    }
+
+    async fn list_by_projets(&self, projet_ids: &[Uuid]) -> Result<Vec<Travail>> {
+        let rows = sqlx::query("SELECT * FROM travaux WHERE projet_id = ANY($1) AND deleted_at IS NULL ORDER BY date_debut, id")
+            .bind(projet_ids)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(travail_from_row).collect()
+    }
+
+    async fn list_by_utilisateur(&self, utilisateur_id: Uuid) -> Result<Vec<Travail>> {
+        let rows = sqlx::query("SELECT * FROM travaux WHERE (responsable = $1 OR $1 = ANY(equipe)) AND deleted_at IS NULL ORDER BY date_debut, id")
+            .bind(utilisateur_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(travail_from_row).collect()
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<Travail>> {
+        let row = sqlx::query("SELECT * FROM travaux WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(travail_from_row).transpose()
+    }
+
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Travail>> {
+        let row = sqlx::query("SELECT * FROM travaux WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(travail_from_row).transpose()
+    }
+
+    async fn insert(&self, travail: &Travail) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO travaux (id, projet_id, type_travail, application, environnement, description,
+                                 date_debut, date_fin_prevue, statut, responsable, equipe, promotion_de)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#
+        )
+        .bind(travail.id)
+        .bind(travail.projet_id)
+        .bind(&travail.type_travail)
+        .bind(&travail.application)
+        .bind(&travail.environnement)
+        .bind(&travail.description)
+        .bind(travail.date_debut)
+        .bind(travail.date_fin_prevue)
+        .bind(&travail.statut)
+        .bind(travail.responsable)
+        .bind(&travail.equipe)
+        .bind(travail.promotion_de)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update(&self, travail: &Travail) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE travaux
+            SET projet_id = $2, type_travail = $3, application = $4, environnement = $5,
+                description = $6, date_debut = $7, date_fin_prevue = $8, statut = $9,
+                responsable = $10, equipe = $11, promotion_de = $12,
+                deleted_at = $14, deleted_by = $15,
+                version = version + 1, updated_at = NOW()
+            WHERE id = $1 AND version = $13
+            "#
+        )
+        .bind(travail.id)
+        .bind(travail.projet_id)
+        .bind(&travail.type_travail)
+        .bind(&travail.application)
+        .bind(&travail.environnement)
+        .bind(&travail.description)
+        .bind(travail.date_debut)
+        .bind(travail.date_fin_prevue)
+        .bind(&travail.statut)
+        .bind(travail.responsable)
+        .bind(&travail.equipe)
+        .bind(travail.promotion_de)
+        .bind(travail.version)
+        .bind(travail.suppression.as_ref().map(|s| s.le))
+        .bind(travail.suppression.as_ref().and_then(|s| s.par))
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM travaux WHERE id = $1")
+            .bind(id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+pub struct PgUtilisateurRepository {
+    connexion: Connexion,
+}
+
+impl PgUtilisateurRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgUtilisateurRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl UtilisateurRepository for PgUtilisateurRepository {
+    async fn list(&self, filtre: &FiltreUtilisateurs, pagination: &Pagination) -> Result<Page<Utilisateur>> {
+        let (rows, total) = lister(&self.connexion, "utilisateurs", pagination, |requete| {
+            if let Some(role) = &filtre.role {
+                requete.push(" AND role = ").push_bind(role.clone());
+            }
+            if let Some(equipe) = &filtre.equipe {
+                requete.push(" AND equipe = ").push_bind(equipe.clone());
+            }
+            if let Some(actif) = filtre.actif {
+                requete.push(" AND actif = ").push_bind(actif);
+            }
+            filtrer_supprimes(requete, filtre.supprimes);
+        }).await?;
+        Ok(Page { elements: rows.iter().map(utilisateur_from_row).collect::<Result<_>>()?, total })
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<Utilisateur>> {
+        let row = sqlx::query("SELECT * FROM utilisateurs WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(utilisateur_from_row).transpose()
+    }
+
+    async fn find_supprime(&self, id: Uuid) -> Result<Option<Utilisateur>> {
+        let row = sqlx::query("SELECT * FROM utilisateurs WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(utilisateur_from_row).transpose()
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<Utilisateur>> {
+        let row = sqlx::query("SELECT * FROM utilisateurs WHERE email = $1 AND deleted_at IS NULL")
+            .bind(email)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(utilisateur_from_row).transpose()
+    }
+
+    async fn insert(&self, utilisateur: &Utilisateur) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO utilisateurs (id, nom, email, role, equipe, actif)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(utilisateur.id)
+        .bind(&utilisateur.nom)
+        .bind(&utilisateur.email)
+        .bind(&utilisateur.role)
+        .bind(&utilisateur.equipe)
+        .bind(utilisateur.actif)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update(&self, utilisateur: &Utilisateur) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE utilisateurs
+            SET nom = $2, email = $3, role = $4, equipe = $5, actif = $6,
+                deleted_at = $8, deleted_by = $9,
+                version = version + 1, updated_at = NOW()
+            WHERE id = $1 AND version = $7
+            "#
+        )
+        .bind(utilisateur.id)
+        .bind(&utilisateur.nom)
+        .bind(&utilisateur.email)
+        .bind(&utilisateur.role)
+        .bind(&utilisateur.equipe)
+        .bind(utilisateur.actif)
+        .bind(utilisateur.version)
+        .bind(utilisateur.suppression.as_ref().map(|s| s.le))
+        .bind(utilisateur.suppression.as_ref().and_then(|s| s.par))
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM utilisateurs WHERE id = $1")
+            .bind(id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mot_de_passe(&self, id: Uuid) -> Result<Option<String>> {
+        let hash = sqlx::query_scalar("SELECT mot_de_passe_hash FROM utilisateurs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        Ok(hash.flatten())
+    }
+
+    async fn set_mot_de_passe(&self, id: Uuid, hash: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE utilisateurs SET mot_de_passe_hash = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(hash)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_jeton_calendrier(&self, id: Uuid, empreinte: Option<&str>) -> Result<bool> {
+        let result = sqlx::query("UPDATE utilisateurs SET jeton_calendrier_empreinte = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(empreinte)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_by_jeton_calendrier(&self, empreinte: &str) -> Result<Option<Utilisateur>> {
+        let row = sqlx::query("SELECT * FROM utilisateurs WHERE jeton_calendrier_empreinte = $1 AND deleted_at IS NULL")
+            .bind(empreinte)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(utilisateur_from_row).transpose()
+    }
+}
+
+pub struct PgChecklistRepository {
+    connexion: Connexion,
+}
+
+impl PgChecklistRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgChecklistRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl ChecklistRepository for PgChecklistRepository {
+    async fn list_by_travail(&self, travail_id: Uuid) -> Result<Vec<ChecklistItem>> {
+        let rows = sqlx::query("SELECT * FROM checklist_items WHERE travail_id = $1 AND deleted_at IS NULL ORDER BY ordre, id")
+            .bind(travail_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(checklist_item_from_row).collect()
+    }
+
+    async fn list_by_responsable(&self, utilisateur_id: Uuid) -> Result<Vec<ChecklistItem>> {
+        let rows = sqlx::query("SELECT * FROM checklist_items WHERE responsable = $1 AND deleted_at IS NULL ORDER BY travail_id, ordre")
+            .bind(utilisateur_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(checklist_item_from_row).collect()
+    }
+
+    async fn list_by_travaux(&self, travail_ids: &[Uuid]) -> Result<Vec<ChecklistItem>> {
+        let rows = sqlx::query("SELECT * FROM checklist_items WHERE travail_id = ANY($1) AND deleted_at IS NULL ORDER BY travail_id, ordre")
+            .bind(travail_ids)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(checklist_item_from_row).collect()
+    }
+
+    async fn list_supprimes_by_responsable(&self, utilisateur_id: Uuid) -> Result<Vec<ChecklistItem>> {
+        let rows = sqlx::query("SELECT * FROM checklist_items WHERE responsable = $1 AND deleted_at IS NOT NULL ORDER BY travail_id, ordre")
+            .bind(utilisateur_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(checklist_item_from_row).collect()
+    }
+
+    async fn list_supprimes_avant(&self, date: DateTime<Utc>) -> Result<Vec<ChecklistItem>> {
+        let rows = sqlx::query("SELECT * FROM checklist_items WHERE deleted_at < $1 ORDER BY travail_id, ordre")
+            .bind(date)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(checklist_item_from_row).collect()
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<ChecklistItem>> {
+        let row = sqlx::query("SELECT * FROM checklist_items WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(checklist_item_from_row).transpose()
+    }
+
+    async fn insert(&self, item: &ChecklistItem) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO checklist_items (id, travail_id, description, statut, responsable,
+                                         date_echeance, commentaires, ordre)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(item.id)
+        .bind(item.travail_id)
+        .bind(&item.description)
+        .bind(&item.statut)
+        .bind(item.responsable)
+        .bind(item.date_echeance)
+        .bind(&item.commentaires)
+        .bind(item.ordre)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update(&self, item: &ChecklistItem) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE checklist_items
+            SET description = $2, statut = $3, responsable = $4, date_echeance = $5,
+                commentaires = $6, ordre = $7, deleted_at = $8, deleted_by = $9, updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(item.id)
+        .bind(&item.description)
+        .bind(&item.statut)
+        .bind(item.responsable)
+        .bind(item.date_echeance)
+        .bind(&item.commentaires)
+        .bind(item.ordre)
+        .bind(item.suppression.as_ref().map(|s| s.le))
+        .bind(item.suppression.as_ref().and_then(|s| s.par))
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM checklist_items WHERE id = $1")
+            .bind(id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_by_travail(&self, travail_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM checklist_items WHERE travail_id = $1")
+            .bind(travail_id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reorder(&self, travail_id: Uuid, ids: &[Uuid]) -> Result<()> {
+        let mut connexion = self.connexion.acquerir().await?;
+        let mut tx = connexion.begin().await?;
+        for (position, id) in ids.iter().enumerate() {
+            sqlx::query("UPDATE checklist_items SET ordre = $3, updated_at = NOW() WHERE id = $1 AND travail_id = $2")
+                .bind(id)
+                .bind(travail_id)
+                .bind(position as i32)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+pub struct PgModeleChecklistRepository {
+    connexion: Connexion,
+}
+
+impl PgModeleChecklistRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgModeleChecklistRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl ModeleChecklistRepository for PgModeleChecklistRepository {
+    async fn list(&self) -> Result<Vec<ModeleChecklist>> {
+        let rows = sqlx::query("SELECT * FROM modeles_checklist ORDER BY type_travail, nom")
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(modele_checklist_from_row).collect()
+    }
+
+    async fn list_by_type(&self, type_travail: &TypeTravail) -> Result<Vec<ModeleChecklist>> {
+        let rows = sqlx::query("SELECT * FROM modeles_checklist WHERE type_travail = $1")
+            .bind(type_travail)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(modele_checklist_from_row).collect()
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<ModeleChecklist>> {
+        let row = sqlx::query("SELECT * FROM modeles_checklist WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(modele_checklist_from_row).transpose()
+    }
+
+    async fn insert(&self, modele: &ModeleChecklist) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO modeles_checklist (id, nom, type_travail, application, environnement, etapes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(modele.id)
+        .bind(&modele.nom)
+        .bind(&modele.type_travail)
+        .bind(&modele.application)
+        .bind(&modele.environnement)
+        .bind(Json(&modele.etapes))
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update(&self, modele: &ModeleChecklist) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE modeles_checklist
+            SET nom = $2, type_travail = $3, application = $4, environnement = $5,
+                etapes = $6, updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(modele.id)
+        .bind(&modele.nom)
+        .bind(&modele.type_travail)
+        .bind(&modele.application)
+        .bind(&modele.environnement)
+        .bind(Json(&modele.etapes))
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM modeles_checklist WHERE id = $1")
+            .bind(id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+pub struct PgTransitionRepository {
+    connexion: Connexion,
+}
+
+impl PgTransitionRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgTransitionRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl TransitionRepository for PgTransitionRepository {
+    async fn list_by_entite(&self, entite: &TypeEntite, entite_id: Uuid) -> Result<Vec<TransitionStatut>> {
+        let rows = sqlx::query("SELECT * FROM transitions_statut WHERE entite = $1 AND entite_id = $2 ORDER BY date, id")
+            .bind(entite)
+            .bind(entite_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(transition_from_row).collect()
+    }
+
+    async fn insert(&self, transition: &TransitionStatut) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO transitions_statut (id, entite, entite_id, de, vers, raison, date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(transition.id)
+        .bind(&transition.entite)
+        .bind(transition.entite_id)
+        .bind(transition.de.as_str())
+        .bind(transition.vers.as_str())
+        .bind(&transition.raison)
+        .bind(transition.date)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub struct PgApprobationRepository {
+    connexion: Connexion,
+}
+
+impl PgApprobationRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgApprobationRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl ApprobationRepository for PgApprobationRepository {
+    async fn list_demandes(&self, travail_id: Uuid) -> Result<Vec<DemandeApprobation>> {
+        let rows = sqlx::query("SELECT * FROM demandes_approbation WHERE travail_id = $1 ORDER BY date, id")
+            .bind(travail_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(demande_from_row).collect()
+    }
+
+    async fn list_decisions(&self, demande_id: Uuid) -> Result<Vec<Approbation>> {
+        let rows = sqlx::query("SELECT * FROM approbations WHERE demande_id = $1 ORDER BY date, id")
+            .bind(demande_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(approbation_from_row).collect()
+    }
+
+    async fn insert_demande(&self, demande: &DemandeApprobation) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO demandes_approbation (id, travail_id, demandeur, date, invalidee_le)
+            VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(demande.id)
+        .bind(demande.travail_id)
+        .bind(demande.demandeur)
+        .bind(demande.date)
+        .bind(demande.invalidee_le)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn insert_decision(&self, decision: &Approbation) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO approbations (id, demande_id, approbateur, decision, commentaire, date)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(decision.id)
+        .bind(decision.demande_id)
+        .bind(decision.approbateur)
+        .bind(&decision.decision)
+        .bind(&decision.commentaire)
+        .bind(decision.date)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn invalider(&self, travail_id: Uuid) -> Result<u64> {
+        let result = sqlx::query("UPDATE demandes_approbation SET invalidee_le = NOW() WHERE travail_id = $1 AND invalidee_le IS NULL")
+            .bind(travail_id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_by_travail(&self, travail_id: Uuid) -> Result<()> {
+        let mut connexion = self.connexion.acquerir().await?;
+        let mut tx = connexion.begin().await?;
+        sqlx::query("DELETE FROM approbations WHERE demande_id IN (SELECT id FROM demandes_approbation WHERE travail_id = $1)")
+            .bind(travail_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM demandes_approbation WHERE travail_id = $1")
+            .bind(travail_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+pub struct PgAuditRepository {
+    connexion: Connexion,
+}
+
+impl PgAuditRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgAuditRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl AuditRepository for PgAuditRepository {
+    async fn list(&self, filtre: &FiltreAudit, pagination: &Pagination) -> Result<Page<EvenementAudit>> {
+        let (rows, total) = lister(&self.connexion, "journal_audit", pagination, |requete| {
+            if let Some(entite) = &filtre.entite {
+                requete.push(" AND entite = ").push_bind(entite.clone());
+            }
+            if let Some(entite_id) = filtre.entite_id {
+                requete.push(" AND entite_id = ").push_bind(entite_id);
+            }
+            if let Some(acteur) = filtre.acteur {
+                requete.push(" AND acteur = ").push_bind(acteur);
+            }
+            if let Some(action) = &filtre.action {
+                requete.push(" AND action = ").push_bind(action.clone());
+            }
+            if let Some(du) = filtre.du {
+                requete.push(" AND date >= ").push_bind(du);
+            }
+            if let Some(au) = filtre.au {
+                requete.push(" AND date <= ").push_bind(au);
+            }
+        }).await?;
+        Ok(Page { elements: rows.iter().map(evenement_from_row).collect::<Result<_>>()?, total })
+    }
+
+    async fn insert(&self, evenement: &EvenementAudit) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO journal_audit (id, acteur, date, entite, entite_id, action, changements)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(evenement.id)
+        .bind(evenement.acteur)
+        .bind(evenement.date)
+        .bind(&evenement.entite)
+        .bind(evenement.entite_id)
+        .bind(&evenement.action)
+        .bind(Json(&evenement.changements))
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub struct PgGelRepository {
+    connexion: Connexion,
+}
+
+impl PgGelRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgGelRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl GelRepository for PgGelRepository {
+    async fn list(&self) -> Result<Vec<PeriodeGel>> {
+        let rows = sqlx::query("SELECT * FROM periodes_gel ORDER BY date_debut, id")
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(periode_gel_from_row).collect()
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<PeriodeGel>> {
+        let row = sqlx::query("SELECT * FROM periodes_gel WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut *self.connexion.acquerir().await?)
+            .await?;
+        row.as_ref().map(periode_gel_from_row).transpose()
+    }
+
+    async fn insert(&self, gel: &PeriodeGel) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO periodes_gel (id, date_debut, date_fin, applications, environnements, raison, actif)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(gel.id)
+        .bind(gel.date_debut)
+        .bind(gel.date_fin)
+        .bind(&gel.applications)
+        .bind(&gel.environnements)
+        .bind(&gel.raison)
+        .bind(gel.actif)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update(&self, gel: &PeriodeGel) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE periodes_gel
+            SET date_debut = $2, date_fin = $3, applications = $4, environnements = $5,
+                raison = $6, actif = $7, updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(gel.id)
+        .bind(gel.date_debut)
+        .bind(gel.date_fin)
+        .bind(&gel.applications)
+        .bind(&gel.environnements)
+        .bind(&gel.raison)
+        .bind(gel.actif)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut connexion = self.connexion.acquerir().await?;
+        let mut tx = connexion.begin().await?;
+        sqlx::query("DELETE FROM derogations_gel WHERE periode_gel_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM periodes_gel WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_derogations_by_gel(&self, periode_gel_id: Uuid) -> Result<Vec<DerogationGel>> {
+        let rows = sqlx::query("SELECT * FROM derogations_gel WHERE periode_gel_id = $1 ORDER BY date, id")
+            .bind(periode_gel_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(derogation_gel_from_row).collect()
+    }
+
+    async fn list_derogations_by_travail(&self, travail_id: Uuid) -> Result<Vec<DerogationGel>> {
+        let rows = sqlx::query("SELECT * FROM derogations_gel WHERE travail_id = $1 ORDER BY date, id")
+            .bind(travail_id)
+            .fetch_all(&mut *self.connexion.acquerir().await?)
+            .await?;
+        rows.iter().map(derogation_gel_from_row).collect()
+    }
+
+    async fn insert_derogation(&self, derogation: &DerogationGel) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO derogations_gel (id, periode_gel_id, travail_id, auteur, justification, date)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(derogation.id)
+        .bind(derogation.periode_gel_id)
+        .bind(derogation.travail_id)
+        .bind(derogation.auteur)
+        .bind(&derogation.justification)
+        .bind(derogation.date)
+        .execute(&mut *self.connexion.acquerir().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_derogations_by_travail(&self, travail_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM derogations_gel WHERE travail_id = $1")
+            .bind(travail_id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+        Ok(())
+    }
+}
+
+pub struct PgDependanceRepository {
+    connexion: Connexion,
+}
+
+impl PgDependanceRepository {
+    pub fn new(connexion: Connexion) -> Self {
+        PgDependanceRepository { connexion }
+    }
+}
+
+#[async_trait]
+impl DependanceRepository for PgDependanceRepository {
+    async fn list_by_travaux(&self, travail_ids: &[Uuid]) -> Result<Vec<Dependance>> {
+        let rows = sqlx::query(
+            "SELECT * FROM dependances_travaux WHERE travail_id = ANY($1) OR depend_de = ANY($1) ORDER BY travail_id, depend_de"
+        )
+        .bind(travail_ids)
+        .fetch_all(&mut *self.connexion.acquerir().await?)
+        .await?;
+        rows.iter().map(dependance_from_row).collect()
+    }
+
+    async fn insert(&self, dependance: &Dependance) -> Result<()> {
+        sqlx::query("INSERT INTO dependances_travaux (id, travail_id, depend_de) VALUES ($1, $2, $3)")
+            .bind(dependance.id)
+            .bind(dependance.travail_id)
+            .bind(dependance.depend_de)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM dependances_travaux WHERE id = $1")
+            .bind(id)
+            .execute(&mut *self.connexion.acquerir().await?)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+// Page de résultats et nombre total ; `filtrer` ajoute les conditions " AND ..." aux deux requêtes
+async fn lister(
+    connexion: &Connexion,
+    table: &str,
+    pagination: &Pagination,
+    filtrer: impl Fn(&mut QueryBuilder<'_, Postgres>),
+) -> Result<(Vec<PgRow>, i64)> {
+    let mut connexion = connexion.acquerir().await?;
+    let mut compte = QueryBuilder::new(format!("SELECT COUNT(*) FROM {table} WHERE TRUE"));
+    filtrer(&mut compte);
+    let total: i64 = compte.build_query_scalar().fetch_one(&mut *connexion).await?;
+
+    let mut requete = QueryBuilder::new(format!("SELECT * FROM {table} WHERE TRUE"));
+    filtrer(&mut requete);
+    requete.push(order_by(&pagination.tri));
+    if let Some(limit) = pagination.limit {
+        requete.push(" LIMIT ").push_bind(limit);
+    }
+    requete.push(" OFFSET ").push_bind(pagination.offset);
+    let rows = requete.build().fetch_all(&mut *connexion).await?;
+
+    Ok((rows, total))
+}
+
+// Condition sur deleted_at correspondant à `supprimes`
+fn filtrer_supprimes(requete: &mut QueryBuilder<'_, Postgres>, supprimes: Supprimes) {
+    match supprimes {
+        Supprimes::Exclus => {
+            requete.push(" AND deleted_at IS NULL");
+        }
+        Supprimes::Inclus => {}
+        Supprimes::Avant(date) => {
+            requete.push(" AND deleted_at < ").push_bind(date);
+        }
+    }
+}
+
+// Conversion des lignes SQL vers les modèles
+
+fn suppression_from_row(row: &PgRow) -> Result<Option<Suppression>> {
+    Ok(match row.try_get("deleted_at")? {
+        Some(le) => Some(Suppression { le, par: row.try_get("deleted_by")? }),
+        None => None,
+    })
+}
+
+fn projet_from_row(row: &PgRow) -> Result<Projet> {
+    Ok(Projet {
+        id: row.try_get("id")?,
+        code: row.try_get("code")?,
+        nom: row.try_get("nom")?,
+        description: row.try_get::<Option<String>, _>("description")?.unwrap_or_default(),
+        date_debut: row.try_get("date_debut")?,
+        date_fin_prevue: row.try_get("date_fin_prevue")?,
+        statut: row.try_get("statut")?,
+        priorite: row.try_get("priorite")?,
+        version: row.try_get("version")?,
+        suppression: suppression_from_row(row)?,
+    })
+}
+
+fn travail_from_row(row: &PgRow) -> Result<Travail> {
+    Ok(Travail {
+        id: row.try_get("id")?,
+        projet_id: row.try_get("projet_id")?,
+        type_travail: row.try_get("type_travail")?,
+        application: row.try_get("application")?,
+        environnement: row.try_get("environnement")?,
+        description: row.try_get("description")?,
+        date_debut: row.try_get("date_debut")?,
+        date_fin_prevue: row.try_get("date_fin_prevue")?,
+        statut: row.try_get("statut")?,
+        responsable: row.try_get("responsable")?,
+        equipe: row.try_get::<Option<Vec<Uuid>>, _>("equipe")?.unwrap_or_default(),
+        promotion_de: row.try_get("promotion_de")?,
+        version: row.try_get("version")?,
+        suppression: suppression_from_row(row)?,
+    })
+}
+
+fn utilisateur_from_row(row: &PgRow) -> Result<Utilisateur> {
+    Ok(Utilisateur {
+        id: row.try_get("id")?,
+        nom: row.try_get("nom")?,
+        email: row.try_get("email")?,
+        role: row.try_get("role")?,
+        equipe: row.try_get("equipe")?,
+        actif: row.try_get::<Option<bool>, _>("actif")?.unwrap_or(true),
+        version: row.try_get("version")?,
+        suppression: suppression_from_row(row)?,
+    })
+}
+
+fn checklist_item_from_row(row: &PgRow) -> Result<ChecklistItem> {
+    Ok(ChecklistItem {
+        id: row.try_get("id")?,
+        travail_id: row.try_get("travail_id")?,
+        description: row.try_get("description")?,
+        statut: row.try_get("statut")?,
+        responsable: row.try_get("responsable")?,
+        date_echeance: row.try_get("date_echeance")?,
+        commentaires: row.try_get("commentaires")?,
+        ordre: row.try_get("ordre")?,
+        suppression: suppression_from_row(row)?,
+    })
+}
+
+fn modele_checklist_from_row(row: &PgRow) -> Result<ModeleChecklist> {
+    Ok(ModeleChecklist {
+        id: row.try_get("id")?,
+        nom: row.try_get("nom")?,
+        type_travail: row.try_get("type_travail")?,
+        application: row.try_get("application")?,
+        environnement: row.try_get("environnement")?,
+        etapes: row.try_get::<Json<Vec<EtapeModele>>, _>("etapes")?.0,
+    })
+}
+
+fn transition_from_row(row: &PgRow) -> Result<TransitionStatut> {
+    let entite: TypeEntite = row.try_get("entite")?;
+    Ok(TransitionStatut {
+        id: row.try_get("id")?,
+        entite: entite.clone(),
+        entite_id: row.try_get("entite_id")?,
+        de: StatutEntite::depuis_texte(&entite, row.try_get("de")?).map_err(anyhow::Error::msg)?,
+        vers: StatutEntite::depuis_texte(&entite, row.try_get("vers")?).map_err(anyhow::Error::msg)?,
+        raison: row.try_get("raison")?,
+        date: row.try_get("date")?,
+    })
+}
+
+fn demande_from_row(row: &PgRow) -> Result<DemandeApprobation> {
+    Ok(DemandeApprobation {
+        id: row.try_get("id")?,
+        travail_id: row.try_get("travail_id")?,
+        demandeur: row.try_get("demandeur")?,
+        date: row.try_get("date")?,
+        invalidee_le: row.try_get("invalidee_le")?,
+    })
+}
+
+fn approbation_from_row(row: &PgRow) -> Result<Approbation> {
+    Ok(Approbation {
+        id: row.try_get("id")?,
+        demande_id: row.try_get("demande_id")?,
+        approbateur: row.try_get("approbateur")?,
+        decision: row.try_get("decision")?,
+        commentaire: row.try_get("commentaire")?,
+        date: row.try_get("date")?,
+    })
+}
+
+fn evenement_from_row(row: &PgRow) -> Result<EvenementAudit> {
+    Ok(EvenementAudit {
+        id: row.try_get("id")?,
+        acteur: row.try_get("acteur")?,
+        date: row.try_get("date")?,
+        entite: row.try_get("entite")?,
+        entite_id: row.try_get("entite_id")?,
+        action: row.try_get("action")?,
+        changements: row.try_get::<Json<Vec<ChangementChamp>>, _>("changements")?.0,
+    })
+}
+
+fn periode_gel_from_row(row: &PgRow) -> Result<PeriodeGel> {
+    Ok(PeriodeGel {
+        id: row.try_get("id")?,
+        date_debut: row.try_get("date_debut")?,
+        date_fin: row.try_get("date_fin")?,
+        applications: row.try_get("applications")?,
+        environnements: row.try_get("environnements")?,
+        raison: row.try_get("raison")?,
+        actif: row.try_get("actif")?,
+    })
+}
+
+fn derogation_gel_from_row(row: &PgRow) -> Result<DerogationGel> {
+    Ok(DerogationGel {
+        id: row.try_get("id")?,
+        periode_gel_id: row.try_get("periode_gel_id")?,
+        travail_id: row.try_get("travail_id")?,
+        auteur: row.try_get("auteur")?,
+        justification: row.try_get("justification")?,
+        date: row.try_get("date")?,
+    })
+}
+
+fn dependance_from_row(row: &PgRow) -> Result<Dependance> {
+    Ok(Dependance {
+        id: row.try_get("id")?,
+        travail_id: row.try_get("travail_id")?,
+        depend_de: row.try_get("depend_de")?,
+    })
+}
@@ -0,0 +1,222 @@
+// Avancement et santé d'un projet, calculés à partir de ses travaux et de leurs checklists.
+// Les travaux annulés (et leurs items) sont exclus des décomptes.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::{ChecklistItem, Projet, StatutChecklist, StatutProjet, StatutTravail, Travail};
+
+// Écart toléré entre le temps écoulé et l'avancement avant de considérer le projet à risque
+const ECART_AVANCEMENT_MAX: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub enum IndicateurSante {
+    #[serde(rename = "dans_les_temps", alias = "DansLesTemps")]
+    DansLesTemps,
+    #[serde(rename = "à_risque", alias = "ARisque")]
+    ARisque,
+    #[serde(rename = "en_retard", alias = "EnRetard")]
+    EnRetard,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SanteProjet {
+    pub travaux_total: usize,
+    pub travaux_termines: usize,
+    // None si le projet n'a aucun travail
+    pub pourcentage_termines: Option<f64>,
+    pub items_total: usize,
+    pub items_termines: usize,
+    // Entre 0 et 1 ; None si aucun item de checklist
+    pub ratio_checklist: Option<f64>,
+    pub items_bloques: usize,
+    // Travaux non terminés dont la date de fin prévue est dépassée
+    pub travaux_en_retard: usize,
+    pub indicateur: IndicateurSante,
+}
+
+fn ratio(termines: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| termines as f64 / total as f64)
+}
+
+// Part de la période du projet déjà écoulée, entre 0 et 1
+fn temps_ecoule(projet: &Projet, maintenant: DateTime<Utc>) -> f64 {
+    let duree = (projet.date_fin_prevue - projet.date_debut).num_seconds();
+    if duree <= 0 {
+        return 1.0;
+    }
+    ((maintenant - projet.date_debut).num_seconds() as f64 / duree as f64).clamp(0.0, 1.0)
+}
+
+// En retard : un travail ou le projet lui-même a dépassé sa date de fin prévue.
+// À risque : items bloqués, travail planifié au-delà de la fin du projet, ou avancement
+// (checklist, à défaut travaux) nettement inférieur au temps écoulé.
+pub fn calculer(projet: &Projet, travaux: &[Travail], items: &[ChecklistItem], maintenant: DateTime<Utc>) -> SanteProjet {
+    let travaux: Vec<&Travail> = travaux.iter().filter(|t| t.statut != StatutTravail::Annule).collect();
+    let items: Vec<&ChecklistItem> = items.iter()
+        .filter(|i| travaux.iter().any(|t| t.id == i.travail_id))
+        .collect();
+
+    let travaux_termines = travaux.iter().filter(|t| t.statut == StatutTravail::Termine).count();
+    let items_termines = items.iter().filter(|i| i.statut == StatutChecklist::Termine).count();
+    let items_bloques = items.iter().filter(|i| i.statut == StatutChecklist::Bloque).count();
+    let travaux_en_retard = travaux.iter()
+        .filter(|t| t.statut.est_actif() && t.date_fin_prevue < maintenant)
+        .count();
+
+    let avancement_travaux = ratio(travaux_termines, travaux.len());
+    let ratio_checklist = ratio(items_termines, items.len());
+
+    let indicateur = if projet.statut == StatutProjet::Termine {
+        IndicateurSante::DansLesTemps
+    } else if travaux_en_retard > 0 || projet.date_fin_prevue < maintenant {
+        IndicateurSante::EnRetard
+    } else {
+        let avancement = ratio_checklist.or(avancement_travaux).unwrap_or(0.0);
+        let depasse_projet = travaux.iter().any(|t| t.statut.est_actif() && t.date_fin_prevue > projet.date_fin_prevue);
+        if items_bloques > 0 || depasse_projet || temps_ecoule(projet, maintenant) - avancement > ECART_AVANCEMENT_MAX {
+            IndicateurSante::ARisque
+        } else {
+            IndicateurSante::DansLesTemps
+        }
+    };
+
+    SanteProjet {
+        travaux_total: travaux.len(),
+        travaux_termines,
+        pourcentage_termines: avancement_travaux.map(|r| (r * 1000.0).round() / 10.0),
+        items_total: items.len(),
+        items_termines,
+        ratio_checklist: ratio_checklist.map(|r| (r * 1000.0).round() / 1000.0),
+        items_bloques,
+        travaux_en_retard,
+        indicateur,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::test_support;
+
+    fn jour(n: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap() + Duration::days(n)
+    }
+
+    // Projet du jour 0 au jour 10, évalué au jour 5 : la moitié du temps est écoulée
+    const MAINTENANT: i64 = 5;
+
+    fn projet() -> Projet {
+        Projet { date_debut: jour(0), date_fin_prevue: jour(10), ..test_support::projet() }
+    }
+
+    fn travail(projet: &Projet, fin: i64, statut: StatutTravail) -> Travail {
+        Travail {
+            projet_id: projet.id,
+            date_debut: jour(0),
+            date_fin_prevue: jour(fin),
+            statut,
+            ..test_support::travail()
+        }
+    }
+
+    // `termines` items terminés sur `total`, plus `bloques` items bloqués
+    fn items(travail: &Travail, termines: usize, total: usize, bloques: usize) -> Vec<ChecklistItem> {
+        (0..total + bloques)
+            .map(|i| ChecklistItem {
+                id: Uuid::new_v4(),
+                travail_id: travail.id,
+                description: String::new(),
+                statut: if i < termines {
+                    StatutChecklist::Termine
+                } else if i >= total {
+                    StatutChecklist::Bloque
+                } else {
+                    StatutChecklist::NonDemarre
+                },
+                responsable: travail.responsable,
+                date_echeance: None,
+                commentaires: None,
+                ordre: i as i32,
+                suppression: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dans_les_temps() {
+        let projet = projet();
+        let travaux = [travail(&projet, 3, StatutTravail::Termine), travail(&projet, 8, StatutTravail::EnCours)];
+        let sante = calculer(&projet, &travaux, &items(&travaux[1], 1, 2, 0), jour(MAINTENANT));
+        assert_eq!(sante.indicateur, IndicateurSante::DansLesTemps);
+        assert_eq!((sante.travaux_total, sante.travaux_termines), (2, 1));
+        assert_eq!(sante.pourcentage_termines, Some(50.0));
+        assert_eq!(sante.ratio_checklist, Some(0.5));
+    }
+
+    #[test]
+    fn en_retard() {
+        let projet = projet();
+        let travaux = [travail(&projet, 4, StatutTravail::EnCours), travail(&projet, 8, StatutTravail::Planifie)];
+        let sante = calculer(&projet, &travaux, &[], jour(MAINTENANT));
+        assert_eq!(sante.indicateur, IndicateurSante::EnRetard);
+        assert_eq!(sante.travaux_en_retard, 1);
+
+        // Le projet lui-même a dépassé sa fin prévue
+        let sante = calculer(&projet, &[], &[], jour(11));
+        assert_eq!(sante.indicateur, IndicateurSante::EnRetard);
+    }
+
+    #[test]
+    fn a_risque_items_bloques() {
+        let projet = projet();
+        let travaux = [travail(&projet, 8, StatutTravail::EnCours)];
+        let sante = calculer(&projet, &travaux, &items(&travaux[0], 4, 4, 1), jour(MAINTENANT));
+        assert_eq!(sante.items_bloques, 1);
+        assert_eq!(sante.indicateur, IndicateurSante::ARisque);
+    }
+
+    #[test]
+    fn a_risque_ecart_d_avancement() {
+        let projet = projet();
+        let travaux = [travail(&projet, 8, StatutTravail::EnCours)];
+        // 50 % du temps écoulé : 25 % d'avancement est toléré, 20 % ne l'est plus
+        let sante = calculer(&projet, &travaux, &items(&travaux[0], 1, 4, 0), jour(MAINTENANT));
+        assert_eq!(sante.indicateur, IndicateurSante::DansLesTemps);
+        let sante = calculer(&projet, &travaux, &items(&travaux[0], 1, 5, 0), jour(MAINTENANT));
+        assert_eq!(sante.indicateur, IndicateurSante::ARisque);
+
+        // Sans checklist, l'avancement est celui des travaux
+        let sante = calculer(&projet, &travaux, &[], jour(MAINTENANT));
+        assert_eq!(sante.ratio_checklist, None);
+        assert_eq!(sante.indicateur, IndicateurSante::ARisque);
+    }
+
+    #[test]
+    fn projet_termine() {
+        let projet = Projet { statut: StatutProjet::Termine, ..projet() };
+        let travaux = [travail(&projet, 4, StatutTravail::EnCours)];
+        let sante = calculer(&projet, &travaux, &items(&travaux[0], 0, 2, 3), jour(20));
+        assert_eq!(sante.indicateur, IndicateurSante::DansLesTemps);
+    }
+
+    #[test]
+    fn travaux_annules_exclus() {
+        let projet = projet();
+        let annule = travail(&projet, 2, StatutTravail::Annule);
+        let actif = travail(&projet, 8, StatutTravail::EnCours);
+        let mut checklist = items(&actif, 3, 4, 0);
+        checklist.extend(items(&annule, 0, 6, 2));
+
+        let sante = calculer(&projet, &[annule, actif], &checklist, jour(MAINTENANT));
+        assert_eq!((sante.travaux_total, sante.travaux_termines), (1, 0));
+        assert_eq!((sante.items_total, sante.items_termines, sante.items_bloques), (4, 3, 0));
+        assert_eq!(sante.travaux_en_retard, 0);
+        assert_eq!(sante.indicateur, IndicateurSante::DansLesTemps);
+    }
+}
@@ -1,3 +1,12 @@
+// Version du contrat JSON de l'API (en-tête X-Contrat-Version)
+export const VERSION_CONTRAT = '2';
+
+// Présente uniquement sur les éléments supprimés, listés avec ?inclure_supprimes=true
+export interface Suppression {
+  le: string;
+  par?: string;
+}
+
 export interface Projet {
   id: string;
   code: string;
@@ -7,6 +16,9 @@ export interface Projet {
   dateFinPrevue: string;
   statut: 'planifié' | 'en_cours' | 'terminé' | 'suspendu';
   priorite: 'faible' | 'moyenne' | 'élevée' | 'critique';
+  // Incrémentée à chaque modification ; renvoyée dans l'en-tête ETag, à passer en If-Match
+  version: number;
+  suppression?: Suppression;
 }
 
 export interface Travail {
@@ -21,6 +33,9 @@ export interface Travail {
   statut: 'planifié' | 'en_cours' | 'terminé' | 'suspendu' | 'annulé';
   responsable: string;
   equipe: string[];
+  promotionDe?: string;
+  version: number;
+  suppression?: Suppression;
 }
 
 export interface ChecklistItem {
@@ -31,6 +46,7 @@ export interface ChecklistItem {
   responsable: string;
   dateEcheance?: string;
   commentaires?: string;
+  ordre: number;
 }
 
 export interface Utilisateur {
@@ -40,4 +56,6 @@ export interface Utilisateur {
   role: 'conseiller' | 'manager' | 'specialiste' | 'admin';
   equipe: string;
   actif: boolean;
+  version: number;
+  suppression?: Suppression;
 }
@@ -0,0 +1,144 @@
+// Validation des requêtes : accumule les erreurs par champ et produit une erreur 422
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::error::ApiError;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErreurChamp {
+    pub field: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Validation {
+    erreurs: Vec<ErreurChamp>,
+}
+
+impl Validation {
+    pub fn new() -> Self {
+        Validation::default()
+    }
+
+    pub fn ajouter(&mut self, field: &str, code: &'static str, message: impl Into<String>) {
+        self.erreurs.push(ErreurChamp {
+            field: field.to_string(),
+            code,
+            message: message.into(),
+        });
+    }
+
+    // Champ texte obligatoire, borné à `max` caractères (taille des colonnes VARCHAR)
+    pub fn texte(&mut self, field: &str, valeur: &str, max: usize) {
+        if valeur.trim().is_empty() {
+            self.ajouter(field, "requis", "Ce champ est obligatoire");
+        } else if valeur.chars().count() > max {
+            self.ajouter(field, "trop_long", format!("{} caractères maximum", max));
+        }
+    }
+
+    pub fn email(&mut self, field: &str, valeur: &str) {
+        let valide = match valeur.split_once('@') {
+            Some((local, domaine)) => {
+                !local.is_empty()
+                    && domaine.contains('.')
+                    && !domaine.starts_with('.')
+                    && !domaine.ends_with('.')
+                    && !domaine.contains('@')
+                    && !valeur.chars().any(char::is_whitespace)
+            }
+            None => false,
+        };
+        if !valide {
+            self.ajouter(field, "email_invalide", "Adresse email invalide");
+        }
+    }
+
+    pub fn mot_de_passe(&mut self, field: &str, valeur: &str) {
+        if valeur.chars().count() < 8 {
+            self.ajouter(field, "trop_court", "8 caractères minimum");
+        }
+    }
+
+    pub fn periode(&mut self, debut: DateTime<Utc>, fin: DateTime<Utc>) {
+        if fin < debut {
+            self.ajouter("dateFinPrevue", "avant_date_debut", "La date de fin prévue précède la date de début");
+        }
+    }
+
+    pub fn est_valide(&self) -> bool {
+        self.erreurs.is_empty()
+    }
+
+    // Erreur 422 pour un champ isolé
+    pub fn champ_invalide(field: &str, code: &'static str, message: impl Into<String>) -> ApiError {
+        let mut validation = Validation::new();
+        validation.ajouter(field, code, message);
+        ApiError::Validation(validation.erreurs)
+    }
+
+    // Ok si aucune erreur, sinon l'erreur 422 listant toutes les erreurs
+    pub fn verifier(self) -> Result<(), ApiError> {
+        if self.est_valide() {
+            Ok(())
+        } else {
+            Err(ApiError::Validation(self.erreurs))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn codes(validation: &Validation) -> Vec<(&str, &str)> {
+        validation.erreurs.iter().map(|e| (e.field.as_str(), e.code)).collect()
+    }
+
+    #[test]
+    fn erreurs_accumulees_dans_l_ordre() {
+        let mut validation = Validation::new();
+        validation.texte("nom", "  ", 10);
+        validation.texte("code", "ééééééééééé", 10);
+        validation.texte("equipe", "éééééééééé", 10);
+        validation.email("email", "jean@localhost");
+        validation.mot_de_passe("motDePasse", "court");
+        let debut = Utc.with_ymd_and_hms(2030, 3, 2, 0, 0, 0).unwrap();
+        validation.periode(debut, debut - chrono::Duration::seconds(1));
+        validation.periode(debut, debut);
+
+        assert!(!validation.est_valide());
+        assert_eq!(
+            codes(&validation),
+            [("nom", "requis"), ("code", "trop_long"), ("email", "email_invalide"), ("motDePasse", "trop_court"), ("dateFinPrevue", "avant_date_debut")]
+        );
+        match validation.verifier() {
+            Err(ApiError::Validation(erreurs)) => assert_eq!(erreurs.len(), 5),
+            autre => panic!("422 attendu : {:?}", autre),
+        }
+    }
+
+    #[test]
+    fn requete_valide() {
+        let mut validation = Validation::new();
+        validation.texte("nom", "Jean", 10);
+        validation.email("email", "jean.dupont@entreprise.com");
+        validation.mot_de_passe("motDePasse", "huit car");
+        assert!(validation.est_valide());
+        assert!(validation.verifier().is_ok());
+    }
+
+    #[test]
+    fn emails_refuses() {
+        for email in ["", "jean", "@entreprise.com", "jean@entreprise", "jean@.com", "jean@entreprise.", "jean@a@b.com", "jean dupont@entreprise.com"] {
+            let mut validation = Validation::new();
+            validation.email("email", email);
+            assert_eq!(codes(&validation), [("email", "email_invalide")], "{email}");
+        }
+    }
+}
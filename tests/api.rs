@@ -0,0 +1,220 @@
+// Tests d'intégration : application complète (configurer) sur le stockage en mémoire, appelée
+// uniquement à travers l'API HTTP publique
+
+use actix_web::dev::ServiceResponse;
+use actix_web::http::StatusCode;
+use actix_web::test::{self, TestRequest};
+use actix_web::{web, App};
+use chrono::{DateTime, Duration, SecondsFormat, TimeZone, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use travaux_backend::auth;
+use travaux_backend::config::{Config, PolitiqueConflits, PolitiqueSuppression};
+use travaux_backend::models::{Application, Environnement, Role, StatutTravail, Travail, TypeTravail, Utilisateur, VERSION_INITIALE};
+use travaux_backend::repositories::Repositories;
+
+struct Api {
+    depots: Repositories,
+    config: Config,
+    responsable: Utilisateur,
+    authorization: String,
+}
+
+impl Api {
+    // Stockage vide et un administrateur connecté
+    async fn demarrer() -> Self {
+        let depots = Repositories::memoire();
+        let config = Config {
+            suppression_projets: PolitiqueSuppression::Rejeter,
+            suppression_utilisateurs: PolitiqueSuppression::Rejeter,
+            conflits_planning: PolitiqueConflits::Avertir,
+            cle_jetons: auth::cle_aleatoire().unwrap(),
+            duree_jetons: Duration::hours(1),
+            approbations_production: 1,
+            retention_suppressions: Duration::days(30),
+        };
+        let responsable = Utilisateur {
+            id: Uuid::new_v4(),
+            nom: "Admin Intégration".to_string(),
+            email: "admin@integration.test".to_string(),
+            role: Role::Admin,
+            equipe: "Administration".to_string(),
+            actif: true,
+            version: VERSION_INITIALE,
+            suppression: None,
+        };
+        depots.utilisateurs.insert(&responsable).await.unwrap();
+        let jeton = auth::emettre_jeton(&config.cle_jetons, responsable.id, Utc::now() + config.duree_jetons);
+        Api { depots, config, responsable, authorization: format!("Bearer {}", jeton) }
+    }
+
+    async fn appeler(&self, requete: TestRequest) -> ServiceResponse {
+        let config = web::Data::new(self.config.clone());
+        let app = test::init_service(App::new().configure(|cfg| travaux_backend::configurer(cfg, config, &self.depots))).await;
+        test::call_service(&app, requete.insert_header(("Authorization", self.authorization.clone())).to_request()).await
+    }
+
+    async fn creer_projet(&self, code: &str, statut: &str, priorite: &str, debut: DateTime<Utc>) -> Value {
+        let reponse = self
+            .appeler(TestRequest::post().uri("/api/projets").set_json(json!({
+                "code": code,
+                "nom": format!("Projet {code}"),
+                "description": "Projet de test",
+                "dateDebut": debut,
+                "dateFinPrevue": debut + Duration::days(30),
+                "statut": statut,
+                "priorite": priorite,
+            })))
+            .await;
+        assert_eq!(reponse.status(), StatusCode::CREATED);
+        test::read_body_json(reponse).await
+    }
+
+    // Codes des projets de la page, et total annoncé dans X-Total-Count
+    async fn lister_projets(&self, query: &str) -> (Vec<String>, i64) {
+        let reponse = self.appeler(TestRequest::get().uri(&format!("/api/projets?{query}"))).await;
+        assert_eq!(reponse.status(), StatusCode::OK, "GET /api/projets?{query}");
+        let total = reponse.headers().get("X-Total-Count").unwrap().to_str().unwrap().parse().unwrap();
+        let corps: Vec<Value> = test::read_body_json(reponse).await;
+        (corps.iter().map(|p| p["code"].as_str().unwrap().to_string()).collect(), total)
+    }
+}
+
+fn jour(jour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 3, jour, 8, 0, 0).unwrap()
+}
+
+fn rfc3339(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[actix_web::test]
+async fn cycle_de_vie_d_un_projet() {
+    let api = Api::demarrer().await;
+    let projet = api.creer_projet("INT-1", "planifié", "moyenne", jour(1)).await;
+    let uri = format!("/api/projets/{}", projet["id"].as_str().unwrap());
+    assert_eq!(projet["version"], VERSION_INITIALE);
+
+    let reponse = api.appeler(TestRequest::get().uri(&uri)).await;
+    assert_eq!(reponse.status(), StatusCode::OK);
+    let lu: Value = test::read_body_json(reponse).await;
+    assert_eq!((lu["code"].as_str(), lu["priorite"].as_str()), (Some("INT-1"), Some("moyenne")));
+
+    let reponse = api.appeler(TestRequest::patch().uri(&uri).set_json(json!({"nom": "Renommé", "priorite": "critique"}))).await;
+    assert_eq!(reponse.status(), StatusCode::OK);
+    let modifie: Value = test::read_body_json(reponse).await;
+    assert_eq!((modifie["nom"].as_str(), modifie["priorite"].as_str()), (Some("Renommé"), Some("critique")));
+    assert_eq!(modifie["description"], "Projet de test");
+    assert_eq!(modifie["version"], VERSION_INITIALE + 1);
+
+    // Un code déjà pris est refusé tant que le projet qui le porte existe
+    let reponse = api
+        .appeler(TestRequest::post().uri("/api/projets").set_json(json!({
+            "code": "INT-1", "nom": "Doublon", "description": "", "dateDebut": jour(1), "dateFinPrevue": jour(2),
+            "statut": "planifié", "priorite": "faible",
+        })))
+        .await;
+    assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let reponse = api.appeler(TestRequest::delete().uri(&uri)).await;
+    assert_eq!(reponse.status(), StatusCode::NO_CONTENT);
+    let reponse = api.appeler(TestRequest::get().uri(&uri)).await;
+    assert_eq!(reponse.status(), StatusCode::NOT_FOUND);
+    assert_eq!(api.lister_projets("").await, (Vec::new(), 0));
+    let (codes, _) = api.lister_projets("inclure_supprimes=true").await;
+    assert_eq!(codes, ["INT-1"]);
+}
+
+#[actix_web::test]
+async fn tri_et_pagination_des_projets() {
+    let api = Api::demarrer().await;
+    api.creer_projet("C", "planifié", "critique", jour(3)).await;
+    api.creer_projet("A", "en_cours", "faible", jour(1)).await;
+    api.creer_projet("D", "en_cours", "élevée", jour(4)).await;
+    api.creer_projet("B", "planifié", "moyenne", jour(2)).await;
+
+    // Clé du JSON (camelCase), ancienne graphie snake_case acceptée comme alias
+    assert_eq!(api.lister_projets("sort=dateDebut").await, (vec!["A".into(), "B".into(), "C".into(), "D".into()], 4));
+    assert_eq!(api.lister_projets("sort=date_debut").await.0, ["A", "B", "C", "D"]);
+    assert_eq!(api.lister_projets("sort=-dateFinPrevue").await.0, ["D", "C", "B", "A"]);
+    // La priorité se trie dans l'ordre métier, pas alphabétique
+    assert_eq!(api.lister_projets("sort=priorite").await.0, ["A", "B", "D", "C"]);
+
+    // Le total compte tous les éléments filtrés, pas seulement ceux de la page
+    assert_eq!(api.lister_projets("sort=code&offset=1&limit=2").await, (vec!["B".into(), "C".into()], 4));
+    assert_eq!(api.lister_projets("sort=code&offset=10").await, (Vec::new(), 4));
+    assert_eq!(api.lister_projets("statut=en_cours&sort=-code").await, (vec!["D".into(), "A".into()], 2));
+    assert_eq!(api.lister_projets("statut=planifi%C3%A9&priorite=critique").await, (vec!["C".into()], 1));
+
+    for (query, champ) in [("sort=inconnu", "sort"), ("sort=DateDebut", "sort"), ("offset=-1", "offset"), ("limit=0", "limit"), ("limit=501", "limit")] {
+        let reponse = api.appeler(TestRequest::get().uri(&format!("/api/projets?{query}"))).await;
+        assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY, "{query}");
+        let corps: Value = test::read_body_json(reponse).await;
+        assert_eq!(corps["erreurs"][0]["field"], champ, "{query}");
+    }
+    let reponse = api.appeler(TestRequest::get().uri("/api/projets?sort=inconnu")).await;
+    let corps: Value = test::read_body_json(reponse).await;
+    assert!(corps["erreurs"][0]["message"].as_str().unwrap().contains("dateDebut, dateFinPrevue"));
+
+    let reponse = api.appeler(TestRequest::get().uri("/api/projets?statut=inconnu")).await;
+    assert_eq!(reponse.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn filtres_des_travaux() {
+    let api = Api::demarrer().await;
+    let projet = api.creer_projet("INT-T", "en_cours", "moyenne", jour(1)).await;
+    let projet_id: Uuid = projet["id"].as_str().unwrap().parse().unwrap();
+    let autre = api.creer_projet("INT-U", "en_cours", "moyenne", jour(1)).await;
+    let autre_id: Uuid = autre["id"].as_str().unwrap().parse().unwrap();
+
+    // Trois travaux consécutifs de deux jours, puis un travail d'un autre projet
+    let mut travaux = Vec::new();
+    for (i, (projet_id, environnement)) in [(projet_id, Environnement::Test), (projet_id, Environnement::Formation), (projet_id, Environnement::Test), (autre_id, Environnement::Test)]
+        .into_iter()
+        .enumerate()
+    {
+        let travail = Travail {
+            id: Uuid::new_v4(),
+            projet_id,
+            type_travail: TypeTravail::CloneBd,
+            application: Application::EspressoGfr,
+            environnement,
+            description: format!("Travail {i}"),
+            date_debut: jour(1 + 2 * i as u32),
+            date_fin_prevue: jour(2 + 2 * i as u32),
+            statut: StatutTravail::Planifie,
+            responsable: api.responsable.id,
+            equipe: Vec::new(),
+            promotion_de: None,
+            version: VERSION_INITIALE,
+            suppression: None,
+        };
+        api.depots.travaux.insert(&travail).await.unwrap();
+        travaux.push(travail);
+    }
+    let descriptions = |corps: Vec<Value>| -> Vec<String> { corps.iter().map(|t| t["description"].as_str().unwrap().to_string()).collect() };
+
+    let uri = format!("/api/travaux?projet_id={projet_id}&sort=-dateDebut");
+    let corps = test::read_body_json(api.appeler(TestRequest::get().uri(&uri)).await).await;
+    assert_eq!(descriptions(corps), ["Travail 2", "Travail 1", "Travail 0"]);
+
+    // Chevauchement de période : [du, au] touche les travaux 1 et 2, bornes incluses
+    let uri = format!("/api/travaux?projet_id={projet_id}&du={}&au={}&sort=dateDebut", rfc3339(jour(4)), rfc3339(jour(5)));
+    let corps = test::read_body_json(api.appeler(TestRequest::get().uri(&uri)).await).await;
+    assert_eq!(descriptions(corps), ["Travail 1", "Travail 2"]);
+
+    let reponse = api.appeler(TestRequest::get().uri("/api/travaux?environnement=test&sort=description&limit=2")).await;
+    assert_eq!(reponse.headers().get("X-Total-Count").unwrap(), "3");
+    assert_eq!(descriptions(test::read_body_json(reponse).await), ["Travail 0", "Travail 2"]);
+
+    let uri = format!("/api/travaux/{}", travaux[0].id);
+    assert_eq!(api.appeler(TestRequest::delete().uri(&uri)).await.status(), StatusCode::NO_CONTENT);
+    let uri = format!("/api/travaux?projet_id={projet_id}&sort=dateDebut");
+    let corps = test::read_body_json(api.appeler(TestRequest::get().uri(&uri)).await).await;
+    assert_eq!(descriptions(corps), ["Travail 1", "Travail 2"]);
+
+    let reponse = api.appeler(TestRequest::get().uri("/api/travaux?sort=priorite")).await;
+    assert_eq!(reponse.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}